@@ -0,0 +1,14 @@
+-- Audit trail of customer merges. Local only; the merged rows themselves
+-- reach the remote through the regular sync queue.
+CREATE TABLE IF NOT EXISTS customer_merges (
+  id TEXT PRIMARY KEY,
+  survivor_id TEXT NOT NULL,
+  merged_id TEXT NOT NULL,
+  survivor_snapshot TEXT NOT NULL,
+  merged_snapshot TEXT NOT NULL,
+  moved_order_ids TEXT NOT NULL,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_merges_survivor ON customer_merges(survivor_id);
+CREATE INDEX IF NOT EXISTS idx_customer_merges_merged ON customer_merges(merged_id);
@@ -0,0 +1,2 @@
+-- Local lookup key for customers.phone; the display text stays in `phone`.
+CREATE INDEX IF NOT EXISTS idx_customers_phone_e164 ON customers(phone_e164);
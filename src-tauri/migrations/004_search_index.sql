@@ -0,0 +1,174 @@
+-- Full-text index behind global_search. One row per customer, order and
+-- expense; an order's row also carries its customer's name and the product
+-- URLs of its items. The trigram tokenizer keeps substring matching for
+-- Myanmar text, codes and phone digits. Triggers below keep it in sync with
+-- every write, including rows pulled in by sync and imports.
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+  entity_type UNINDEXED,
+  entity_id UNINDEXED,
+  title,
+  subtitle UNINDEXED,
+  body,
+  tokenize = 'trigram'
+);
+
+DROP VIEW IF EXISTS search_documents;
+CREATE VIEW search_documents AS
+SELECT
+  'customer' AS entity_type,
+  c.id AS entity_id,
+  concat_ws(' ', c.customer_id, c.name) AS title,
+  concat_ws(' · ', NULLIF(TRIM(c.phone), ''), NULLIF(TRIM(c.city), '')) AS subtitle,
+  concat_ws(' ', c.phone, c.phone_e164, c.address, c.city, c.social_media_url, c.platform) AS body
+FROM customers c
+WHERE c.deleted_at IS NULL
+UNION ALL
+SELECT
+  'order',
+  o.id,
+  concat_ws(' ', o.order_id, c.name),
+  concat_ws(' · ', o.status, DATE(o.order_date)),
+  concat_ws(
+    ' ',
+    o.order_from,
+    c.customer_id,
+    c.phone,
+    c.phone_e164,
+    (SELECT group_concat(oi.product_url, ' ')
+     FROM order_items oi
+     WHERE oi.order_id = o.id AND oi.deleted_at IS NULL)
+  )
+FROM orders o
+LEFT JOIN customers c ON c.id = o.customer_id
+WHERE o.deleted_at IS NULL
+UNION ALL
+SELECT
+  'expense',
+  e.id,
+  concat_ws(' ', e.expense_id, e.title),
+  concat_ws(' · ', e.category, DATE(e.expense_date)),
+  concat_ws(' ', e.category, e.payment_method, e.notes)
+FROM expenses e
+WHERE e.deleted_at IS NULL;
+
+-- Customers: their own row plus the rows of their orders, which show the name.
+DROP TRIGGER IF EXISTS search_customers_ai;
+CREATE TRIGGER search_customers_ai AFTER INSERT ON customers BEGIN
+  DELETE FROM search_index WHERE entity_type = 'customer' AND entity_id = NEW.id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'customer' AND entity_id = NEW.id;
+  DELETE FROM search_index WHERE entity_type = 'order'
+    AND entity_id IN (SELECT id FROM orders WHERE customer_id = NEW.id);
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order'
+      AND entity_id IN (SELECT id FROM orders WHERE customer_id = NEW.id);
+END;
+
+DROP TRIGGER IF EXISTS search_customers_au;
+CREATE TRIGGER search_customers_au
+AFTER UPDATE OF customer_id, name, phone, phone_e164, address, city, social_media_url, platform, deleted_at
+ON customers BEGIN
+  DELETE FROM search_index WHERE entity_type = 'customer' AND entity_id = NEW.id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'customer' AND entity_id = NEW.id;
+  DELETE FROM search_index WHERE entity_type = 'order'
+    AND entity_id IN (SELECT id FROM orders WHERE customer_id = NEW.id);
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order'
+      AND entity_id IN (SELECT id FROM orders WHERE customer_id = NEW.id);
+END;
+
+DROP TRIGGER IF EXISTS search_customers_ad;
+CREATE TRIGGER search_customers_ad AFTER DELETE ON customers BEGIN
+  DELETE FROM search_index WHERE entity_type = 'customer' AND entity_id = OLD.id;
+  DELETE FROM search_index WHERE entity_type = 'order'
+    AND entity_id IN (SELECT id FROM orders WHERE customer_id = OLD.id);
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order'
+      AND entity_id IN (SELECT id FROM orders WHERE customer_id = OLD.id);
+END;
+
+-- Orders
+DROP TRIGGER IF EXISTS search_orders_ai;
+CREATE TRIGGER search_orders_ai AFTER INSERT ON orders BEGIN
+  DELETE FROM search_index WHERE entity_type = 'order' AND entity_id = NEW.id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order' AND entity_id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS search_orders_au;
+CREATE TRIGGER search_orders_au
+AFTER UPDATE OF order_id, customer_id, status, order_from, order_date, deleted_at
+ON orders BEGIN
+  DELETE FROM search_index WHERE entity_type = 'order' AND entity_id = NEW.id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order' AND entity_id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS search_orders_ad;
+CREATE TRIGGER search_orders_ad AFTER DELETE ON orders BEGIN
+  DELETE FROM search_index WHERE entity_type = 'order' AND entity_id = OLD.id;
+END;
+
+-- Order items only contribute to their order's row.
+DROP TRIGGER IF EXISTS search_order_items_ai;
+CREATE TRIGGER search_order_items_ai AFTER INSERT ON order_items BEGIN
+  DELETE FROM search_index WHERE entity_type = 'order' AND entity_id = NEW.order_id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order' AND entity_id = NEW.order_id;
+END;
+
+DROP TRIGGER IF EXISTS search_order_items_au;
+CREATE TRIGGER search_order_items_au
+AFTER UPDATE OF order_id, product_url, deleted_at
+ON order_items BEGIN
+  DELETE FROM search_index WHERE entity_type = 'order'
+    AND entity_id IN (OLD.order_id, NEW.order_id);
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order' AND entity_id IN (OLD.order_id, NEW.order_id);
+END;
+
+DROP TRIGGER IF EXISTS search_order_items_ad;
+CREATE TRIGGER search_order_items_ad AFTER DELETE ON order_items BEGIN
+  DELETE FROM search_index WHERE entity_type = 'order' AND entity_id = OLD.order_id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'order' AND entity_id = OLD.order_id;
+END;
+
+-- Expenses
+DROP TRIGGER IF EXISTS search_expenses_ai;
+CREATE TRIGGER search_expenses_ai AFTER INSERT ON expenses BEGIN
+  DELETE FROM search_index WHERE entity_type = 'expense' AND entity_id = NEW.id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'expense' AND entity_id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS search_expenses_au;
+CREATE TRIGGER search_expenses_au
+AFTER UPDATE OF expense_id, title, category, payment_method, notes, expense_date, deleted_at
+ON expenses BEGIN
+  DELETE FROM search_index WHERE entity_type = 'expense' AND entity_id = NEW.id;
+  INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+    SELECT entity_type, entity_id, title, subtitle, body FROM search_documents
+    WHERE entity_type = 'expense' AND entity_id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS search_expenses_ad;
+CREATE TRIGGER search_expenses_ad AFTER DELETE ON expenses BEGIN
+  DELETE FROM search_index WHERE entity_type = 'expense' AND entity_id = OLD.id;
+END;
+
+DELETE FROM search_index;
+INSERT INTO search_index (entity_type, entity_id, title, subtitle, body)
+  SELECT entity_type, entity_id, title, subtitle, body FROM search_documents;
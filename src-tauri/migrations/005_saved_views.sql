@@ -0,0 +1,17 @@
+-- Named list filters (search, conditions, sort) plus a column set, owned by a
+-- user and optionally shared with everyone. Local only.
+CREATE TABLE IF NOT EXISTS saved_views (
+  id TEXT PRIMARY KEY,
+  owner_id TEXT NOT NULL,
+  name TEXT NOT NULL,
+  entity TEXT NOT NULL CHECK(entity IN ('orders','customers','expenses')),
+  filter TEXT NOT NULL,
+  columns TEXT,
+  is_shared INTEGER DEFAULT 0,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_views_owner ON saved_views(owner_id, entity);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_views_owner_name
+  ON saved_views(owner_id, entity, name COLLATE NOCASE);
@@ -0,0 +1,25 @@
+-- Managed expense categories and payment methods. Expenses keep storing the
+-- name as text (that is what syncs), matched case-insensitively against these
+-- tables. Local only.
+CREATE TABLE IF NOT EXISTS expense_categories (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  parent_id TEXT REFERENCES expense_categories (id) ON DELETE SET NULL,
+  monthly_budget REAL CHECK(monthly_budget IS NULL OR monthly_budget >= 0),
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_expense_categories_name
+  ON expense_categories(name COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS idx_expense_categories_parent ON expense_categories(parent_id);
+
+CREATE TABLE IF NOT EXISTS payment_methods (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_payment_methods_name
+  ON payment_methods(name COLLATE NOCASE);
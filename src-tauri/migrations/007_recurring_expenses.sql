@@ -0,0 +1,34 @@
+-- Templates for expenses that repeat (rent, internet, salaries). The scheduler
+-- turns each due occurrence into a real expense. Local only; the generated
+-- expenses sync like any other.
+CREATE TABLE IF NOT EXISTS recurring_expenses (
+  id TEXT PRIMARY KEY,
+  title TEXT NOT NULL,
+  amount REAL NOT NULL CHECK(amount >= 0),
+  category TEXT,
+  payment_method TEXT,
+  notes TEXT,
+  frequency TEXT NOT NULL CHECK(frequency IN ('monthly','weekly')),
+  -- 1-31 for monthly; months without that day use their last day.
+  day_of_month INTEGER CHECK(day_of_month IS NULL OR day_of_month BETWEEN 1 AND 31),
+  -- 0 (Sunday) to 6 (Saturday) for weekly.
+  day_of_week INTEGER CHECK(day_of_week IS NULL OR day_of_week BETWEEN 0 AND 6),
+  start_date TEXT NOT NULL,
+  end_date TEXT,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  -- Latest occurrence already posted; generation resumes after it.
+  last_generated_date TEXT,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME
+);
+
+-- One row per posted occurrence. The primary key is the idempotency guard: an
+-- occurrence is claimed here with the id of its expense before that expense is
+-- written, so a retry after a crash or restart reuses the same id.
+CREATE TABLE IF NOT EXISTS recurring_expense_runs (
+  recurring_id TEXT NOT NULL REFERENCES recurring_expenses (id) ON DELETE CASCADE,
+  due_date TEXT NOT NULL,
+  expense_id TEXT NOT NULL,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (recurring_id, due_date)
+);
@@ -0,0 +1,19 @@
+-- Files (receipt photos, documents) attached to expenses, orders and
+-- customers. The file lives in app data under attachments/; `cloud_key` is set
+-- once it has been uploaded to the configured S3 bucket. Local only.
+CREATE TABLE IF NOT EXISTS attachments (
+  id TEXT PRIMARY KEY,
+  entity_type TEXT NOT NULL CHECK(entity_type IN ('expense','order','customer')),
+  entity_id TEXT NOT NULL,
+  file_name TEXT NOT NULL,
+  content_type TEXT NOT NULL,
+  size_bytes INTEGER NOT NULL,
+  local_path TEXT NOT NULL,
+  thumbnail_path TEXT,
+  cloud_key TEXT,
+  cloud_url TEXT,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_entity ON attachments(entity_type, entity_id);
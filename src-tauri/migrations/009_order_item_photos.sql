@@ -0,0 +1,24 @@
+-- Product photos kept per order item, so we still know what was ordered once
+-- the marketplace listing is gone. Files are content-addressed: the image lives
+-- in app data under product-photos/<hash[..2]>/<hash>.<ext>, shared by every
+-- row with the same hash. `cloud_key` is set once mirrored to S3. Local only.
+CREATE TABLE IF NOT EXISTS order_item_photos (
+  id TEXT PRIMARY KEY,
+  order_item_id TEXT NOT NULL,
+  content_hash TEXT NOT NULL,
+  file_ext TEXT NOT NULL,
+  content_type TEXT NOT NULL,
+  size_bytes INTEGER NOT NULL,
+  width INTEGER,
+  height INTEGER,
+  source TEXT NOT NULL CHECK(source IN ('file','clipboard','url')),
+  source_url TEXT,
+  cloud_key TEXT,
+  cloud_url TEXT,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME,
+  UNIQUE(order_item_id, content_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_item_photos_item ON order_item_photos(order_item_id);
+CREATE INDEX IF NOT EXISTS idx_order_item_photos_hash ON order_item_photos(content_hash);
@@ -0,0 +1,37 @@
+-- Places scheduled backups are uploaded to. Every enabled target receives a
+-- copy of each backup. S3 secret keys are stored encrypted by the vault.
+-- Local only.
+CREATE TABLE IF NOT EXISTS backup_targets (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  kind TEXT NOT NULL CHECK(kind IN ('google_drive','s3','s3_compatible','local')),
+  is_enabled INTEGER NOT NULL DEFAULT 1,
+  -- Folder for `local`; may be a mounted network share.
+  local_dir TEXT,
+  s3_bucket TEXT,
+  s3_region TEXT,
+  s3_endpoint_url TEXT,
+  s3_force_path_style INTEGER NOT NULL DEFAULT 1,
+  s3_access_key_id TEXT,
+  s3_secret_access_key TEXT,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+  updated_at DATETIME
+);
+
+-- Google Drive was the only scheduled target before; keep backing up there.
+INSERT OR IGNORE INTO backup_targets (id, name, kind) VALUES ('google-drive', 'Google Drive', 'google_drive');
+
+-- One row per backup attempt on one target.
+CREATE TABLE IF NOT EXISTS backup_runs (
+  id TEXT PRIMARY KEY,
+  target_id TEXT NOT NULL REFERENCES backup_targets (id) ON DELETE CASCADE,
+  trigger TEXT NOT NULL CHECK(trigger IN ('scheduled','manual')),
+  status TEXT NOT NULL CHECK(status IN ('success','failed')),
+  file_name TEXT,
+  size_bytes INTEGER,
+  error TEXT,
+  started_at DATETIME NOT NULL,
+  finished_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_runs_target ON backup_runs(target_id, started_at);
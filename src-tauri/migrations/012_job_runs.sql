@@ -0,0 +1,14 @@
+-- One row per run of a scheduled job. `catch_up` runs make up for a time the
+-- app was closed or the computer asleep. A run still `running` when the app
+-- starts was interrupted. Local only.
+CREATE TABLE IF NOT EXISTS job_runs (
+  id TEXT PRIMARY KEY,
+  job TEXT NOT NULL CHECK(job IN ('backup','recurring_expenses')),
+  trigger TEXT NOT NULL CHECK(trigger IN ('scheduled','catch_up')),
+  status TEXT NOT NULL CHECK(status IN ('running','success','failed')),
+  error TEXT,
+  started_at DATETIME NOT NULL,
+  finished_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_runs_job ON job_runs(job, started_at);
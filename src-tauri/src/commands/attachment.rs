@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::attachment;
+use crate::state::AppState;
+
+pub use crate::services::attachment::{Attachment, AttachmentEntityType};
+
+/// Attaches a file from disk to an expense, order or customer.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn add_attachment(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    entity_type: AttachmentEntityType,
+    entity_id: String,
+    file_path: String,
+    upload_to_s3: Option<bool>,
+) -> Result<Attachment, AppError> {
+    attachment::add_attachment(
+        state.inner().clone(),
+        &app,
+        entity_type,
+        entity_id,
+        file_path,
+        upload_to_s3.unwrap_or(false),
+    )
+    .await
+}
+
+/// Lists the attachments of one record.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_attachments(
+    state: State<'_, Arc<AppState>>,
+    entity_type: AttachmentEntityType,
+    entity_id: String,
+) -> Result<Vec<Attachment>, AppError> {
+    attachment::get_attachments(state.inner().clone(), entity_type, entity_id).await
+}
+
+/// Uploads an attachment to the configured storage.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn upload_attachment_to_s3(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<Attachment, AppError> {
+    attachment::upload_attachment_to_s3(state.inner().clone(), &app, id).await
+}
+
+/// Deletes an attachment and its files.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn delete_attachment(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<(), AppError> {
+    attachment::delete_attachment(state.inner().clone(), &app, id).await
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::backup::{self, BackupTrigger};
+use crate::state::AppState;
+
+pub use crate::services::backup::{
+    BackupEncryption, BackupEncryptionInput, BackupRun, BackupTarget, BackupTargetInput,
+    RemoteBackup,
+};
+pub use crate::services::storage::StoredObject;
+
+/// Uploads a database backup to the configured storage.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn trigger_storage_backup(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, AppError> {
+    backup::upload_backup_to_storage(state.inner().clone(), &app).await
+}
+
+/// Lists the backups in the configured storage, newest first.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn list_storage_backups(app: AppHandle) -> Result<Vec<StoredObject>, AppError> {
+    backup::list_storage_backups(&app).await
+}
+
+/// Lists backup targets with their latest outcome.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_backup_targets(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<BackupTarget>, AppError> {
+    backup::get_backup_targets(state.inner().clone()).await
+}
+
+/// Adds a Google Drive, S3 or folder backup target.
+#[tauri::command]
+#[instrument(skip(state, input))]
+pub async fn create_backup_target(
+    state: State<'_, Arc<AppState>>,
+    input: BackupTargetInput,
+) -> Result<BackupTarget, AppError> {
+    backup::create_backup_target(state.inner().clone(), input).await
+}
+
+/// Replaces a backup target's settings.
+#[tauri::command]
+#[instrument(skip(state, input))]
+pub async fn update_backup_target(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    input: BackupTargetInput,
+) -> Result<BackupTarget, AppError> {
+    backup::update_backup_target(state.inner().clone(), id, input).await
+}
+
+/// Deletes a backup target and its history.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_backup_target(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<(), AppError> {
+    backup::delete_backup_target(state.inner().clone(), id).await
+}
+
+/// Checks that a backup target can be reached.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn test_backup_target(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<(), AppError> {
+    backup::test_backup_target(state.inner().clone(), &app, id).await
+}
+
+/// Backs up now to one target, or to every enabled target.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn run_backup_now(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    target_id: Option<String>,
+) -> Result<Vec<BackupRun>, AppError> {
+    backup::run_backups(
+        state.inner().clone(),
+        &app,
+        BackupTrigger::Manual,
+        target_id,
+    )
+    .await
+}
+
+/// Lists backup attempts, newest first.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_backup_runs(
+    state: State<'_, Arc<AppState>>,
+    target_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<BackupRun>, AppError> {
+    backup::get_backup_runs(state.inner().clone(), target_id, limit).await
+}
+
+/// Lists the backups stored on a target, newest first.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn list_target_backups(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    target_id: String,
+) -> Result<Vec<RemoteBackup>, AppError> {
+    backup::list_target_backups(state.inner().clone(), &app, target_id).await
+}
+
+/// Restores a backup stored on a target, keeping a copy of the current data.
+#[tauri::command]
+#[instrument(skip(app, state, passphrase))]
+pub async fn restore_from_backup_target(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    target_id: String,
+    backup_id: String,
+    passphrase: Option<String>,
+) -> Result<(), AppError> {
+    backup::restore_from_backup_target(
+        state.inner().clone(),
+        &app,
+        target_id,
+        backup_id,
+        passphrase,
+    )
+    .await
+}
+
+/// Returns what backup archives are encrypted with.
+#[tauri::command]
+#[instrument(skip(app))]
+pub fn get_backup_encryption(app: AppHandle) -> Result<BackupEncryption, AppError> {
+    backup::get_backup_encryption(&app)
+}
+
+/// Turns backup encryption on or off.
+#[tauri::command]
+#[instrument(skip(app, state, input))]
+pub async fn set_backup_encryption(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    input: BackupEncryptionInput,
+) -> Result<BackupEncryption, AppError> {
+    backup::set_backup_encryption(state.inner().clone(), &app, input).await
+}
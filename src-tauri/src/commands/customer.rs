@@ -4,8 +4,9 @@ use tauri::{AppHandle, State};
 use tracing::instrument;
 
 use crate::error::AppError;
-use crate::models::{Customer, PaginatedCustomers};
+use crate::models::{Customer, CustomerListFilter, PaginatedCustomers};
 use crate::services::customer;
+use crate::services::pagination::CursorPage;
 use crate::state::AppState;
 
 /// Creates a customer record.
@@ -26,6 +27,7 @@ pub async fn create_customer(
     created_at: Option<String>,
     updated_at: Option<String>,
     deleted_at: Option<String>,
+    allow_duplicate: Option<bool>,
 ) -> Result<String, AppError> {
     customer::create_customer(
         state.inner().clone(),
@@ -41,6 +43,7 @@ pub async fn create_customer(
         created_at,
         updated_at,
         deleted_at,
+        allow_duplicate.unwrap_or(false),
     )
     .await
 }
@@ -76,6 +79,26 @@ pub async fn get_customers_paginated(
     .await
 }
 
+/// Loads one cursor page of customers; pass `next_cursor`/`prev_cursor` back as `cursor`.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_customers_page(
+    state: State<'_, Arc<AppState>>,
+    filter: Option<CustomerListFilter>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: Option<bool>,
+) -> Result<CursorPage<Customer>, AppError> {
+    customer::get_customers_page(
+        state.inner().clone(),
+        filter.unwrap_or_default(),
+        cursor,
+        limit,
+        include_total.unwrap_or(false),
+    )
+    .await
+}
+
 /// Loads a customer by id.
 #[tauri::command]
 #[instrument(skip(state))]
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::customer_dedupe;
+use crate::state::AppState;
+
+pub use crate::services::customer_dedupe::{
+    CustomerMatch, CustomerMergeRecord, CustomerMergeResult, DuplicateCandidate,
+};
+
+/// Lists customers resembling the given details, e.g. before creating a new one.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn check_customer_duplicates(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    name: String,
+    phone: Option<String>,
+    social_media_url: Option<String>,
+    exclude_id: Option<String>,
+    min_name_similarity: Option<f64>,
+) -> Result<Vec<CustomerMatch>, AppError> {
+    customer_dedupe::check_customer_duplicates(
+        state.inner().clone(),
+        &app,
+        name,
+        phone,
+        social_media_url,
+        exclude_id,
+        min_name_similarity,
+    )
+    .await
+}
+
+/// Scans all customers for likely duplicate pairs.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn find_duplicate_customers(
+    state: State<'_, Arc<AppState>>,
+    min_name_similarity: Option<f64>,
+) -> Result<Vec<DuplicateCandidate>, AppError> {
+    customer_dedupe::find_duplicate_customers(state.inner().clone(), min_name_similarity).await
+}
+
+/// Merges one customer into another and soft-deletes the merged record.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn merge_customers(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    survivor_id: String,
+    merged_id: String,
+) -> Result<CustomerMergeResult, AppError> {
+    customer_dedupe::merge_customers(state.inner().clone(), &app, survivor_id, merged_id).await
+}
+
+/// Lists the customer merge audit trail.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_customer_merges(
+    state: State<'_, Arc<AppState>>,
+    customer_id: Option<String>,
+) -> Result<Vec<CustomerMergeRecord>, AppError> {
+    customer_dedupe::get_customer_merges(state.inner().clone(), customer_id).await
+}
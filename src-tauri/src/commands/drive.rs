@@ -1,8 +1,11 @@
-use tauri::AppHandle;
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
 use tracing::instrument;
 
 use crate::error::AppError;
 use crate::services::drive;
+use crate::state::AppState;
 
 pub use crate::services::drive::DriveStatus;
 
@@ -29,13 +32,10 @@ pub async fn disconnect_google_drive(app: AppHandle) -> Result<(), AppError> {
 
 /// Triggers immediate Google Drive backup upload.
 #[tauri::command]
-#[instrument(skip(app))]
-pub async fn trigger_drive_backup(app: AppHandle) -> Result<String, AppError> {
-    drive::trigger_drive_backup(&app).await
-}
-
-/// Performs backup upload workflow and is reused by scheduler jobs.
-#[instrument(skip(app))]
-pub async fn perform_drive_backup(app: &AppHandle) -> Result<String, AppError> {
-    drive::perform_drive_backup(app).await
+#[instrument(skip(app, state))]
+pub async fn trigger_drive_backup(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, AppError> {
+    drive::trigger_drive_backup(state.inner().clone(), &app).await
 }
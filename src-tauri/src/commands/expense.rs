@@ -4,8 +4,9 @@ use tauri::{AppHandle, State};
 use tracing::instrument;
 
 use crate::error::AppError;
-use crate::models::{Expense, PaginatedExpenses};
+use crate::models::{Expense, ExpenseListFilter, PaginatedExpenses};
 use crate::services::expense;
+use crate::services::pagination::CursorPage;
 use crate::state::AppState;
 
 /// Creates an expense record.
@@ -66,13 +67,35 @@ pub async fn get_expenses_paginated(
         state.inner().clone(),
         page,
         page_size,
-        search_key,
-        search_term,
-        category_filter,
-        date_from,
-        date_to,
-        sort_by,
-        sort_order,
+        ExpenseListFilter {
+            search_key,
+            search_term,
+            category_filter,
+            date_from,
+            date_to,
+            sort_by,
+            sort_order,
+        },
+    )
+    .await
+}
+
+/// Loads one cursor page of expenses; pass `next_cursor`/`prev_cursor` back as `cursor`.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_expenses_page(
+    state: State<'_, Arc<AppState>>,
+    filter: Option<ExpenseListFilter>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: Option<bool>,
+) -> Result<CursorPage<Expense>, AppError> {
+    expense::get_expenses_page(
+        state.inner().clone(),
+        filter.unwrap_or_default(),
+        cursor,
+        limit,
+        include_total.unwrap_or(false),
     )
     .await
 }
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::expense_category;
+use crate::state::AppState;
+
+pub use crate::services::expense_category::{BudgetReportRow, ExpenseCategory, PaymentMethod};
+
+/// Lists expense categories with their usage.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_expense_categories(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ExpenseCategory>, AppError> {
+    expense_category::get_expense_categories(state.inner().clone()).await
+}
+
+/// Creates an expense category.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_expense_category(
+    state: State<'_, Arc<AppState>>,
+    name: String,
+    parent_id: Option<String>,
+    monthly_budget: Option<f64>,
+) -> Result<ExpenseCategory, AppError> {
+    expense_category::create_expense_category(
+        state.inner().clone(),
+        name,
+        parent_id,
+        monthly_budget,
+    )
+    .await
+}
+
+/// Updates an expense category; a rename carries over to its expenses.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn update_expense_category(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    name: String,
+    parent_id: Option<String>,
+    monthly_budget: Option<f64>,
+) -> Result<ExpenseCategory, AppError> {
+    expense_category::update_expense_category(
+        state.inner().clone(),
+        &app,
+        id,
+        name,
+        parent_id,
+        monthly_budget,
+    )
+    .await
+}
+
+/// Deletes an expense category, moving its expenses to `replacement_id`.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn delete_expense_category(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    replacement_id: Option<String>,
+) -> Result<u64, AppError> {
+    expense_category::delete_expense_category(state.inner().clone(), &app, id, replacement_id).await
+}
+
+/// Lists payment methods with their usage.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_payment_methods(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PaymentMethod>, AppError> {
+    expense_category::get_payment_methods(state.inner().clone()).await
+}
+
+/// Creates a payment method.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_payment_method(
+    state: State<'_, Arc<AppState>>,
+    name: String,
+) -> Result<PaymentMethod, AppError> {
+    expense_category::create_payment_method(state.inner().clone(), name).await
+}
+
+/// Renames a payment method and the expenses paid with it.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn update_payment_method(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    name: String,
+) -> Result<PaymentMethod, AppError> {
+    expense_category::update_payment_method(state.inner().clone(), &app, id, name).await
+}
+
+/// Deletes a payment method, moving its expenses to `replacement_id`.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn delete_payment_method(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    replacement_id: Option<String>,
+) -> Result<u64, AppError> {
+    expense_category::delete_payment_method(state.inner().clone(), &app, id, replacement_id).await
+}
+
+/// Budget against actual spend per category for each month in the range.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_budget_report(
+    state: State<'_, Arc<AppState>>,
+    from_month: String,
+    to_month: String,
+) -> Result<Vec<BudgetReportRow>, AppError> {
+    expense_category::get_budget_report(state.inner().clone(), from_month, to_month).await
+}
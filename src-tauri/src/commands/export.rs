@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::models::{CustomerListFilter, ExpenseListFilter, OrderListFilter};
+use crate::services::export;
+use crate::state::AppState;
+
+pub use crate::services::export::ExportSummary;
+
+/// Writes orders matching the list filters to a CSV or XLSX file.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_orders(
+    state: State<'_, Arc<AppState>>,
+    dest_path: String,
+    format: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: Option<OrderListFilter>,
+) -> Result<ExportSummary, AppError> {
+    export::export_orders(
+        state.inner().clone(),
+        dest_path,
+        format,
+        columns,
+        filter.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Writes customers matching the list filters to a CSV or XLSX file.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_customers(
+    state: State<'_, Arc<AppState>>,
+    dest_path: String,
+    format: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: Option<CustomerListFilter>,
+) -> Result<ExportSummary, AppError> {
+    export::export_customers(
+        state.inner().clone(),
+        dest_path,
+        format,
+        columns,
+        filter.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Writes expenses matching the list filters to a CSV or XLSX file.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_expenses(
+    state: State<'_, Arc<AppState>>,
+    dest_path: String,
+    format: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: Option<ExpenseListFilter>,
+) -> Result<ExportSummary, AppError> {
+    export::export_expenses(
+        state.inner().clone(),
+        dest_path,
+        format,
+        columns,
+        filter.unwrap_or_default(),
+    )
+    .await
+}
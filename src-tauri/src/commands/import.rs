@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::import;
+use crate::state::AppState;
+
+pub use crate::services::import::{ImportEntity, ImportPreview, ImportReport};
+
+/// Reads the header and sample rows of an import file and suggests a column mapping.
+#[tauri::command]
+#[instrument]
+pub async fn preview_import(
+    path: String,
+    entity: ImportEntity,
+    format: Option<String>,
+) -> Result<ImportPreview, AppError> {
+    import::preview_import(path, entity, format).await
+}
+
+/// Validates a customer import file and, unless `dry_run`, commits it.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn import_customers(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    format: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, AppError> {
+    import::import_customers(
+        state.inner().clone(),
+        &app,
+        path,
+        format,
+        mapping,
+        dry_run.unwrap_or(true),
+    )
+    .await
+}
+
+/// Validates an order import file and, unless `dry_run`, commits it.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn import_orders(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    format: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, AppError> {
+    import::import_orders(
+        state.inner().clone(),
+        &app,
+        path,
+        format,
+        mapping,
+        dry_run.unwrap_or(true),
+    )
+    .await
+}
+
+/// Validates an order item import file and, unless `dry_run`, commits it.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn import_order_items(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    format: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, AppError> {
+    import::import_order_items(
+        state.inner().clone(),
+        &app,
+        path,
+        format,
+        mapping,
+        dry_run.unwrap_or(true),
+    )
+    .await
+}
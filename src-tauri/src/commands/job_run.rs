@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::job_run;
+use crate::state::AppState;
+
+pub use crate::services::job_run::{JobRun, ScheduledJob};
+
+/// Lists scheduled job runs, newest first.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_job_runs(
+    state: State<'_, Arc<AppState>>,
+    job: Option<ScheduledJob>,
+    limit: Option<u32>,
+) -> Result<Vec<JobRun>, AppError> {
+    job_run::get_job_runs(state.inner().clone(), job, limit).await
+}
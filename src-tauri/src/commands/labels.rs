@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::labels;
+use crate::state::AppState;
+
+pub use crate::services::labels::LabelSheetResult;
+
+/// Writes a PDF of parcel labels for the selected orders or an order status.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn generate_parcel_labels(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_ids: Option<Vec<String>>,
+    status: Option<String>,
+    sheet_size: Option<String>,
+    dest_path: String,
+) -> Result<LabelSheetResult, AppError> {
+    labels::generate_parcel_labels(
+        state.inner().clone(),
+        &app,
+        order_ids,
+        status,
+        sheet_size,
+        dest_path,
+    )
+    .await
+}
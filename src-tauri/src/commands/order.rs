@@ -5,10 +5,11 @@ use tracing::instrument;
 
 use crate::error::AppError;
 use crate::models::{
-    DashboardDetailRecord, DashboardStats, OrderDetail, OrderExportRow, OrderItemPayload,
-    OrderWithCustomer, PaginatedOrders,
+    DashboardDetailRecord, DashboardStats, OrderConditions, OrderDetail, OrderExportRow,
+    OrderItemPayload, OrderListFilter, OrderWithCustomer, PaginatedOrders,
 };
 use crate::services::order;
+use crate::services::pagination::CursorPage;
 use crate::state::AppState;
 
 /// Creates an order and its order items.
@@ -97,6 +98,7 @@ pub async fn get_orders_paginated(
     status_filter: Option<String>,
     sort_by: Option<String>,
     sort_order: Option<String>,
+    conditions: Option<OrderConditions>,
 ) -> Result<PaginatedOrders, AppError> {
     order::get_orders_paginated(
         state.inner().clone(),
@@ -107,6 +109,27 @@ pub async fn get_orders_paginated(
         status_filter,
         sort_by,
         sort_order,
+        conditions,
+    )
+    .await
+}
+
+/// Loads one cursor page of orders; pass `next_cursor`/`prev_cursor` back as `cursor`.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_orders_page(
+    state: State<'_, Arc<AppState>>,
+    filter: Option<OrderListFilter>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: Option<bool>,
+) -> Result<CursorPage<OrderWithCustomer>, AppError> {
+    order::get_orders_page(
+        state.inner().clone(),
+        filter.unwrap_or_default(),
+        cursor,
+        limit,
+        include_total.unwrap_or(false),
     )
     .await
 }
@@ -0,0 +1,52 @@
+use tauri::AppHandle;
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::printer;
+
+pub use crate::services::printer::{PrintJob, PrintJobStatus, PrinterInfo};
+
+/// Lists printers available through CUPS.
+#[tauri::command]
+#[instrument]
+pub async fn list_printers() -> Result<Vec<PrinterInfo>, AppError> {
+    printer::run_blocking(printer::list_printers).await
+}
+
+/// Checks that a printer exists, falling back to the configured invoice printer.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn validate_printer(
+    app: AppHandle,
+    printer_name: Option<String>,
+) -> Result<PrinterInfo, AppError> {
+    printer::run_blocking(move || printer::validate_configured_printer(app, printer_name)).await
+}
+
+/// Returns the current state of a submitted print job.
+#[tauri::command]
+#[instrument]
+pub async fn get_print_job_status(job_id: String) -> Result<PrintJobStatus, AppError> {
+    printer::run_blocking(move || printer::get_print_job_status(job_id)).await
+}
+
+/// Prints a test page on the given or configured printer.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn test_print(
+    app: AppHandle,
+    printer_name: Option<String>,
+) -> Result<PrintJob, AppError> {
+    printer::run_blocking(move || printer::test_print(app, printer_name)).await
+}
+
+/// Prints a rendered invoice image without a print dialog.
+#[tauri::command]
+#[instrument(skip(app, bytes))]
+pub async fn print_invoice_direct(
+    app: AppHandle,
+    bytes: Vec<u8>,
+    printer_name: Option<String>,
+) -> Result<PrintJob, AppError> {
+    printer::run_blocking(move || printer::print_invoice_image(&app, bytes, printer_name)).await
+}
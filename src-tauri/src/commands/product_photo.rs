@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::product_photo;
+use crate::state::AppState;
+
+pub use crate::services::product_photo::OrderItemPhoto;
+
+/// Adds an image file from disk to an order item.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn add_order_item_photo_from_file(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_item_id: String,
+    file_path: String,
+    upload_to_s3: Option<bool>,
+) -> Result<OrderItemPhoto, AppError> {
+    product_photo::add_order_item_photo_from_file(
+        state.inner().clone(),
+        &app,
+        order_item_id,
+        file_path,
+        upload_to_s3.unwrap_or(false),
+    )
+    .await
+}
+
+/// Adds the image on the clipboard to an order item.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn add_order_item_photo_from_clipboard(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_item_id: String,
+    upload_to_s3: Option<bool>,
+) -> Result<OrderItemPhoto, AppError> {
+    product_photo::add_order_item_photo_from_clipboard(
+        state.inner().clone(),
+        &app,
+        order_item_id,
+        upload_to_s3.unwrap_or(false),
+    )
+    .await
+}
+
+/// Downloads an image and adds it to an order item.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn add_order_item_photo_from_url(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_item_id: String,
+    url: String,
+    upload_to_s3: Option<bool>,
+) -> Result<OrderItemPhoto, AppError> {
+    product_photo::add_order_item_photo_from_url(
+        state.inner().clone(),
+        &app,
+        order_item_id,
+        url,
+        upload_to_s3.unwrap_or(false),
+    )
+    .await
+}
+
+/// Lists the photos of one order item.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn get_order_item_photos(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_item_id: String,
+) -> Result<Vec<OrderItemPhoto>, AppError> {
+    product_photo::get_order_item_photos(state.inner().clone(), &app, order_item_id).await
+}
+
+/// Lists the photos of every item of an order.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn get_order_photos(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_id: String,
+) -> Result<Vec<OrderItemPhoto>, AppError> {
+    product_photo::get_order_photos(state.inner().clone(), &app, order_id).await
+}
+
+/// Uploads a photo to the configured storage.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn upload_order_item_photo_to_s3(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<OrderItemPhoto, AppError> {
+    product_photo::upload_order_item_photo_to_s3(state.inner().clone(), &app, id).await
+}
+
+/// Removes a photo from its order item.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn delete_order_item_photo(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<(), AppError> {
+    product_photo::delete_order_item_photo(state.inner().clone(), &app, id).await
+}
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::printer::PrintJob;
+use crate::services::receipt;
+use crate::state::AppState;
+
+/// Prints an order receipt on the configured ESC/POS thermal printer.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn print_order_receipt(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    order_id: String,
+) -> Result<PrintJob, AppError> {
+    receipt::print_order_receipt(state.inner().clone(), &app, order_id).await
+}
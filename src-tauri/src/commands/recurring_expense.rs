@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::recurring_expense;
+use crate::state::AppState;
+
+pub use crate::services::recurring_expense::{
+    RecurringExpense, RecurringExpenseInput, UpcomingRecurringExpense,
+};
+
+/// Lists recurring expense templates.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_recurring_expenses(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<RecurringExpense>, AppError> {
+    recurring_expense::get_recurring_expenses(state.inner().clone()).await
+}
+
+/// Creates a recurring expense template.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_recurring_expense(
+    state: State<'_, Arc<AppState>>,
+    input: RecurringExpenseInput,
+) -> Result<RecurringExpense, AppError> {
+    recurring_expense::create_recurring_expense(state.inner().clone(), input).await
+}
+
+/// Updates a recurring expense template.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn update_recurring_expense(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    input: RecurringExpenseInput,
+) -> Result<RecurringExpense, AppError> {
+    recurring_expense::update_recurring_expense(state.inner().clone(), id, input).await
+}
+
+/// Deletes a recurring expense template; posted expenses are kept.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_recurring_expense(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<(), AppError> {
+    recurring_expense::delete_recurring_expense(state.inner().clone(), id).await
+}
+
+/// Lists occurrences not yet posted within the next `days` days.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_upcoming_recurring_expenses(
+    state: State<'_, Arc<AppState>>,
+    days: Option<i64>,
+) -> Result<Vec<UpcomingRecurringExpense>, AppError> {
+    recurring_expense::get_upcoming_recurring_expenses(state.inner().clone(), days).await
+}
+
+/// Posts due occurrences now instead of waiting for the scheduler.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn generate_recurring_expenses(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<u64, AppError> {
+    recurring_expense::generate_recurring_expenses(state.inner().clone(), &app).await
+}
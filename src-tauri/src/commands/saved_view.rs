@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use serde_json::Value as JsonValue;
+use tauri::State;
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::saved_view;
+use crate::state::AppState;
+
+pub use crate::services::saved_view::{SavedView, SavedViewEntity, SavedViewRun};
+
+/// Saves a named list filter, sort and column set for the user.
+#[tauri::command]
+#[instrument(skip(state, filter))]
+pub async fn create_saved_view(
+    state: State<'_, Arc<AppState>>,
+    user_id: String,
+    name: String,
+    entity: SavedViewEntity,
+    filter: JsonValue,
+    columns: Option<Vec<String>>,
+    is_shared: Option<bool>,
+) -> Result<SavedView, AppError> {
+    saved_view::create_saved_view(
+        state.inner().clone(),
+        user_id,
+        name,
+        entity,
+        filter,
+        columns,
+        is_shared,
+    )
+    .await
+}
+
+/// Lists the user's saved views and those shared with everyone.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_saved_views(
+    state: State<'_, Arc<AppState>>,
+    user_id: String,
+    entity: Option<SavedViewEntity>,
+) -> Result<Vec<SavedView>, AppError> {
+    saved_view::get_saved_views(state.inner().clone(), user_id, entity).await
+}
+
+/// Updates a saved view owned by the user.
+#[tauri::command]
+#[instrument(skip(state, filter))]
+pub async fn update_saved_view(
+    state: State<'_, Arc<AppState>>,
+    user_id: String,
+    id: String,
+    name: Option<String>,
+    filter: Option<JsonValue>,
+    columns: Option<Vec<String>>,
+    is_shared: Option<bool>,
+) -> Result<SavedView, AppError> {
+    saved_view::update_saved_view(
+        state.inner().clone(),
+        user_id,
+        id,
+        name,
+        filter,
+        columns,
+        is_shared,
+    )
+    .await
+}
+
+/// Deletes a saved view owned by the user.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_saved_view(
+    state: State<'_, Arc<AppState>>,
+    user_id: String,
+    id: String,
+) -> Result<(), AppError> {
+    saved_view::delete_saved_view(state.inner().clone(), user_id, id).await
+}
+
+/// Loads one page of a saved view's list.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn run_saved_view(
+    state: State<'_, Arc<AppState>>,
+    user_id: String,
+    id: String,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<SavedViewRun, AppError> {
+    saved_view::run_saved_view(state.inner().clone(), user_id, id, page, page_size).await
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::scan;
+use crate::state::AppState;
+
+pub use crate::services::scan::{ScanResult, ScanSessionReport};
+
+/// Resolves a scanned order code and applies the configured status transition.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn scan_order_code(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    code: String,
+    target_status: Option<String>,
+) -> Result<ScanResult, AppError> {
+    scan::scan_order_code(state.inner().clone(), &app, code, target_status).await
+}
+
+/// Starts a batch scan session for the given orders or status.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn start_scan_session(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    expected_order_ids: Option<Vec<String>>,
+    expected_status: Option<String>,
+    target_status: Option<String>,
+) -> Result<ScanSessionReport, AppError> {
+    scan::start_scan_session(
+        state.inner().clone(),
+        &app,
+        expected_order_ids,
+        expected_status,
+        target_status,
+    )
+    .await
+}
+
+/// Records a scanned code in an open session.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn scan_in_session(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+    code: String,
+) -> Result<ScanSessionReport, AppError> {
+    scan::scan_in_session(state.inner().clone(), &app, session_id, code).await
+}
+
+/// Returns scanned, missing and unknown codes for a session.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_scan_session(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<ScanSessionReport, AppError> {
+    scan::get_scan_session(state.inner().clone(), session_id).await
+}
+
+/// Closes a session and returns its final report.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn close_scan_session(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<ScanSessionReport, AppError> {
+    scan::close_scan_session(state.inner().clone(), session_id).await
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::search;
+use crate::state::AppState;
+
+pub use crate::services::search::{SearchEntityType, SearchHit};
+
+/// Ranked full-text search across customers, orders and expenses.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn global_search(
+    state: State<'_, Arc<AppState>>,
+    query: String,
+    entity_types: Option<Vec<SearchEntityType>>,
+    limit: Option<u64>,
+) -> Result<Vec<SearchHit>, AppError> {
+    search::global_search(state.inner().clone(), query, entity_types, limit).await
+}
+
+/// Rebuilds the full-text index and returns the number of indexed records.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn rebuild_search_index(state: State<'_, Arc<AppState>>) -> Result<u64, AppError> {
+    search::rebuild_search_index(state.inner().clone()).await
+}
@@ -5,11 +5,11 @@ use crate::services::settings;
 
 pub use crate::services::settings::{AppSettings, AwsS3ConnectionInput, AwsS3ConnectionStatus};
 
-/// Returns app settings from local settings storage.
+/// Returns app settings from local settings storage, with secrets redacted.
 #[tauri::command]
 #[instrument(skip(app))]
 pub fn get_app_settings(app: tauri::AppHandle) -> Result<AppSettings, AppError> {
-    settings::get_app_settings(app)
+    settings::get_redacted_app_settings(app)
 }
 
 /// Updates app settings in local settings storage.
@@ -21,11 +21,12 @@ pub fn update_app_settings(app: tauri::AppHandle, settings: AppSettings) -> Resu
 
 /// Tests an AWS S3 connection using provided credentials.
 #[tauri::command]
-#[instrument(skip(config))]
+#[instrument(skip(app, config))]
 pub async fn test_aws_s3_connection(
+    app: tauri::AppHandle,
     config: AwsS3ConnectionInput,
 ) -> Result<AwsS3ConnectionStatus, AppError> {
-    settings::test_aws_s3_connection(config).await
+    settings::test_aws_s3_connection(app, config).await
 }
 
 /// Returns current AWS S3 connection status from stored settings.
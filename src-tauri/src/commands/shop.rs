@@ -63,7 +63,7 @@ pub async fn update_shop_settings(
     .await
 }
 
-/// Uploads shop logo to S3 and persists cloud URL.
+/// Uploads shop logo to the configured storage and persists cloud URL.
 #[tauri::command]
 #[instrument(skip(state, app))]
 pub async fn upload_shop_logo_to_s3(
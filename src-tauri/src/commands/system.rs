@@ -18,22 +18,27 @@ pub async fn reset_app_data(
     system::reset_app_data(state.inner().clone(), &app).await
 }
 
-/// Copies the sqlite database file to backup destination.
+/// Copies a verified snapshot of the sqlite database to backup destination.
 #[tauri::command]
-#[instrument(skip(app))]
-pub async fn backup_database(app: AppHandle, dest_path: String) -> Result<u64, AppError> {
-    system::backup_database(&app, dest_path).await
+#[instrument(skip(state, app))]
+pub async fn backup_database(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    dest_path: String,
+) -> Result<u64, AppError> {
+    system::backup_database(state.inner().clone(), &app, dest_path).await
 }
 
-/// Restores sqlite database file from source path.
+/// Restores sqlite database file or backup archive from source path.
 #[tauri::command]
-#[instrument(skip(state, app))]
+#[instrument(skip(state, app, passphrase))]
 pub async fn restore_database(
     app: AppHandle,
     state: State<'_, Arc<AppState>>,
     restore_path: String,
+    passphrase: Option<String>,
 ) -> Result<(), AppError> {
-    system::restore_database(state.inner().clone(), &app, restore_path).await
+    system::restore_database(state.inner().clone(), &app, restore_path, passphrase).await
 }
 
 /// Returns sqlite metadata and table row counts.
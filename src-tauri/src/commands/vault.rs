@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tracing::instrument;
+
+use crate::error::AppError;
+use crate::services::vault;
+use crate::state::AppState;
+
+pub use crate::services::vault::{VaultMode, VaultStatus};
+
+/// Reports how stored secrets are protected and whether they are unlocked.
+#[tauri::command]
+#[instrument(skip(app))]
+pub fn get_vault_status(app: AppHandle) -> Result<VaultStatus, AppError> {
+    vault::get_vault_status(&app)
+}
+
+/// Unlocks stored secrets with the master password for this session.
+#[tauri::command]
+#[instrument(skip(app, state, master_password))]
+pub async fn unlock_vault(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    master_password: String,
+) -> Result<VaultStatus, AppError> {
+    vault::unlock_vault(state.inner().clone(), &app, master_password).await
+}
+
+/// Locks stored secrets until the next unlock.
+#[tauri::command]
+#[instrument(skip(app))]
+pub fn lock_vault(app: AppHandle) -> Result<VaultStatus, AppError> {
+    vault::lock_vault(&app)
+}
+
+/// Switches stored secrets between the machine key and the master password.
+#[tauri::command]
+#[instrument(skip(app, state, master_password))]
+pub async fn set_vault_mode(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    mode: VaultMode,
+    master_password: String,
+) -> Result<VaultStatus, AppError> {
+    vault::set_vault_mode(state.inner().clone(), &app, mode, master_password).await
+}
+
+/// Replaces a vault that can no longer be opened, clearing its secrets.
+#[tauri::command]
+#[instrument(skip(app, state, master_password))]
+pub async fn reset_vault(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    master_password: String,
+) -> Result<VaultStatus, AppError> {
+    vault::reset_vault(state.inner().clone(), &app, master_password).await
+}
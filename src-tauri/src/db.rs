@@ -30,6 +30,91 @@ pub const ORDER_WITH_CUSTOMER_SELECT: &str = r#"
 
 pub const ORDER_WITH_CUSTOMER_GROUP_BY: &str = " GROUP BY o.id ";
 
+/// WHERE/ORDER BY fragments shared by a list endpoint and its export, so both
+/// apply identical filters.
+#[derive(Debug, Clone, Default)]
+pub struct ListQuery {
+    pub where_clause: String,
+    pub order_clause: String,
+    pub params: Vec<sea_orm::Value>,
+    /// Sort key for cursor pagination; `None` means the list only pages by offset.
+    pub sort: Option<ListSort>,
+}
+
+/// The sort of a list in a form keyset pagination can seek on.
+#[derive(Debug, Clone)]
+pub struct ListSort {
+    /// Column and direction, stored in cursors so they can't be replayed on another sort.
+    pub key: String,
+    /// Non-null sort expression, e.g. `COALESCE(o.order_id, '')`.
+    pub expr: String,
+    /// Whether `expr` is numeric rather than text.
+    pub numeric: bool,
+    pub descending: bool,
+    /// Unique column that orders rows with equal sort values, e.g. `o.id`.
+    pub tiebreaker: &'static str,
+}
+
+impl ListSort {
+    fn new(
+        column: &str,
+        expr: String,
+        numeric: bool,
+        descending: bool,
+        tiebreaker: &'static str,
+    ) -> Self {
+        let direction = if descending { "desc" } else { "asc" };
+        Self {
+            key: format!("{column} {direction}"),
+            expr,
+            numeric,
+            descending,
+            tiebreaker,
+        }
+    }
+
+    pub fn text(column: &str, descending: bool, tiebreaker: &'static str) -> Self {
+        Self::new(
+            column,
+            format!("COALESCE({column}, '')"),
+            false,
+            descending,
+            tiebreaker,
+        )
+    }
+
+    pub fn numeric(column: &str, descending: bool, tiebreaker: &'static str) -> Self {
+        Self::new(
+            column,
+            format!("COALESCE({column}, 0.0)"),
+            true,
+            descending,
+            tiebreaker,
+        )
+    }
+}
+
+impl ListQuery {
+    pub fn new(conditions: Vec<String>, params: Vec<sea_orm::Value>, order_clause: String) -> Self {
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        Self {
+            where_clause,
+            order_clause,
+            params,
+            sort: None,
+        }
+    }
+
+    pub fn with_sort(mut self, sort: ListSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+}
+
 pub fn copy_logo_to_app_data(app: &AppHandle, logo_file_path: &str) -> AppResult<Option<String>> {
     if logo_file_path.is_empty() {
         return Ok(None);
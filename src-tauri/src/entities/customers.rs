@@ -9,6 +9,7 @@ pub struct Model {
     pub customer_id: Option<String>,
     pub name: String,
     pub phone: Option<String>,
+    pub phone_e164: Option<String>,
     pub address: Option<String>,
     pub city: Option<String>,
     pub social_media_url: Option<String>,
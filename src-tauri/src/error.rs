@@ -21,12 +21,20 @@ pub enum AppError {
     Tauri(#[from] tauri::Error),
     #[error("Sync is not configured or enabled.")]
     SyncConfigNotFound,
+    #[error("Stored secrets are locked. Unlock them with the master password.")]
+    VaultLocked,
     #[error("Invalid input: {0}")]
     InvalidInput(String),
     #[error("Not found: {0}")]
     NotFound(String),
     #[error("Internal error: {0}")]
     Internal(String),
+    #[error("Printer not found: {0}")]
+    PrinterNotFound(String),
+    #[error("Print failed: {0}")]
+    PrintFailed(String),
+    #[error("Possible duplicate of customer {0}")]
+    DuplicateCustomer(String),
     #[error("{operation} failed with status {status}: {body}")]
     SupabaseRequestFailed {
         operation: &'static str,
@@ -66,6 +74,11 @@ impl AppError {
         Self::Internal(message.into())
     }
 
+    /// Creates a print failure error from a displayable message.
+    pub fn print_failed(message: impl Into<String>) -> Self {
+        Self::PrintFailed(message.into())
+    }
+
     /// Creates a Supabase HTTP failure error and trims oversized response bodies.
     pub fn supabase_request_failed(operation: &'static str, status: u16, body: String) -> Self {
         Self::SupabaseRequestFailed {
@@ -93,9 +106,13 @@ impl AppError {
             Self::Bcrypt(_) => "bcrypt_error",
             Self::Tauri(_) => "tauri_error",
             Self::SyncConfigNotFound => "sync_config_not_found",
+            Self::VaultLocked => "vault_locked",
             Self::InvalidInput(_) => "invalid_input",
             Self::NotFound(_) => "not_found",
             Self::Internal(_) => "internal_error",
+            Self::PrinterNotFound(_) => "printer_not_found",
+            Self::PrintFailed(_) => "print_failed",
+            Self::DuplicateCustomer(_) => "duplicate_customer",
             Self::SupabaseRequestFailed { .. } => "supabase_request_failed",
             Self::InvalidApiResponse { .. } => "invalid_api_response",
         }
@@ -10,9 +10,7 @@ mod state;
 pub mod sync;
 
 use std::fs;
-use std::process::Command;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use reqwest::Client;
 use sea_orm::SqlxSqliteConnector;
@@ -22,23 +20,66 @@ use tauri::Manager;
 use tokio::sync::Mutex;
 
 use crate::commands::account::get_account_summary;
+use crate::commands::attachment::{
+    add_attachment, delete_attachment, get_attachments, upload_attachment_to_s3,
+};
 use crate::commands::auth::{check_is_onboarded, login_user, register_user};
+use crate::commands::backup::{
+    create_backup_target, delete_backup_target, get_backup_encryption, get_backup_runs,
+    get_backup_targets, list_storage_backups, list_target_backups, restore_from_backup_target,
+    run_backup_now, set_backup_encryption, test_backup_target, trigger_storage_backup,
+    update_backup_target,
+};
 use crate::commands::customer::{
-    create_customer, delete_customer, get_customer, get_customers, get_customers_paginated,
-    update_customer,
+    create_customer, delete_customer, get_customer, get_customers, get_customers_page,
+    get_customers_paginated, update_customer,
+};
+use crate::commands::customer_dedupe::{
+    check_customer_duplicates, find_duplicate_customers, get_customer_merges, merge_customers,
 };
 use crate::commands::drive::{
     disconnect_google_drive, get_drive_connection_status, start_google_oauth, trigger_drive_backup,
 };
 use crate::commands::expense::{
-    create_expense, delete_expense, get_expense, get_expenses, get_expenses_paginated,
-    update_expense,
+    create_expense, delete_expense, get_expense, get_expenses, get_expenses_page,
+    get_expenses_paginated, update_expense,
+};
+use crate::commands::expense_category::{
+    create_expense_category, create_payment_method, delete_expense_category, delete_payment_method,
+    get_budget_report, get_expense_categories, get_payment_methods, update_expense_category,
+    update_payment_method,
 };
+use crate::commands::export::{export_customers, export_expenses, export_orders};
+use crate::commands::import::{
+    import_customers, import_order_items, import_orders, preview_import,
+};
+use crate::commands::job_run::get_job_runs;
+use crate::commands::labels::generate_parcel_labels;
 use crate::commands::order::{
     create_order, delete_order, get_customer_orders, get_dashboard_detail_records,
-    get_dashboard_stats, get_order, get_orders, get_orders_for_export, get_orders_paginated,
-    update_order,
+    get_dashboard_stats, get_order, get_orders, get_orders_for_export, get_orders_page,
+    get_orders_paginated, update_order,
+};
+use crate::commands::printer::{
+    get_print_job_status, list_printers, print_invoice_direct, test_print, validate_printer,
+};
+use crate::commands::product_photo::{
+    add_order_item_photo_from_clipboard, add_order_item_photo_from_file,
+    add_order_item_photo_from_url, delete_order_item_photo, get_order_item_photos,
+    get_order_photos, upload_order_item_photo_to_s3,
+};
+use crate::commands::receipt::print_order_receipt;
+use crate::commands::recurring_expense::{
+    create_recurring_expense, delete_recurring_expense, generate_recurring_expenses,
+    get_recurring_expenses, get_upcoming_recurring_expenses, update_recurring_expense,
 };
+use crate::commands::saved_view::{
+    create_saved_view, delete_saved_view, get_saved_views, run_saved_view, update_saved_view,
+};
+use crate::commands::scan::{
+    close_scan_session, get_scan_session, scan_in_session, scan_order_code, start_scan_session,
+};
+use crate::commands::search::{global_search, rebuild_search_index};
 use crate::commands::settings::{
     get_app_settings, get_aws_s3_connection_status, test_aws_s3_connection, update_app_settings,
     AppSettings,
@@ -52,8 +93,11 @@ use crate::commands::staff::{
 use crate::commands::system::{
     backup_database, get_db_status, reset_app_data, reset_table_sequence, restore_database,
 };
+use crate::commands::vault::{
+    get_vault_status, lock_vault, reset_vault, set_vault_mode, unlock_vault,
+};
 use crate::migration::Migrator;
-use crate::scheduler::{reload_scheduler, setup_scheduler};
+use crate::scheduler::{get_next_backup_run, reload_scheduler, setup_scheduler};
 use crate::state::{AppDb, AppState};
 use crate::sync::{
     apply_remote_changes, clean_sync_data, clear_synced_items, fetch_remote_changes,
@@ -69,87 +113,6 @@ fn print_window(window: tauri::WebviewWindow) -> tauri::Result<()> {
     Ok(())
 }
 
-#[tauri::command]
-fn print_invoice_direct(
-    app: tauri::AppHandle,
-    bytes: Vec<u8>,
-    printer_name: Option<String>,
-) -> Result<(), String> {
-    if bytes.is_empty() {
-        return Err("Invoice image is empty".to_string());
-    }
-
-    let temp_dir = app
-        .path()
-        .app_cache_dir()
-        .unwrap_or_else(|_| std::env::temp_dir());
-    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| e.to_string())?
-        .as_millis();
-    let temp_path = temp_dir.join(format!("invoice_{timestamp}.png"));
-    fs::write(&temp_path, bytes).map_err(|e| e.to_string())?;
-
-    let sanitized_printer = printer_name
-        .map(|name| name.trim().to_string())
-        .filter(|name| !name.is_empty());
-
-    let print_result = (|| -> Result<(), String> {
-        #[cfg(any(target_os = "macos", target_os = "linux"))]
-        {
-            let mut command = Command::new("lp");
-            if let Some(printer) = sanitized_printer {
-                command.arg("-d").arg(printer);
-            }
-            let output = command
-                .arg(&temp_path)
-                .output()
-                .map_err(|e| e.to_string())?;
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                let details = if !stderr.is_empty() { stderr } else { stdout };
-                return Err(if details.is_empty() {
-                    "Failed to print invoice".to_string()
-                } else {
-                    details
-                });
-            }
-            Ok(())
-        }
-
-        #[cfg(target_os = "windows")]
-        {
-            let printer = sanitized_printer.ok_or_else(|| {
-                "Please set a printer name in Settings before using direct print.".to_string()
-            })?;
-
-            let output = Command::new("mspaint")
-                .arg("/pt")
-                .arg(&temp_path)
-                .arg(printer)
-                .output()
-                .map_err(|e| e.to_string())?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                let details = if !stderr.is_empty() { stderr } else { stdout };
-                return Err(if details.is_empty() {
-                    "Failed to print invoice".to_string()
-                } else {
-                    details
-                });
-            }
-            Ok(())
-        }
-    })();
-
-    let _ = fs::remove_file(&temp_path);
-    print_result
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -173,9 +136,15 @@ pub fn run() {
                 fs::write(&settings_path, settings_json).expect("Failed to write settings.json");
             }
 
+            // Load the key that encrypts stored credentials
+            if let Err(err) = services::vault::initialize_vault(app.handle()) {
+                tracing::warn!("Failed to initialize the secrets vault: {err}");
+            }
+
             let db_path = app_data_dir.join("shop.db");
             let db_url = format!("sqlite:{}?mode=rwc", db_path.to_string_lossy());
 
+            let phone_country = services::phone::default_phone_country(app.handle());
             let (db, shared_pool) = tauri::async_runtime::block_on(async {
                 let pool = SqlitePoolOptions::new()
                     .max_connections(5)
@@ -190,6 +159,13 @@ pub fn run() {
                     .await
                     .expect("Failed to run database migrations");
 
+                if let Err(err) = services::phone::backfill_phone_e164(&db, &phone_country).await {
+                    tracing::warn!("Failed to backfill customer phone numbers: {err}");
+                }
+                if let Err(err) = services::expense_category::register_expense_names(&db).await {
+                    tracing::warn!("Failed to register expense categories in use: {err}");
+                }
+
                 let shared_pool = Arc::new(Mutex::new(pool));
                 (db, shared_pool)
             });
@@ -204,6 +180,39 @@ pub fn run() {
                 tauri::async_runtime::block_on(async { setup_scheduler(app_handle).await });
             app.manage(scheduler_state);
 
+            // Remove files left behind by records deleted elsewhere
+            let cleanup_state = app_state.clone();
+            let cleanup_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = services::attachment::cleanup_orphaned_attachments(
+                    cleanup_state.clone(),
+                    &cleanup_handle,
+                )
+                .await
+                {
+                    tracing::warn!("Failed to clean up orphaned attachments: {err}");
+                }
+                if let Err(err) = services::product_photo::cleanup_orphaned_order_item_photos(
+                    cleanup_state.clone(),
+                    &cleanup_handle,
+                )
+                .await
+                {
+                    tracing::warn!("Failed to clean up orphaned product photos: {err}");
+                }
+                // Encrypt credentials saved before the vault existed
+                let vault_unlocked = services::vault::get_vault_status(&cleanup_handle)
+                    .is_ok_and(|status| status.unlocked);
+                if vault_unlocked {
+                    if let Err(err) =
+                        services::vault::seal_plaintext_secrets(cleanup_state, &cleanup_handle)
+                            .await
+                    {
+                        tracing::warn!("Failed to encrypt plaintext secrets: {err}");
+                    }
+                }
+            });
+
             // Start the sync background loop
             start_sync_loop(app.handle().clone());
 
@@ -230,19 +239,52 @@ pub fn run() {
             create_customer,
             get_customers,
             get_customers_paginated,
+            get_customers_page,
             get_customer,
             update_customer,
             delete_customer,
+            check_customer_duplicates,
+            find_duplicate_customers,
+            merge_customers,
+            get_customer_merges,
             create_expense,
             get_expenses,
             get_expenses_paginated,
+            get_expenses_page,
             get_expense,
             update_expense,
             delete_expense,
+            get_expense_categories,
+            create_expense_category,
+            update_expense_category,
+            delete_expense_category,
+            get_payment_methods,
+            create_payment_method,
+            update_payment_method,
+            delete_payment_method,
+            get_budget_report,
+            get_recurring_expenses,
+            create_recurring_expense,
+            update_recurring_expense,
+            delete_recurring_expense,
+            get_upcoming_recurring_expenses,
+            generate_recurring_expenses,
+            add_attachment,
+            get_attachments,
+            upload_attachment_to_s3,
+            delete_attachment,
+            add_order_item_photo_from_file,
+            add_order_item_photo_from_clipboard,
+            add_order_item_photo_from_url,
+            get_order_item_photos,
+            get_order_photos,
+            upload_order_item_photo_to_s3,
+            delete_order_item_photo,
             create_order,
             get_orders,
             get_orders_for_export,
             get_orders_paginated,
+            get_orders_page,
             get_order,
             get_customer_orders,
             update_order,
@@ -254,13 +296,58 @@ pub fn run() {
             update_app_settings,
             test_aws_s3_connection,
             get_aws_s3_connection_status,
+            get_vault_status,
+            unlock_vault,
+            lock_vault,
+            set_vault_mode,
+            reset_vault,
             print_window,
             print_invoice_direct,
+            list_printers,
+            validate_printer,
+            get_print_job_status,
+            test_print,
+            generate_parcel_labels,
+            scan_order_code,
+            start_scan_session,
+            scan_in_session,
+            get_scan_session,
+            close_scan_session,
+            export_orders,
+            export_customers,
+            export_expenses,
+            preview_import,
+            import_customers,
+            import_orders,
+            import_order_items,
+            global_search,
+            rebuild_search_index,
+            create_saved_view,
+            get_saved_views,
+            update_saved_view,
+            delete_saved_view,
+            run_saved_view,
+            print_order_receipt,
             start_google_oauth,
             get_drive_connection_status,
             disconnect_google_drive,
             trigger_drive_backup,
+            trigger_storage_backup,
+            list_storage_backups,
+            get_backup_targets,
+            create_backup_target,
+            update_backup_target,
+            delete_backup_target,
+            test_backup_target,
+            run_backup_now,
+            get_backup_runs,
+            list_target_backups,
+            restore_from_backup_target,
+            get_backup_encryption,
+            set_backup_encryption,
             reload_scheduler,
+            get_next_backup_run,
+            get_job_runs,
             save_sync_config,
             get_sync_config,
             test_sync_connection,
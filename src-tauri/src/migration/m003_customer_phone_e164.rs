@@ -0,0 +1,53 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m003_customer_phone_e164"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Values are filled in on startup by `phone::backfill_phone_e164`, which
+        // needs the configured default country.
+        let exists = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT 1 FROM pragma_table_info('customers') WHERE name = 'phone_e164' LIMIT 1",
+            ))
+            .await?
+            .is_some();
+        if !exists {
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "ALTER TABLE customers ADD COLUMN phone_e164 TEXT",
+            ))
+            .await?;
+        }
+
+        db.execute_unprepared(include_str!("../../migrations/003_customer_phone_e164.sql"))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "DROP INDEX IF EXISTS idx_customers_phone_e164",
+        ))
+        .await?;
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "ALTER TABLE customers DROP COLUMN phone_e164",
+        ))
+        .await?;
+        Ok(())
+    }
+}
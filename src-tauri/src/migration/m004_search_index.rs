@@ -0,0 +1,58 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m004_search_index"
+    }
+}
+
+const TRIGGERS: &[&str] = &[
+    "search_customers_ai",
+    "search_customers_au",
+    "search_customers_ad",
+    "search_orders_ai",
+    "search_orders_au",
+    "search_orders_ad",
+    "search_order_items_ai",
+    "search_order_items_au",
+    "search_order_items_ad",
+    "search_expenses_ai",
+    "search_expenses_au",
+    "search_expenses_ad",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("../../migrations/004_search_index.sql"))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for trigger in TRIGGERS {
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                format!("DROP TRIGGER IF EXISTS {trigger}"),
+            ))
+            .await?;
+        }
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "DROP VIEW IF EXISTS search_documents",
+        ))
+        .await?;
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "DROP TABLE IF EXISTS search_index",
+        ))
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m006_expense_categories"
+    }
+}
+
+/// The keys the expense form has always offered; seeded first so their
+/// spelling wins over variants typed by hand.
+const DEFAULT_CATEGORIES: &[&str] = &[
+    "operation",
+    "transport",
+    "rent",
+    "salary",
+    "utilities",
+    "marketing",
+    "other",
+];
+const DEFAULT_PAYMENT_METHODS: &[&str] =
+    &["cash", "bank_transfer", "mobile_wallet", "credit", "other"];
+
+/// Registers the defaults, then every value already used on expenses, most
+/// used spelling first. `INSERT OR IGNORE` on the NOCASE index folds case variants.
+async fn seed<C: ConnectionTrait>(
+    db: &C,
+    table: &str,
+    column: &str,
+    defaults: &[&str],
+) -> Result<(), DbErr> {
+    let used = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!(
+                "SELECT TRIM({column}) AS name, COUNT(*) AS uses FROM expenses \
+                 WHERE deleted_at IS NULL AND TRIM(COALESCE({column}, '')) <> '' \
+                 GROUP BY TRIM({column}) ORDER BY uses DESC, name ASC"
+            ),
+        ))
+        .await?
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let names = defaults.iter().map(|&name| String::from(name)).chain(used);
+    for name in names {
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!("INSERT OR IGNORE INTO {table} (id, name, updated_at) VALUES (?, ?, datetime('now'))"),
+            [Uuid::new_v4().to_string().into(), name.into()],
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared(include_str!("../../migrations/006_expense_categories.sql"))
+            .await?;
+        seed(db, "expense_categories", "category", DEFAULT_CATEGORIES).await?;
+        seed(
+            db,
+            "payment_methods",
+            "payment_method",
+            DEFAULT_PAYMENT_METHODS,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for table in ["payment_methods", "expense_categories"] {
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                format!("DROP TABLE IF EXISTS {table}"),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m007_recurring_expenses"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("../../migrations/007_recurring_expenses.sql"))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for table in ["recurring_expense_runs", "recurring_expenses"] {
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                format!("DROP TABLE IF EXISTS {table}"),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+}
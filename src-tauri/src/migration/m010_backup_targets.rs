@@ -0,0 +1,33 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m010_backup_targets"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("../../migrations/010_backup_targets.sql"))
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for table in ["backup_runs", "backup_targets"] {
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                format!("DROP TABLE IF EXISTS {table}"),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m011_backup_retention"
+    }
+}
+
+/// Retention per backup target: the newest backup of each of the last
+/// `keep_daily` days, `keep_weekly` weeks and `keep_monthly` months is kept.
+/// All NULL keeps every backup. Runs record how many old backups they pruned.
+const COLUMNS: [(&str, &str); 4] = [
+    ("backup_targets", "keep_daily"),
+    ("backup_targets", "keep_weekly"),
+    ("backup_targets", "keep_monthly"),
+    ("backup_runs", "pruned_count"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        // SQLite has no `ADD COLUMN IF NOT EXISTS`, so each column is checked
+        // first, as in m003. There is nothing else to run, hence no SQL file.
+        for (table, column) in COLUMNS {
+            let exists = db
+                .query_one(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    format!(
+                        "SELECT 1 FROM pragma_table_info('{table}') WHERE name = '{column}' LIMIT 1"
+                    ),
+                ))
+                .await?
+                .is_some();
+            if !exists {
+                db.execute(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    format!("ALTER TABLE {table} ADD COLUMN {column} INTEGER"),
+                ))
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for (table, column) in COLUMNS {
+            db.execute(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                format!("ALTER TABLE {table} DROP COLUMN {column}"),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+}
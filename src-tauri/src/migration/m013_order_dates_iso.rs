@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+use crate::services::order::normalize_order_dates;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m013_order_dates_iso"
+    }
+}
+
+/// Order dates are free-form text; rewrites the layouts `parse_flexible_date`
+/// understands (dd/mm/yyyy, dd.mm.yyyy, ...) to `YYYY-MM-DD` so list filters
+/// can compare them in SQL. Needs the Rust parser, hence no SQL file.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        normalize_order_dates(manager.get_connection())
+            .await
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        Ok(())
+    }
+
+    /// The original layouts are not kept; ISO dates read fine everywhere.
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
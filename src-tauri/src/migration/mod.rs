@@ -1,12 +1,38 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m001_initial;
+mod m002_customer_merges;
+mod m003_customer_phone_e164;
+mod m004_search_index;
+mod m005_saved_views;
+mod m006_expense_categories;
+mod m007_recurring_expenses;
+mod m008_attachments;
+mod m009_order_item_photos;
+mod m010_backup_targets;
+mod m011_backup_retention;
+mod m012_job_runs;
+mod m013_order_dates_iso;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m001_initial::Migration)]
+        vec![
+            Box::new(m001_initial::Migration),
+            Box::new(m002_customer_merges::Migration),
+            Box::new(m003_customer_phone_e164::Migration),
+            Box::new(m004_search_index::Migration),
+            Box::new(m005_saved_views::Migration),
+            Box::new(m006_expense_categories::Migration),
+            Box::new(m007_recurring_expenses::Migration),
+            Box::new(m008_attachments::Migration),
+            Box::new(m009_order_item_photos::Migration),
+            Box::new(m010_backup_targets::Migration),
+            Box::new(m011_backup_retention::Migration),
+            Box::new(m012_job_runs::Migration),
+            Box::new(m013_order_dates_iso::Migration),
+        ]
     }
 }
@@ -26,7 +26,7 @@ pub struct User {
     pub created_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromQueryResult)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromQueryResult)]
 pub struct Customer {
     pub id: String,
     pub customer_id: Option<String>,
@@ -127,8 +127,88 @@ pub struct OrderItem {
     pub deleted_at: Option<String>,
 }
 
+/// Filters accepted by the paginated order list and the order export.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OrderListFilter {
+    pub search_key: Option<String>,
+    pub search_term: Option<String>,
+    pub status_filter: Option<String>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    pub conditions: Option<OrderConditions>,
+}
+
+/// Structured order conditions, all combined with AND. Empty lists and unset
+/// fields are ignored.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OrderConditions {
+    pub statuses: Option<Vec<String>>,
+    pub order_from: Option<Vec<String>>,
+    pub cities: Option<Vec<String>>,
+    pub customer_ids: Option<Vec<String>>,
+    pub date_ranges: Option<Vec<DateRangeCondition>>,
+    pub paid: Option<OrderFeeFlags>,
+    pub by_shop: Option<OrderFeeFlags>,
+    pub exclude_cargo_fee: Option<bool>,
+    /// Sum of item price times quantity.
+    pub total_price: Option<NumberRange>,
+    pub total_weight: Option<NumberRange>,
+    pub has_items: Option<bool>,
+}
+
+/// Inclusive date range on one of the order date columns.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DateRangeCondition {
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NumberRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Per-fee flag conditions, used for both the `*_paid` and `*_by_shop` columns.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OrderFeeFlags {
+    pub shipping_fee: Option<bool>,
+    pub delivery_fee: Option<bool>,
+    pub cargo_fee: Option<bool>,
+    pub service_fee: Option<bool>,
+}
+
+/// Filters accepted by the paginated customer list and the customer export.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CustomerListFilter {
+    pub search_key: Option<String>,
+    pub search_term: Option<String>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
+
+/// Filters accepted by the paginated expense list and the expense export.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExpenseListFilter {
+    pub search_key: Option<String>,
+    pub search_term: Option<String>,
+    pub category_filter: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderItemPayload {
+    /// Id of the item being edited, so its photos carry over to the
+    /// re-inserted row. `None` for new items.
+    pub id: Option<String>,
     pub product_url: Option<String>,
     pub product_qty: Option<i64>,
     pub price: Option<f64>,
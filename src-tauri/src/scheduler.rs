@@ -1,82 +1,423 @@
-use crate::commands::drive::perform_drive_backup;
 use crate::commands::settings::get_app_settings;
+use crate::error::{AppError, AppResult};
+use crate::services::backup::{run_backups, BackupTrigger};
+use crate::services::job_run::{
+    emit_catch_up, fail_interrupted_runs, last_success, record_job_run, JobTrigger, ScheduledJob,
+};
+use crate::services::recurring_expense::generate_recurring_expenses;
+use crate::services::settings::AppSettings;
+use crate::state::AppState;
+use chrono::{DateTime, Local, NaiveTime, SubsecRound, Timelike, Utc};
+use croner::parser::{CronParser, Seconds};
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
-// The current scheduled job id
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+/// Later days are missing from some months, which would skip their backup.
+const MAX_BACKUP_MONTH_DAY: u32 = 28;
+
+// The currently scheduled backup job ids, one per cron expression
 pub struct SchedulerState {
     pub sched: JobScheduler,
-    pub job_id: Option<uuid::Uuid>,
+    pub job_ids: Vec<uuid::Uuid>,
 }
 
 pub async fn setup_scheduler(app: AppHandle) -> Arc<Mutex<SchedulerState>> {
+    // Before any job starts, so only runs of a previous session are affected.
+    if let Some(app_state) = app.try_state::<Arc<AppState>>() {
+        let db = app_state.db.lock().await.clone();
+        if let Err(e) = fail_interrupted_runs(&db).await {
+            tracing::warn!("Failed to close interrupted job runs: {e}");
+        }
+    }
+
     let sched = JobScheduler::new().await.unwrap();
     sched.start().await.unwrap();
     let state = Arc::new(Mutex::new(SchedulerState {
         sched,
-        job_id: None,
+        job_ids: Vec::new(),
     }));
 
-    update_scheduler(&app, &state).await;
+    if let Err(e) = update_scheduler(&app, &state).await {
+        tracing::warn!("Backups are not scheduled: {e}");
+    }
+    schedule_recurring_expenses(&app, &state).await;
+
+    let startup_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run_recurring_expenses(&startup_app, JobTrigger::CatchUp).await;
+        catch_up_missed_backup(&startup_app).await;
+    });
     state
 }
 
-pub async fn update_scheduler(app: &AppHandle, state: &Arc<Mutex<SchedulerState>>) {
-    let settings = get_app_settings(app.clone()).unwrap_or_default();
-    let mut state_lock = state.lock().await;
+async fn run_recurring_expenses(app: &AppHandle, trigger: JobTrigger) {
+    let Some(app_state) = app.try_state::<Arc<AppState>>() else {
+        return;
+    };
+    let state = app_state.inner().clone();
+    let run = async {
+        let posted = generate_recurring_expenses(state.clone(), app).await?;
+        if posted > 0 {
+            tracing::info!("Posted {posted} recurring expense(s)");
+        }
+        Ok(())
+    };
+    match record_job_run(&state, app, ScheduledJob::RecurringExpenses, trigger, run).await {
+        Ok(job_run) => {
+            if let Some(error) = job_run.error {
+                tracing::warn!("Failed to post recurring expenses: {error}");
+            }
+        }
+        Err(e) => tracing::warn!("Failed to record recurring expense run: {e}"),
+    }
+}
 
-    if let Some(id) = state_lock.job_id {
-        let _ = state_lock.sched.remove(&id).await;
-        state_lock.job_id = None;
+/// Backs up to every enabled target; each outcome is kept in `backup_runs`.
+/// Fails when any target failed.
+async fn perform_scheduled_backups(state: Arc<AppState>, app: &AppHandle) -> AppResult<()> {
+    let runs = run_backups(state, app, BackupTrigger::Scheduled, None).await?;
+    let failed: Vec<String> = runs
+        .iter()
+        .filter(|run| run.status == "failed")
+        .map(|run| {
+            format!(
+                "{}: {}",
+                run.target_name.as_deref().unwrap_or(&run.target_id),
+                run.error.as_deref().unwrap_or("failed")
+            )
+        })
+        .collect();
+    tracing::info!(
+        "Scheduled backup finished: {} target(s), {} failed",
+        runs.len(),
+        failed.len()
+    );
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::internal(format!(
+            "Backup failed on {}",
+            failed.join("; ")
+        )))
     }
+}
 
-    if !settings.auto_backup
-        || settings.backup_frequency == "never"
-        || settings.backup_frequency.is_empty()
-    {
+async fn run_scheduled_backups(app: &AppHandle, trigger: JobTrigger) {
+    let Some(app_state) = app.try_state::<Arc<AppState>>() else {
         return;
+    };
+    let state = app_state.inner().clone();
+    let run = perform_scheduled_backups(state.clone(), app);
+    match record_job_run(&state, app, ScheduledJob::Backup, trigger, run).await {
+        Ok(job_run) => {
+            if let Some(error) = job_run.error {
+                tracing::warn!("Scheduled backup did not succeed: {error}");
+            }
+        }
+        Err(e) => tracing::warn!("Failed to record scheduled backup: {e}"),
     }
+}
+
+/// The latest time a backup was due, up to now. `None` when backups are off
+/// or the schedule is invalid.
+fn last_due_backup(settings: &AppSettings) -> Option<DateTime<Utc>> {
+    let parser = CronParser::builder()
+        .seconds(Seconds::Required)
+        .dom_and_dow(true)
+        .build();
+    let now = Local::now();
+    backup_cron_expressions(settings)
+        .ok()?
+        .iter()
+        .filter_map(|cron_expr| parser.parse(cron_expr).ok())
+        .filter_map(|cron| cron.find_previous_occurrence(&now, true).ok())
+        .max()
+        // Run times are recorded to the second.
+        .map(|time| time.with_timezone(&Utc).trunc_subsecs(0))
+}
 
-    let time_parts: Vec<&str> = settings.backup_time.split(':').collect();
-    if time_parts.len() != 2 {
+/// Runs a backup on startup when one was due after the last successful
+/// scheduled backup, e.g. while the computer was asleep or the app closed,
+/// and emits `JOB_CATCH_UP_EVENT` first.
+async fn catch_up_missed_backup(app: &AppHandle) {
+    let Some(app_state) = app.try_state::<Arc<AppState>>() else {
         return;
+    };
+    let settings = get_app_settings(app.clone()).unwrap_or_default();
+    let Some(missed_at) = last_due_backup(&settings) else {
+        return;
+    };
+    let db = app_state.db.lock().await.clone();
+    match last_success(&db, ScheduledJob::Backup).await {
+        Ok(Some(succeeded_at)) if succeeded_at >= missed_at => return,
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("Failed to check for a missed backup: {e}");
+            return;
+        }
     }
-    let hour = time_parts[0];
-    let minute = time_parts[1];
 
-    let cron_expr = match settings.backup_frequency.as_str() {
-        "daily" => format!("0 {} {} * * *", minute, hour),
-        "weekly" => format!("0 {} {} * * 0", minute, hour), // Sunday
-        "monthly" => format!("0 {} {} 1 * *", minute, hour), // 1st of month
-        _ => return,
-    };
+    tracing::info!("Catching up on the backup due at {missed_at}");
+    emit_catch_up(app, ScheduledJob::Backup, missed_at);
+    run_scheduled_backups(app, JobTrigger::CatchUp).await;
+}
 
+/// Posts due recurring expenses hourly; `setup_scheduler` also runs it on
+/// startup. Generation is idempotent, so overlapping runs are harmless.
+async fn schedule_recurring_expenses(app: &AppHandle, state: &Arc<Mutex<SchedulerState>>) {
     let app_clone = app.clone();
-    match Job::new_async(cron_expr.as_str(), move |_uuid, mut _l| {
+    match Job::new_async("0 5 * * * *", move |_uuid, mut _l| {
         let app_task = app_clone.clone();
         Box::pin(async move {
-            println!("Running scheduled drive backup...");
-            let _ = perform_drive_backup(&app_task).await;
+            run_recurring_expenses(&app_task, JobTrigger::Scheduled).await;
         })
     }) {
         Ok(job) => {
-            if let Ok(id) = state_lock.sched.add(job).await {
-                state_lock.job_id = Some(id);
+            if let Err(e) = state.lock().await.sched.add(job).await {
+                tracing::warn!("Failed to schedule recurring expense job: {e}");
             }
         }
         Err(e) => {
-            eprintln!("Failed to schedule backup job: {}", e);
+            tracing::warn!("Failed to schedule recurring expense job: {e}");
         }
     }
 }
 
+fn backup_job(app: &AppHandle, cron_expr: &str) -> AppResult<Job> {
+    let app_clone = app.clone();
+    // Times in the backup settings are local, not UTC.
+    Job::new_async_tz(cron_expr, Local, move |_uuid, mut _l| {
+        let app_task = app_clone.clone();
+        Box::pin(async move {
+            run_scheduled_backups(&app_task, JobTrigger::Scheduled).await;
+        })
+    })
+    .map_err(|_| AppError::invalid_input(format!("Invalid backup schedule: {cron_expr}")))
+}
+
+fn parse_backup_times(backup_time: &str) -> AppResult<Vec<NaiveTime>> {
+    let mut times = backup_time
+        .split(',')
+        .map(str::trim)
+        .filter(|time| !time.is_empty())
+        .map(|time| {
+            NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+                AppError::invalid_input(format!("Invalid backup time \"{time}\", expected HH:MM"))
+            })
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    if times.is_empty() {
+        return Err(AppError::invalid_input("Choose at least one backup time"));
+    }
+    times.sort();
+    times.dedup();
+    Ok(times)
+}
+
+/// Cron expressions (with seconds) the backup settings ask for; empty when
+/// automatic backups are off. A custom expression may leave out the seconds.
+pub(crate) fn backup_cron_expressions(settings: &AppSettings) -> AppResult<Vec<String>> {
+    if !settings.auto_backup
+        || settings.backup_frequency == "never"
+        || settings.backup_frequency.is_empty()
+    {
+        return Ok(Vec::new());
+    }
+
+    let days = match settings.backup_frequency.as_str() {
+        "custom" => {
+            let cron_expr = settings.backup_cron.trim();
+            let expression = match cron_expr.split_whitespace().count() {
+                5 => format!("0 {cron_expr}"),
+                6 => cron_expr.to_string(),
+                _ => {
+                    return Err(AppError::invalid_input(
+                        "A custom backup schedule needs 5 or 6 cron fields",
+                    ))
+                }
+            };
+            return Ok(vec![expression]);
+        }
+        "daily" => "* *".to_string(),
+        "weekly" => {
+            let weekday = WEEKDAYS
+                .get(settings.backup_weekday as usize)
+                .ok_or_else(|| AppError::invalid_input("Backup weekday must be 0 (Sunday) to 6"))?;
+            format!("* {weekday}")
+        }
+        "monthly" => {
+            if !(1..=MAX_BACKUP_MONTH_DAY).contains(&settings.backup_month_day) {
+                return Err(AppError::invalid_input(format!(
+                    "Backup day of the month must be 1 to {MAX_BACKUP_MONTH_DAY}"
+                )));
+            }
+            format!("{} *", settings.backup_month_day)
+        }
+        other => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown backup frequency: {other}"
+            )))
+        }
+    };
+    Ok(parse_backup_times(&settings.backup_time)?
+        .into_iter()
+        .map(|time| format!("0 {} {} {days} *", time.minute(), time.hour()))
+        .collect())
+}
+
+/// Checks the backup schedule in settings before it is saved, so mistakes
+/// are reported instead of silently turning backups off.
+pub(crate) fn validate_backup_schedule(app: &AppHandle, settings: &AppSettings) -> AppResult<()> {
+    for cron_expr in backup_cron_expressions(settings)? {
+        backup_job(app, &cron_expr)?;
+    }
+    Ok(())
+}
+
+/// Replaces the scheduled backup jobs with the ones the settings ask for.
+/// On an invalid schedule no backups stay scheduled and the error is returned.
+pub async fn update_scheduler(
+    app: &AppHandle,
+    state: &Arc<Mutex<SchedulerState>>,
+) -> AppResult<()> {
+    let settings = get_app_settings(app.clone()).unwrap_or_default();
+    let mut state_lock = state.lock().await;
+
+    for id in std::mem::take(&mut state_lock.job_ids) {
+        let _ = state_lock.sched.remove(&id).await;
+    }
+
+    for cron_expr in backup_cron_expressions(&settings)? {
+        let job = backup_job(app, &cron_expr)?;
+        let id = state_lock
+            .sched
+            .add(job)
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to schedule backup job: {e}")))?;
+        state_lock.job_ids.push(id);
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn reload_scheduler(
     app: AppHandle,
     state: tauri::State<'_, Arc<Mutex<SchedulerState>>>,
-) -> Result<(), String> {
-    update_scheduler(&app, &*state).await;
-    Ok(())
+) -> Result<(), AppError> {
+    update_scheduler(&app, &state).await
+}
+
+/// Returns when the next scheduled backup will run, from the scheduler's own
+/// job state. `None` when no backups are scheduled.
+#[tauri::command]
+pub async fn get_next_backup_run(
+    state: tauri::State<'_, Arc<Mutex<SchedulerState>>>,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let mut state_lock = state.lock().await;
+    let mut ticks = Vec::new();
+    for id in state_lock.job_ids.clone() {
+        ticks.push(
+            state_lock.sched.next_tick_for_job(id).await.map_err(|e| {
+                AppError::internal(format!("Failed to read the backup schedule: {e}"))
+            })?,
+        );
+    }
+    Ok(ticks.into_iter().flatten().min())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(frequency: &str, configure: impl FnOnce(&mut AppSettings)) -> AppSettings {
+        let mut settings = AppSettings {
+            auto_backup: true,
+            backup_frequency: frequency.to_string(),
+            ..AppSettings::default()
+        };
+        configure(&mut settings);
+        settings
+    }
+
+    fn expressions(settings: &AppSettings) -> Vec<String> {
+        backup_cron_expressions(settings).unwrap()
+    }
+
+    fn error(settings: &AppSettings) -> String {
+        backup_cron_expressions(settings).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn daily_times_are_sorted_and_deduplicated() {
+        let daily = settings("daily", |s| {
+            s.backup_time = "09:00, 13:30,09:00".to_string()
+        });
+        assert_eq!(expressions(&daily), ["0 0 9 * * *", "0 30 13 * * *"]);
+    }
+
+    #[test]
+    fn weekly_and_monthly_pin_the_day() {
+        let weekly = settings("weekly", |s| s.backup_weekday = 3);
+        assert_eq!(expressions(&weekly), ["0 0 23 * Wed *"]);
+
+        let monthly = settings("monthly", |s| {
+            s.backup_month_day = 15;
+            s.backup_time = "02:05".to_string();
+        });
+        assert_eq!(expressions(&monthly), ["0 5 2 15 * *"]);
+    }
+
+    #[test]
+    fn custom_expressions_get_seconds_when_left_out() {
+        let five = settings("custom", |s| s.backup_cron = " 30 4 * * Mon ".to_string());
+        assert_eq!(expressions(&five), ["0 30 4 * * Mon"]);
+
+        let six = settings("custom", |s| s.backup_cron = "15 30 4 * * Mon".to_string());
+        assert_eq!(expressions(&six), ["15 30 4 * * Mon"]);
+    }
+
+    #[test]
+    fn backups_turned_off_have_no_schedule() {
+        assert!(expressions(&settings("never", |_| {})).is_empty());
+        assert!(expressions(&settings("", |_| {})).is_empty());
+        assert!(expressions(&settings("daily", |s| s.auto_backup = false)).is_empty());
+    }
+
+    #[test]
+    fn invalid_schedules_are_reported() {
+        let cases = [
+            (
+                settings("daily", |s| s.backup_time = "9am".to_string()),
+                "Invalid backup time",
+            ),
+            (
+                settings("daily", |s| s.backup_time = " , ".to_string()),
+                "at least one backup time",
+            ),
+            (settings("weekly", |s| s.backup_weekday = 7), "weekday"),
+            (
+                settings("monthly", |s| s.backup_month_day = 29),
+                "day of the month",
+            ),
+            (
+                settings("monthly", |s| s.backup_month_day = 0),
+                "day of the month",
+            ),
+            (
+                settings("custom", |s| s.backup_cron = "0 4 * *".to_string()),
+                "5 or 6 cron fields",
+            ),
+            (
+                settings("hourly", |_| {}),
+                "Unknown backup frequency: hourly",
+            ),
+        ];
+        for (settings, expected) in cases {
+            let message = error(&settings);
+            assert!(message.contains(expected), "{message}");
+        }
+    }
 }
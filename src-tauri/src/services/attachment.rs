@@ -0,0 +1,441 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::services::storage::{content_type_for, Storage};
+use crate::state::AppState;
+
+const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+/// Longest edge of a generated thumbnail, in pixels.
+const THUMBNAIL_SIZE: u32 = 320;
+const S3_PROVIDER_NAME: &str = "thai-htay-attachments";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentEntityType {
+    Expense,
+    Order,
+    Customer,
+}
+
+impl AttachmentEntityType {
+    fn as_str(self) -> &'static str {
+        match self {
+            AttachmentEntityType::Expense => "expense",
+            AttachmentEntityType::Order => "order",
+            AttachmentEntityType::Customer => "customer",
+        }
+    }
+
+    fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "expense" => Ok(AttachmentEntityType::Expense),
+            "order" => Ok(AttachmentEntityType::Order),
+            "customer" => Ok(AttachmentEntityType::Customer),
+            _ => Err(AppError::internal(format!(
+                "Unknown attachment entity: {value}"
+            ))),
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            AttachmentEntityType::Expense => "expenses",
+            AttachmentEntityType::Order => "orders",
+            AttachmentEntityType::Customer => "customers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Attachment {
+    pub id: String,
+    pub entity_type: AttachmentEntityType,
+    pub entity_id: String,
+    /// Original file name, as picked by the user.
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub local_path: String,
+    /// JPEG preview for images; `None` for other files.
+    pub thumbnail_path: Option<String>,
+    pub cloud_url: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AttachmentRow {
+    id: String,
+    entity_type: String,
+    entity_id: String,
+    file_name: String,
+    content_type: String,
+    size_bytes: i64,
+    local_path: String,
+    thumbnail_path: Option<String>,
+    cloud_key: Option<String>,
+    cloud_url: Option<String>,
+    created_at: Option<String>,
+}
+
+impl TryFrom<AttachmentRow> for Attachment {
+    type Error = AppError;
+
+    fn try_from(row: AttachmentRow) -> AppResult<Self> {
+        Ok(Attachment {
+            id: row.id,
+            entity_type: AttachmentEntityType::parse(&row.entity_type)?,
+            entity_id: row.entity_id,
+            file_name: row.file_name,
+            content_type: row.content_type,
+            size_bytes: row.size_bytes,
+            local_path: row.local_path,
+            thumbnail_path: row.thumbnail_path,
+            cloud_url: row.cloud_url,
+            created_at: row.created_at,
+        })
+    }
+}
+
+const ATTACHMENT_SELECT: &str = "SELECT id, entity_type, entity_id, file_name, content_type, \
+     size_bytes, local_path, thumbnail_path, cloud_key, cloud_url, created_at FROM attachments";
+
+fn attachments_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(app.path().app_data_dir()?.join("attachments"))
+}
+
+async fn find_row(db: &DatabaseConnection, id: &str) -> AppResult<AttachmentRow> {
+    AttachmentRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{ATTACHMENT_SELECT} WHERE id = ?"),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Attachment not found"))
+}
+
+async fn ensure_entity_exists(
+    db: &DatabaseConnection,
+    entity_type: AttachmentEntityType,
+    entity_id: &str,
+) -> AppResult<()> {
+    let found = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "SELECT 1 AS found FROM {} WHERE id = ? AND deleted_at IS NULL",
+                entity_type.table()
+            ),
+            [entity_id.into()],
+        ))
+        .await?;
+    if found.is_none() {
+        return Err(AppError::not_found(format!(
+            "Cannot attach a file: {} not found",
+            entity_type.as_str()
+        )));
+    }
+    Ok(())
+}
+
+/// Writes a JPEG preview of an image file and returns the image's size. Runs
+/// off the async runtime since decoding a phone photo takes a while.
+pub(crate) async fn generate_thumbnail(source: PathBuf, dest: PathBuf) -> AppResult<(u32, u32)> {
+    tauri::async_runtime::spawn_blocking(move || -> AppResult<(u32, u32)> {
+        let image = image::ImageReader::open(&source)?
+            .with_guessed_format()?
+            .decode()
+            .map_err(|e| AppError::invalid_input(format!("Unable to read image: {e}")))?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        image
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+            .to_rgb8()
+            .save_with_format(&dest, image::ImageFormat::Jpeg)
+            .map_err(|e| AppError::internal(format!("Failed to write thumbnail: {e}")))?;
+        Ok((image.width(), image.height()))
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+fn has_thumbnail(content_type: &str) -> bool {
+    matches!(
+        content_type,
+        "image/jpeg" | "image/png" | "image/gif" | "image/webp"
+    )
+}
+
+pub(crate) fn remove_file_if_present(path: &str) {
+    if let Err(err) = fs::remove_file(path) {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove file {path}: {err}");
+        }
+    }
+}
+
+/// Copies a file into app data and attaches it to an expense, order or
+/// customer. With `upload_to_s3`, it is also uploaded to the configured storage;
+/// an upload failure keeps the local copy and leaves `cloud_url` empty.
+#[instrument(skip(state, app))]
+pub async fn add_attachment(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    entity_type: AttachmentEntityType,
+    entity_id: String,
+    file_path: String,
+    upload_to_s3: bool,
+) -> AppResult<Attachment> {
+    let source = PathBuf::from(file_path.trim());
+    let metadata = fs::metadata(&source)
+        .map_err(|_| AppError::not_found(format!("File not found: {}", file_path.trim())))?;
+    if !metadata.is_file() {
+        return Err(AppError::invalid_input("Only files can be attached"));
+    }
+    if metadata.len() > MAX_ATTACHMENT_BYTES {
+        return Err(AppError::invalid_input(format!(
+            "Attachments must be at most {} MB",
+            MAX_ATTACHMENT_BYTES / 1024 / 1024
+        )));
+    }
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| AppError::internal("Invalid file name"))?
+        .to_string_lossy()
+        .to_string();
+
+    let db = state.db.lock().await.clone();
+    ensure_entity_exists(&db, entity_type, &entity_id).await?;
+
+    let id = Uuid::new_v4().to_string();
+    let extension = Path::new(&file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| format!(".{}", ext.to_ascii_lowercase()))
+        .unwrap_or_default();
+    let dir = attachments_dir(app)?;
+    let entity_dir = dir.join(entity_type.as_str());
+    fs::create_dir_all(&entity_dir)?;
+    let local_path = entity_dir.join(format!("{id}{extension}"));
+    fs::copy(&source, &local_path)?;
+    let local_path = local_path.to_string_lossy().to_string();
+
+    let content_type = content_type_for(&file_name).to_string();
+    let thumbnail_path = if has_thumbnail(&content_type) {
+        let dest = dir.join("thumbnails").join(format!("{id}.jpg"));
+        match generate_thumbnail(PathBuf::from(&local_path), dest.clone()).await {
+            Ok(_) => Some(dest.to_string_lossy().to_string()),
+            Err(err) => {
+                tracing::warn!("No thumbnail for {file_name}: {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let inserted = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO attachments (id, entity_type, entity_id, file_name, content_type, \
+             size_bytes, local_path, thumbnail_path, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))",
+            [
+                id.clone().into(),
+                entity_type.as_str().into(),
+                entity_id.into(),
+                file_name.into(),
+                content_type.into(),
+                (metadata.len() as i64).into(),
+                local_path.clone().into(),
+                thumbnail_path.clone().into(),
+            ],
+        ))
+        .await;
+    if let Err(err) = inserted {
+        remove_file_if_present(&local_path);
+        if let Some(thumbnail) = &thumbnail_path {
+            remove_file_if_present(thumbnail);
+        }
+        return Err(err.into());
+    }
+
+    if upload_to_s3 {
+        if let Err(err) = upload_row(&db, app, &id).await {
+            tracing::warn!("Attachment {id} was saved locally but not uploaded: {err}");
+        }
+    }
+    find_row(&db, &id).await?.try_into()
+}
+
+async fn upload_row(db: &DatabaseConnection, app: &AppHandle, id: &str) -> AppResult<()> {
+    let row = find_row(db, id).await?;
+    let storage = Storage::required(app, S3_PROVIDER_NAME).await?;
+    let bytes = fs::read(&row.local_path).map_err(|_| {
+        AppError::not_found(format!("Attachment file not found: {}", row.local_path))
+    })?;
+    let extension = Path::new(&row.local_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| format!(".{ext}"))
+        .unwrap_or_default();
+    let key = format!(
+        "attachments/{}/{}/{}{}",
+        row.entity_type, row.entity_id, row.id, extension
+    );
+    storage.put_object(&key, bytes, &row.content_type).await?;
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE attachments SET cloud_key = ?, cloud_url = ?, updated_at = datetime('now') \
+         WHERE id = ?",
+        [
+            key.clone().into(),
+            storage.public_url(&key).into(),
+            id.into(),
+        ],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Uploads an attachment to the configured storage.
+#[instrument(skip(state, app))]
+pub async fn upload_attachment_to_s3(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+) -> AppResult<Attachment> {
+    let db = state.db.lock().await.clone();
+    upload_row(&db, app, &id).await?;
+    find_row(&db, &id).await?.try_into()
+}
+
+/// Lists the attachments of one record, oldest first.
+#[instrument(skip(state))]
+pub async fn get_attachments(
+    state: Arc<AppState>,
+    entity_type: AttachmentEntityType,
+    entity_id: String,
+) -> AppResult<Vec<Attachment>> {
+    let db = state.db.lock().await.clone();
+    AttachmentRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{ATTACHMENT_SELECT} WHERE entity_type = ? AND entity_id = ? \
+             ORDER BY created_at ASC, id ASC"
+        ),
+        [entity_type.as_str().into(), entity_id.into()],
+    ))
+    .all(&db)
+    .await?
+    .into_iter()
+    .map(Attachment::try_from)
+    .collect()
+}
+
+/// Removes the rows, local files and uploaded copies of the given attachments.
+async fn remove_rows(db: &DatabaseConnection, app: &AppHandle, rows: Vec<AttachmentRow>) -> u64 {
+    if rows.is_empty() {
+        return 0;
+    }
+    let storage = if rows.iter().any(|row| row.cloud_key.is_some()) {
+        Storage::from_settings(app, S3_PROVIDER_NAME)
+            .await
+            .unwrap_or_else(|err| {
+                tracing::warn!("Uploaded attachments cannot be removed from storage: {err}");
+                None
+            })
+    } else {
+        None
+    };
+
+    let mut removed = 0;
+    for row in rows {
+        if let Err(err) = db
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                "DELETE FROM attachments WHERE id = ?",
+                [row.id.clone().into()],
+            ))
+            .await
+        {
+            tracing::warn!("Failed to delete attachment {}: {err}", row.id);
+            continue;
+        }
+        removed += 1;
+        remove_file_if_present(&row.local_path);
+        if let Some(thumbnail) = &row.thumbnail_path {
+            remove_file_if_present(thumbnail);
+        }
+        if let (Some(storage), Some(key)) = (&storage, &row.cloud_key) {
+            if let Err(err) = storage.delete_object(key).await {
+                tracing::warn!("Failed to delete attachment {} from storage: {err}", row.id);
+            }
+        }
+    }
+    removed
+}
+
+/// Deletes an attachment with its files, including the uploaded copy.
+#[instrument(skip(state, app))]
+pub async fn delete_attachment(state: Arc<AppState>, app: &AppHandle, id: String) -> AppResult<()> {
+    let db = state.db.lock().await.clone();
+    let row = find_row(&db, &id).await?;
+    remove_rows(&db, app, vec![row]).await;
+    Ok(())
+}
+
+/// Deletes every attachment of a record; called when the record is deleted.
+pub(crate) async fn delete_entity_attachments(
+    db: &DatabaseConnection,
+    app: &AppHandle,
+    entity_type: AttachmentEntityType,
+    entity_id: &str,
+) -> AppResult<u64> {
+    let rows = AttachmentRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{ATTACHMENT_SELECT} WHERE entity_type = ? AND entity_id = ?"),
+        [entity_type.as_str().into(), entity_id.into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(remove_rows(db, app, rows).await)
+}
+
+/// Deletes attachments whose record no longer exists or was deleted, e.g. by a
+/// delete pulled in through sync. Returns how many were removed.
+#[instrument(skip(state, app))]
+pub async fn cleanup_orphaned_attachments(state: Arc<AppState>, app: &AppHandle) -> AppResult<u64> {
+    let db = state.db.lock().await.clone();
+    let mut orphans = Vec::new();
+    for entity_type in [
+        AttachmentEntityType::Expense,
+        AttachmentEntityType::Order,
+        AttachmentEntityType::Customer,
+    ] {
+        orphans.extend(
+            AttachmentRow::find_by_statement(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                format!(
+                    "{ATTACHMENT_SELECT} WHERE entity_type = ? AND NOT EXISTS \
+                     (SELECT 1 FROM {} p WHERE p.id = attachments.entity_id AND p.deleted_at IS NULL)",
+                    entity_type.table()
+                ),
+                [entity_type.as_str().into()],
+            ))
+            .all(&db)
+            .await?,
+        );
+    }
+    Ok(remove_rows(&db, app, orphans).await)
+}
@@ -0,0 +1,1507 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+use tauri::{AppHandle, Manager};
+use tracing::instrument;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::error::{AppError, AppResult};
+use crate::migration::{Migrator, MigratorTrait};
+use crate::services::drive::DriveBackupDestination;
+use crate::services::settings::{get_app_settings, write_app_settings, AppSettings};
+use crate::services::storage::{
+    LocalStorage, ObjectStorage, S3Config, S3Storage, Storage, StoredObject,
+};
+use crate::services::system::restore_database;
+use crate::services::vault::{
+    open_with_passphrase, redact, reveal_secret, seal_secret, seal_with_passphrase,
+    verify_master_password, REDACTED,
+};
+use crate::state::AppState;
+
+/// Key prefix backups are stored under in the configured storage.
+const BACKUP_PREFIX: &str = "backups";
+/// Backup archives are named `backup_<YYYY-MM-DD_HHMM>.sqlite.zip`.
+const ARCHIVE_PREFIX: &str = "backup_";
+const ARCHIVE_EXTENSION: &str = ".sqlite.zip";
+const ARCHIVE_TIME_FORMAT: &str = "%Y-%m-%d_%H%M";
+/// Appended to the name of an encrypted archive.
+const ENCRYPTED_EXTENSION: &str = ".enc";
+/// Start of an encrypted archive, followed by `seal_with_passphrase` output.
+const ENCRYPTED_MAGIC: &[u8] = b"THBKENC1";
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// Name of the database inside a backup archive.
+const ARCHIVE_DB_ENTRY: &str = "shop.db";
+/// Folder in app data where snapshots and downloads are written before use.
+const SNAPSHOT_DIR: &str = "backup-tmp";
+/// Folder in app data holding the database as it was before each restore.
+const PRE_RESTORE_DIR: &str = "pre-restore";
+const PRE_RESTORE_KEEP: usize = 5;
+/// First bytes of every SQLite database file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+/// Tables a database must have to be restored.
+const RESTORE_REQUIRED_TABLES: [&str; 6] = [
+    "shop_settings",
+    "users",
+    "customers",
+    "orders",
+    "order_items",
+    "expenses",
+];
+const MAX_RETENTION_COUNT: u32 = 1000;
+const MAX_NAME_LEN: usize = 60;
+const DEFAULT_RUN_LIMIT: u32 = 50;
+const MAX_RUN_LIMIT: u32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTargetKind {
+    GoogleDrive,
+    S3,
+    S3Compatible,
+    /// A folder on this computer or a mounted network share.
+    Local,
+}
+
+impl BackupTargetKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupTargetKind::GoogleDrive => "google_drive",
+            BackupTargetKind::S3 => "s3",
+            BackupTargetKind::S3Compatible => "s3_compatible",
+            BackupTargetKind::Local => "local",
+        }
+    }
+
+    fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "google_drive" => Ok(BackupTargetKind::GoogleDrive),
+            "s3" => Ok(BackupTargetKind::S3),
+            "s3_compatible" => Ok(BackupTargetKind::S3Compatible),
+            "local" => Ok(BackupTargetKind::Local),
+            other => Err(AppError::internal(format!(
+                "Unknown backup target kind: {other}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTrigger {
+    Scheduled,
+    Manual,
+}
+
+impl BackupTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupTrigger::Scheduled => "scheduled",
+            BackupTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// Grandfather-father-son retention: the newest backup of each of the last
+/// `keep_daily` days, `keep_weekly` weeks and `keep_monthly` months is kept and
+/// older ones are deleted from the target. Periods follow local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionPolicy {
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+    pub keep_monthly: u32,
+}
+
+/// What backup archives are encrypted with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupEncryption {
+    None,
+    /// The owner's master password at the time it was set.
+    MasterPassword,
+    /// A passphrase used only for backups.
+    Passphrase,
+}
+
+impl BackupEncryption {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupEncryption::None => "none",
+            BackupEncryption::MasterPassword => "master_password",
+            BackupEncryption::Passphrase => "passphrase",
+        }
+    }
+
+    fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "none" => Ok(BackupEncryption::None),
+            "master_password" => Ok(BackupEncryption::MasterPassword),
+            "passphrase" => Ok(BackupEncryption::Passphrase),
+            other => Err(AppError::internal(format!(
+                "Unknown backup encryption: {other}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackupEncryptionInput {
+    pub mode: BackupEncryption,
+    /// Required for `passphrase`.
+    pub passphrase: Option<String>,
+    /// Required for `master_password`.
+    pub master_password: Option<String>,
+}
+
+/// A backup archive stored on a target.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteBackup {
+    /// Object key, or the file id on Google Drive.
+    pub id: String,
+    pub file_name: String,
+    pub size_bytes: Option<i64>,
+    /// When the backup was taken, from its file name if possible.
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl RemoteBackup {
+    /// `None` for files that are not backup archives.
+    pub(crate) fn new(
+        id: String,
+        file_name: String,
+        size_bytes: Option<i64>,
+        modified_at: Option<DateTime<Utc>>,
+    ) -> Option<Self> {
+        if !file_name.starts_with(ARCHIVE_PREFIX) || !file_name.contains(ARCHIVE_EXTENSION) {
+            return None;
+        }
+        let created_at = file_name
+            .strip_prefix(ARCHIVE_PREFIX)
+            .and_then(|rest| rest.get(..15))
+            .and_then(|stamp| NaiveDateTime::parse_from_str(stamp, ARCHIVE_TIME_FORMAT).ok())
+            .map(|time| time.and_utc())
+            .or(modified_at);
+        Some(RemoteBackup {
+            id,
+            file_name,
+            size_bytes,
+            created_at,
+        })
+    }
+}
+
+/// What the settings page sends when saving a target. Only the fields of the
+/// chosen kind are used.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackupTargetInput {
+    pub name: String,
+    pub kind: BackupTargetKind,
+    pub is_enabled: Option<bool>,
+    /// Absolute path, for `local`.
+    pub local_dir: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: Option<String>,
+    /// Required for `s3_compatible`.
+    pub s3_endpoint_url: Option<String>,
+    pub s3_force_path_style: Option<bool>,
+    pub s3_access_key_id: Option<String>,
+    /// `********` keeps the stored key.
+    pub s3_secret_access_key: Option<String>,
+    /// `None` keeps every backup.
+    pub retention: Option<RetentionPolicy>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupTarget {
+    pub id: String,
+    pub name: String,
+    pub kind: BackupTargetKind,
+    pub is_enabled: bool,
+    pub local_dir: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: Option<String>,
+    pub s3_endpoint_url: Option<String>,
+    pub s3_force_path_style: bool,
+    pub s3_access_key_id: Option<String>,
+    /// Redacted.
+    pub s3_secret_access_key: Option<String>,
+    pub retention: Option<RetentionPolicy>,
+    pub last_run: Option<BackupRun>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// One backup attempt on one target.
+#[derive(Debug, Clone, Serialize, FromQueryResult)]
+pub struct BackupRun {
+    pub id: String,
+    pub target_id: String,
+    pub target_name: Option<String>,
+    /// `scheduled` or `manual`
+    pub trigger: String,
+    /// `success` or `failed`
+    pub status: String,
+    pub file_name: Option<String>,
+    pub size_bytes: Option<i64>,
+    /// Old backups deleted by the retention policy after this one.
+    pub pruned_count: Option<i64>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct BackupTargetRow {
+    id: String,
+    name: String,
+    kind: String,
+    is_enabled: bool,
+    local_dir: Option<String>,
+    s3_bucket: Option<String>,
+    s3_region: Option<String>,
+    s3_endpoint_url: Option<String>,
+    s3_force_path_style: bool,
+    s3_access_key_id: Option<String>,
+    s3_secret_access_key: Option<String>,
+    keep_daily: Option<i64>,
+    keep_weekly: Option<i64>,
+    keep_monthly: Option<i64>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+const TARGET_SELECT: &str = "SELECT id, name, kind, is_enabled, local_dir, s3_bucket, s3_region, \
+     s3_endpoint_url, s3_force_path_style, s3_access_key_id, s3_secret_access_key, keep_daily, \
+     keep_weekly, keep_monthly, created_at, updated_at FROM backup_targets";
+
+const RUN_SELECT: &str = "SELECT r.id, r.target_id, t.name AS target_name, r.trigger, r.status, \
+     r.file_name, r.size_bytes, r.pruned_count, r.error, r.started_at, r.finished_at \
+     FROM backup_runs r LEFT JOIN backup_targets t ON t.id = r.target_id";
+
+/// Where backup archives are uploaded to and restored from.
+#[async_trait]
+pub(crate) trait BackupDestination: Send + Sync {
+    async fn upload(&self, file_name: &str, archive: Vec<u8>) -> AppResult<()>;
+    /// Backup archives on the target, in no particular order.
+    async fn list_backups(&self) -> AppResult<Vec<RemoteBackup>>;
+    async fn download_backup(&self, id: &str) -> AppResult<Vec<u8>>;
+    async fn delete_backup(&self, id: &str) -> AppResult<()>;
+    /// Fails when the target cannot be reached or is not authorized.
+    async fn check_connection(&self) -> AppResult<()>;
+}
+
+/// A bucket or folder as a backup target; archives go under `backups/`.
+struct StorageBackupDestination(Box<dyn ObjectStorage>);
+
+impl StorageBackupDestination {
+    fn backup_key(id: &str) -> AppResult<&str> {
+        if id.starts_with(&format!("{BACKUP_PREFIX}/")) {
+            Ok(id)
+        } else {
+            Err(AppError::invalid_input("Not a backup on this target"))
+        }
+    }
+}
+
+#[async_trait]
+impl BackupDestination for StorageBackupDestination {
+    async fn upload(&self, file_name: &str, archive: Vec<u8>) -> AppResult<()> {
+        let key = format!("{BACKUP_PREFIX}/{file_name}");
+        self.0
+            .put_object(&key, archive, archive_content_type(file_name))
+            .await
+    }
+
+    async fn list_backups(&self) -> AppResult<Vec<RemoteBackup>> {
+        let prefix = format!("{BACKUP_PREFIX}/");
+        let objects = self.0.list_objects(&prefix).await?;
+        Ok(objects
+            .into_iter()
+            .filter_map(|object| {
+                let file_name = object.key.strip_prefix(&prefix)?.to_string();
+                if file_name.contains('/') {
+                    return None;
+                }
+                RemoteBackup::new(
+                    object.key,
+                    file_name,
+                    Some(object.size_bytes),
+                    object.last_modified,
+                )
+            })
+            .collect())
+    }
+
+    async fn download_backup(&self, id: &str) -> AppResult<Vec<u8>> {
+        self.0.get_object(Self::backup_key(id)?).await
+    }
+
+    async fn delete_backup(&self, id: &str) -> AppResult<()> {
+        self.0.delete_object(Self::backup_key(id)?).await
+    }
+
+    async fn check_connection(&self) -> AppResult<()> {
+        self.0.check_connection().await
+    }
+}
+
+impl BackupTargetRow {
+    fn into_target(self, last_run: Option<BackupRun>) -> AppResult<BackupTarget> {
+        Ok(BackupTarget {
+            kind: BackupTargetKind::parse(&self.kind)?,
+            s3_secret_access_key: self.s3_secret_access_key.as_deref().map(redact),
+            retention: self.retention(),
+            id: self.id,
+            name: self.name,
+            is_enabled: self.is_enabled,
+            local_dir: self.local_dir,
+            s3_bucket: self.s3_bucket,
+            s3_region: self.s3_region,
+            s3_endpoint_url: self.s3_endpoint_url,
+            s3_force_path_style: self.s3_force_path_style,
+            s3_access_key_id: self.s3_access_key_id,
+            last_run,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+
+    fn retention(&self) -> Option<RetentionPolicy> {
+        let count = |value: Option<i64>| value.and_then(|v| u32::try_from(v).ok());
+        Some(RetentionPolicy {
+            keep_daily: count(self.keep_daily)?,
+            keep_weekly: count(self.keep_weekly)?,
+            keep_monthly: count(self.keep_monthly)?,
+        })
+    }
+
+    fn s3_config(&self) -> AppResult<S3Config> {
+        Ok(S3Config {
+            access_key_id: self.s3_access_key_id.clone().unwrap_or_default(),
+            secret_access_key: reveal_secret(self.s3_secret_access_key.as_deref().unwrap_or(""))?,
+            region: self.s3_region.clone().unwrap_or_default(),
+            bucket: self.s3_bucket.clone().unwrap_or_default(),
+            endpoint_url: self.s3_endpoint_url.clone(),
+            force_path_style: self.s3_force_path_style,
+        })
+    }
+
+    async fn connect(&self, app: &AppHandle) -> AppResult<Box<dyn BackupDestination>> {
+        Ok(match BackupTargetKind::parse(&self.kind)? {
+            BackupTargetKind::GoogleDrive => Box::new(DriveBackupDestination::new(app.clone())),
+            BackupTargetKind::S3 | BackupTargetKind::S3Compatible => {
+                let storage = S3Storage::connect(self.s3_config()?, "thai-htay-backup").await?;
+                Box::new(StorageBackupDestination(Box::new(storage)))
+            }
+            BackupTargetKind::Local => {
+                let storage = LocalStorage::new(self.local_dir.clone().unwrap_or_default())?;
+                Box::new(StorageBackupDestination(Box::new(storage)))
+            }
+        })
+    }
+}
+
+/// Validated column values of a target.
+struct TargetValues {
+    name: String,
+    kind: BackupTargetKind,
+    is_enabled: bool,
+    local_dir: Option<String>,
+    s3_bucket: Option<String>,
+    s3_region: Option<String>,
+    s3_endpoint_url: Option<String>,
+    s3_force_path_style: bool,
+    s3_access_key_id: Option<String>,
+    /// Sealed.
+    s3_secret_access_key: Option<String>,
+    retention: Option<RetentionPolicy>,
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn validate_retention(retention: Option<RetentionPolicy>) -> AppResult<Option<RetentionPolicy>> {
+    let Some(policy) = retention else {
+        return Ok(None);
+    };
+    let counts = [policy.keep_daily, policy.keep_weekly, policy.keep_monthly];
+    if counts.iter().all(|count| *count == 0) {
+        return Err(AppError::invalid_input(
+            "A retention policy must keep at least one daily, weekly or monthly backup",
+        ));
+    }
+    if counts.iter().any(|count| *count > MAX_RETENTION_COUNT) {
+        return Err(AppError::invalid_input(format!(
+            "Retention counts must be at most {MAX_RETENTION_COUNT}"
+        )));
+    }
+    Ok(Some(policy))
+}
+
+async fn validate_input(
+    db: &DatabaseConnection,
+    id: Option<&str>,
+    input: BackupTargetInput,
+) -> AppResult<TargetValues> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Backup target name is required"));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::invalid_input(format!(
+            "Backup target name must be at most {MAX_NAME_LEN} characters"
+        )));
+    }
+    let mut values = TargetValues {
+        name,
+        kind: input.kind,
+        is_enabled: input.is_enabled.unwrap_or(true),
+        local_dir: None,
+        s3_bucket: None,
+        s3_region: None,
+        s3_endpoint_url: None,
+        s3_force_path_style: true,
+        s3_access_key_id: None,
+        s3_secret_access_key: None,
+        retention: validate_retention(input.retention)?,
+    };
+
+    match input.kind {
+        BackupTargetKind::GoogleDrive => {
+            let existing = find_rows(db, "kind = 'google_drive'").await?;
+            if existing.iter().any(|row| Some(row.id.as_str()) != id) {
+                return Err(AppError::invalid_input(
+                    "A Google Drive backup target already exists",
+                ));
+            }
+        }
+        BackupTargetKind::Local => {
+            let dir = trimmed(input.local_dir)
+                .ok_or_else(|| AppError::invalid_input("Backup folder is required"))?;
+            if !Path::new(&dir).is_absolute() {
+                return Err(AppError::invalid_input(
+                    "Backup folder must be an absolute path",
+                ));
+            }
+            values.local_dir = Some(dir);
+        }
+        BackupTargetKind::S3 | BackupTargetKind::S3Compatible => {
+            let secret = match input.s3_secret_access_key {
+                Some(secret) if secret == REDACTED => match id {
+                    Some(id) => {
+                        let stored = find_row(db, id).await?.s3_secret_access_key;
+                        reveal_secret(stored.as_deref().unwrap_or(""))?
+                    }
+                    None => String::new(),
+                },
+                other => other.unwrap_or_default(),
+            };
+            let endpoint_url = trimmed(input.s3_endpoint_url);
+            if input.kind == BackupTargetKind::S3Compatible && endpoint_url.is_none() {
+                return Err(AppError::invalid_input(
+                    "An endpoint URL is required for S3-compatible storage",
+                ));
+            }
+            let compatible = input.kind == BackupTargetKind::S3Compatible;
+            let config = S3Config {
+                access_key_id: input.s3_access_key_id.unwrap_or_default(),
+                secret_access_key: secret,
+                region: input.s3_region.unwrap_or_default(),
+                bucket: input.s3_bucket.unwrap_or_default(),
+                endpoint_url: endpoint_url.filter(|_| compatible),
+                force_path_style: compatible && input.s3_force_path_style.unwrap_or(true),
+            }
+            .validated()?;
+            values.s3_bucket = Some(config.bucket);
+            values.s3_region = Some(config.region);
+            values.s3_endpoint_url = config.endpoint_url;
+            values.s3_force_path_style = config.force_path_style;
+            values.s3_access_key_id = Some(config.access_key_id);
+            values.s3_secret_access_key = Some(seal_secret(&config.secret_access_key)?);
+        }
+    }
+    Ok(values)
+}
+
+async fn find_rows(db: &DatabaseConnection, condition: &str) -> AppResult<Vec<BackupTargetRow>> {
+    Ok(BackupTargetRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!("{TARGET_SELECT} WHERE {condition} ORDER BY created_at, name"),
+    ))
+    .all(db)
+    .await?)
+}
+
+async fn find_row(db: &DatabaseConnection, id: &str) -> AppResult<BackupTargetRow> {
+    BackupTargetRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{TARGET_SELECT} WHERE id = ?"),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Backup target not found"))
+}
+
+async fn last_run(db: &DatabaseConnection, target_id: &str) -> AppResult<Option<BackupRun>> {
+    Ok(BackupRun::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{RUN_SELECT} WHERE r.target_id = ? ORDER BY r.started_at DESC, r.rowid DESC LIMIT 1"
+        ),
+        [target_id.into()],
+    ))
+    .one(db)
+    .await?)
+}
+
+async fn find_target(db: &DatabaseConnection, id: &str) -> AppResult<BackupTarget> {
+    let row = find_row(db, id).await?;
+    let last_run = last_run(db, id).await?;
+    row.into_target(last_run)
+}
+
+fn now() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// A file in the app data temp folder, deleted on drop.
+pub(crate) struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(app: &AppHandle, label: &str, extension: &str) -> AppResult<Self> {
+        let tmp_dir = app.path().app_data_dir()?.join(SNAPSHOT_DIR);
+        fs::create_dir_all(&tmp_dir)?;
+        Ok(TempFile {
+            path: tmp_dir.join(format!("{label}-{}.{extension}", Uuid::new_v4())),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Copies the live database with `VACUUM INTO`. It reads in one transaction,
+/// so the copy includes pages still in the WAL and never sees a half-applied
+/// write, while the app and sync keep writing. The copy is then checked with
+/// `PRAGMA integrity_check`.
+pub(crate) async fn snapshot_database(state: &AppState, app: &AppHandle) -> AppResult<TempFile> {
+    let snapshot = TempFile::new(app, "snapshot", "db")?;
+
+    let db = state.db.lock().await.clone();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "VACUUM INTO ?",
+        [snapshot.path.to_string_lossy().to_string().into()],
+    ))
+    .await
+    .map_err(|e| AppError::internal(format!("Failed to snapshot the database: {e}")))?;
+
+    check_integrity(&snapshot.path).await?;
+    Ok(snapshot)
+}
+
+/// Runs `PRAGMA integrity_check` on a database file, read-only. A file that
+/// is not a database or cannot be read fails the check too.
+pub(crate) async fn check_integrity(path: &Path) -> AppResult<()> {
+    let failed = |detail: String| {
+        AppError::internal(format!("Database failed the integrity check: {detail}"))
+    };
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| failed(e.to_string()))?;
+    let problems: Result<Vec<String>, _> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(&mut conn)
+        .await;
+    let _ = conn.close().await;
+
+    let problems = problems.map_err(|e| failed(e.to_string()))?;
+    if problems.len() == 1 && problems[0] == "ok" {
+        return Ok(());
+    }
+    Err(failed(problems.join("; ")))
+}
+
+/// Checks that a file can replace the live database: a SQLite file that
+/// passes `PRAGMA integrity_check`, has the core tables and was not written by
+/// a newer version of the app.
+pub(crate) async fn validate_restore_candidate(path: &Path) -> AppResult<()> {
+    let mut header = [0u8; SQLITE_HEADER.len()];
+    let is_sqlite = File::open(path)?.read_exact(&mut header).is_ok() && header == *SQLITE_HEADER;
+    if !is_sqlite {
+        return Err(AppError::invalid_input(
+            "The file is not a database backup of this app",
+        ));
+    }
+    check_integrity(path).await.map_err(|err| match err {
+        AppError::Internal(message) => AppError::InvalidInput(message),
+        other => other,
+    })?;
+
+    let unreadable =
+        |e: sqlx::Error| AppError::invalid_input(format!("The backup could not be read: {e}"));
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(unreadable)?;
+    let tables: Result<Vec<String>, _> =
+        sqlx::query_scalar("SELECT name FROM sqlite_schema WHERE type = 'table'")
+            .fetch_all(&mut conn)
+            .await;
+    let applied: Result<Vec<String>, _> = match &tables {
+        Ok(tables) if tables.iter().any(|table| table == "seaql_migrations") => {
+            sqlx::query_scalar("SELECT version FROM seaql_migrations")
+                .fetch_all(&mut conn)
+                .await
+        }
+        _ => Ok(Vec::new()),
+    };
+    let _ = conn.close().await;
+    let (tables, applied) = (tables.map_err(unreadable)?, applied.map_err(unreadable)?);
+
+    let missing: Vec<&str> = RESTORE_REQUIRED_TABLES
+        .into_iter()
+        .filter(|required| !tables.iter().any(|table| table == required))
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::invalid_input(format!(
+            "The backup is missing tables: {}",
+            missing.join(", ")
+        )));
+    }
+
+    // Backups taken before migrations were tracked have none applied; the
+    // migrations bring them up to date after the restore.
+    let known: HashSet<String> = Migrator::migrations()
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect();
+    if let Some(unknown) = applied.iter().find(|version| !known.contains(*version)) {
+        return Err(AppError::invalid_input(format!(
+            "The backup was made by a newer version of the app (migration {unknown}). \
+             Update the app before restoring it."
+        )));
+    }
+    Ok(())
+}
+
+/// Zips a verified snapshot of the database, encrypted when backup
+/// encryption is on. Returns the archive's file name and bytes.
+pub(crate) async fn create_backup_archive(
+    state: &AppState,
+    app: &AppHandle,
+) -> AppResult<(String, Vec<u8>)> {
+    let snapshot = snapshot_database(state, app).await?;
+
+    let timestamp = Utc::now().format(ARCHIVE_TIME_FORMAT).to_string();
+    let zip_filename = format!("{ARCHIVE_PREFIX}{timestamp}{ARCHIVE_EXTENSION}");
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(ARCHIVE_DB_ENTRY, options)
+        .map_err(|e| AppError::internal(e.to_string()))?;
+    let mut db_file = File::open(snapshot.path())?;
+    let mut buffer = Vec::new();
+    db_file.read_to_end(&mut buffer)?;
+    zip.write_all(&buffer)?;
+    let archive = zip
+        .finish()
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .into_inner();
+
+    match backup_passphrase(app)? {
+        Some(passphrase) => Ok((
+            format!("{zip_filename}{ENCRYPTED_EXTENSION}"),
+            encrypt_archive(&passphrase, &archive)?,
+        )),
+        None => Ok((zip_filename, archive)),
+    }
+}
+
+/// Extracts the database from a backup archive into a temp file. `None` when
+/// the file is not a zip archive, i.e. already a plain database file.
+pub(crate) fn extract_backup_archive(app: &AppHandle, path: &Path) -> AppResult<Option<TempFile>> {
+    let mut magic = [0u8; 4];
+    let is_zip = File::open(path)?.read_exact(&mut magic).is_ok() && magic == *b"PK\x03\x04";
+    if !is_zip {
+        return Ok(None);
+    }
+
+    let mut archive = ZipArchive::new(File::open(path)?)
+        .map_err(|e| AppError::invalid_input(format!("Invalid backup archive: {e}")))?;
+    let mut entry = archive.by_name(ARCHIVE_DB_ENTRY).map_err(|_| {
+        AppError::invalid_input(format!(
+            "The backup archive does not contain {ARCHIVE_DB_ENTRY}"
+        ))
+    })?;
+    let extracted = TempFile::new(app, "restore", "db")?;
+    std::io::copy(&mut entry, &mut File::create(extracted.path())?)?;
+    Ok(Some(extracted))
+}
+
+/// The passphrase new backups are encrypted with, or `None` when encryption
+/// is off.
+fn backup_passphrase(app: &AppHandle) -> AppResult<Option<String>> {
+    let settings = get_app_settings(app.clone())?;
+    if BackupEncryption::parse(&settings.backup_encryption)? == BackupEncryption::None {
+        return Ok(None);
+    }
+    if settings.backup_passphrase.is_empty() {
+        return Err(AppError::invalid_input(
+            "Backup encryption is on but its passphrase is missing. Set it again in the backup settings.",
+        ));
+    }
+    Ok(Some(reveal_secret(&settings.backup_passphrase)?))
+}
+
+fn encrypt_archive(passphrase: &str, archive: &[u8]) -> AppResult<Vec<u8>> {
+    let mut encrypted = ENCRYPTED_MAGIC.to_vec();
+    encrypted.extend(seal_with_passphrase(passphrase, archive)?);
+    Ok(encrypted)
+}
+
+/// Content type a backup archive is uploaded with.
+pub(crate) fn archive_content_type(file_name: &str) -> &'static str {
+    if file_name.ends_with(ENCRYPTED_EXTENSION) {
+        "application/octet-stream"
+    } else {
+        "application/zip"
+    }
+}
+
+/// Decrypts an encrypted backup into a temp file. `None` when the file is not
+/// encrypted. `passphrase` overrides the one in the backup settings, for
+/// backups taken with another passphrase or an old master password.
+pub(crate) fn decrypt_backup_file(
+    app: &AppHandle,
+    path: &Path,
+    passphrase: Option<&str>,
+) -> AppResult<Option<TempFile>> {
+    let mut magic = [0u8; ENCRYPTED_MAGIC.len()];
+    let is_encrypted = File::open(path)?.read_exact(&mut magic).is_ok() && magic == ENCRYPTED_MAGIC;
+    if !is_encrypted {
+        return Ok(None);
+    }
+
+    let passphrase = match passphrase.filter(|passphrase| !passphrase.is_empty()) {
+        Some(passphrase) => passphrase.to_string(),
+        None => backup_passphrase(app)?.ok_or_else(|| {
+            AppError::invalid_input("This backup is encrypted. Enter its passphrase to restore it.")
+        })?,
+    };
+    let encrypted = fs::read(path)?;
+    let archive = open_with_passphrase(&passphrase, &encrypted[ENCRYPTED_MAGIC.len()..])
+        .ok_or_else(|| {
+            AppError::invalid_input(
+                "Wrong passphrase for this encrypted backup, or the file is damaged.",
+            )
+        })?;
+    let decrypted = TempFile::new(app, "decrypted", "zip")?;
+    fs::write(decrypted.path(), archive)?;
+    Ok(Some(decrypted))
+}
+
+/// Keeps backups encrypted with the master password after it changes.
+/// Backups taken before still need the old one. Returns the replaced
+/// settings, to write back if the change fails, or `None` when nothing
+/// changed.
+pub(crate) fn follow_master_password_change(
+    app: &AppHandle,
+    new_master: &str,
+) -> AppResult<Option<AppSettings>> {
+    let previous = get_app_settings(app.clone())?;
+    if BackupEncryption::parse(&previous.backup_encryption)? != BackupEncryption::MasterPassword {
+        return Ok(None);
+    }
+    let mut settings = previous.clone();
+    settings.backup_passphrase = seal_secret(new_master)?;
+    write_app_settings(app, &settings)?;
+    Ok(Some(previous))
+}
+
+fn pre_restore_path(app: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app.path().app_data_dir()?.join(PRE_RESTORE_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!(
+        "pre_restore_{}.db",
+        Utc::now().format("%Y-%m-%d_%H%M%S%3f")
+    )))
+}
+
+/// Deletes all but the newest few copies in `pre-restore/`.
+fn prune_pre_restore_copies(app: &AppHandle) -> AppResult<()> {
+    let dir = app.path().app_data_dir()?.join(PRE_RESTORE_DIR);
+    let mut copies: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("pre_restore_") && name.ends_with(".db"))
+        })
+        .collect();
+    copies.sort();
+    copies.reverse();
+    for old in copies.iter().skip(PRE_RESTORE_KEEP) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(())
+}
+
+/// Snapshots the live database with `VACUUM INTO` before a restore
+/// overwrites it, keeping the last few copies in `pre-restore/`. Returns the
+/// copy's path, which a failed restore rolls back to.
+pub(crate) async fn save_pre_restore_copy(
+    app: &AppHandle,
+    db: &DatabaseConnection,
+) -> AppResult<PathBuf> {
+    let copy_path = pre_restore_path(app)?;
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "VACUUM INTO ?",
+        [copy_path.to_string_lossy().to_string().into()],
+    ))
+    .await
+    .map_err(|e| {
+        let _ = fs::remove_file(&copy_path);
+        AppError::internal(format!(
+            "Failed to save the current database before restoring: {e}"
+        ))
+    })?;
+    prune_pre_restore_copies(app)?;
+    Ok(copy_path)
+}
+
+/// Copies the database file aside when it is too damaged to snapshot. The
+/// pool must be closed so the file is complete. `None` when there is no
+/// database or it cannot be copied.
+pub(crate) fn copy_pre_restore_file(app: &AppHandle, db_path: &Path) -> Option<PathBuf> {
+    if !db_path.exists() {
+        return None;
+    }
+    let copied = pre_restore_path(app).and_then(|copy_path| {
+        fs::copy(db_path, &copy_path)?;
+        prune_pre_restore_copies(app)?;
+        Ok(copy_path)
+    });
+    match copied {
+        Ok(copy_path) => Some(copy_path),
+        Err(err) => {
+            tracing::warn!("Failed to copy the database before restoring: {err}");
+            None
+        }
+    }
+}
+
+/// Calendar period a backup falls in: a day, an ISO week or a month.
+type PeriodOf = fn(&DateTime<Local>) -> (i32, u32);
+
+/// Backups outside a retention policy. The newest backup overall is always
+/// kept, and backups whose time is unknown are never pruned.
+fn backups_to_prune(backups: &[RemoteBackup], policy: RetentionPolicy) -> Vec<&RemoteBackup> {
+    let mut dated: Vec<(DateTime<Local>, &RemoteBackup)> = backups
+        .iter()
+        .filter_map(|backup| Some((backup.created_at?.with_timezone(&Local), backup)))
+        .collect();
+    dated.sort_by_key(|(time, _)| std::cmp::Reverse(*time));
+
+    let mut keep = vec![false; dated.len()];
+    if let Some(newest) = keep.first_mut() {
+        *newest = true;
+    }
+    let tiers: [(u32, PeriodOf); 3] = [
+        (policy.keep_daily, |time| (time.year(), time.ordinal())),
+        (policy.keep_weekly, |time| {
+            let week = time.iso_week();
+            (week.year(), week.week())
+        }),
+        (policy.keep_monthly, |time| (time.year(), time.month())),
+    ];
+    for (limit, period_of) in tiers {
+        let mut periods = HashSet::new();
+        for (index, (time, _)) in dated.iter().enumerate() {
+            if periods.len() >= limit as usize {
+                break;
+            }
+            // Newest first, so the first backup seen in a period is its newest.
+            if periods.insert(period_of(time)) {
+                keep[index] = true;
+            }
+        }
+    }
+
+    dated
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| !keep)
+        .map(|((_, backup), _)| backup)
+        .collect()
+}
+
+/// Deletes the backups on a target that its retention policy no longer
+/// keeps. Returns how many were deleted.
+async fn prune_backups(
+    destination: &dyn BackupDestination,
+    policy: RetentionPolicy,
+) -> AppResult<i64> {
+    let backups = destination.list_backups().await?;
+    let mut pruned = 0;
+    for backup in backups_to_prune(&backups, policy) {
+        destination.delete_backup(&backup.id).await?;
+        pruned += 1;
+    }
+    Ok(pruned)
+}
+
+/// Uploads an archive to one target, then applies the target's retention
+/// policy. Returns how many old backups were pruned.
+async fn upload_to_target(
+    app: &AppHandle,
+    target: &BackupTargetRow,
+    file_name: &str,
+    archive: Vec<u8>,
+) -> AppResult<Option<i64>> {
+    let destination = target.connect(app).await?;
+    destination.upload(file_name, archive).await?;
+
+    let Some(policy) = target.retention() else {
+        return Ok(None);
+    };
+    // The backup itself succeeded; a failed prune only leaves extra backups.
+    match prune_backups(destination.as_ref(), policy).await {
+        Ok(pruned) => Ok(Some(pruned)),
+        Err(err) => {
+            tracing::warn!("Failed to prune old backups on \"{}\": {err}", target.name);
+            Ok(None)
+        }
+    }
+}
+
+/// Zips the database and uploads it to the configured storage (S3,
+/// S3-compatible or a local folder). Returns the stored key.
+#[instrument(skip(state, app))]
+pub async fn upload_backup_to_storage(state: Arc<AppState>, app: &AppHandle) -> AppResult<String> {
+    let storage = Storage::required(app, "thai-htay-backup").await?;
+    let (zip_filename, archive) = create_backup_archive(&state, app).await?;
+    let key = format!("{BACKUP_PREFIX}/{zip_filename}");
+    storage.put_object(&key, archive, "application/zip").await?;
+    Ok(key)
+}
+
+/// Lists the backups in the configured storage, newest first.
+#[instrument(skip(app))]
+pub async fn list_storage_backups(app: &AppHandle) -> AppResult<Vec<StoredObject>> {
+    let storage = Storage::required(app, "thai-htay-backup").await?;
+    let mut backups = storage.list_objects(&format!("{BACKUP_PREFIX}/")).await?;
+    backups.reverse();
+    Ok(backups)
+}
+
+/// Lists backup targets with the outcome of their latest backup.
+#[instrument(skip(state))]
+pub async fn get_backup_targets(state: Arc<AppState>) -> AppResult<Vec<BackupTarget>> {
+    let db = state.db.lock().await.clone();
+    let mut targets = Vec::new();
+    for row in find_rows(&db, "1 = 1").await? {
+        let last_run = last_run(&db, &row.id).await?;
+        targets.push(row.into_target(last_run)?);
+    }
+    Ok(targets)
+}
+
+/// Adds a place scheduled backups are uploaded to.
+#[instrument(skip(state, input))]
+pub async fn create_backup_target(
+    state: Arc<AppState>,
+    input: BackupTargetInput,
+) -> AppResult<BackupTarget> {
+    let db = state.db.lock().await.clone();
+    let values = validate_input(&db, None, input).await?;
+    let id = Uuid::new_v4().to_string();
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO backup_targets (id, name, kind, is_enabled, local_dir, s3_bucket, s3_region, \
+         s3_endpoint_url, s3_force_path_style, s3_access_key_id, s3_secret_access_key, \
+         keep_daily, keep_weekly, keep_monthly, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))",
+        [
+            id.clone().into(),
+            values.name.into(),
+            values.kind.as_str().into(),
+            values.is_enabled.into(),
+            values.local_dir.into(),
+            values.s3_bucket.into(),
+            values.s3_region.into(),
+            values.s3_endpoint_url.into(),
+            values.s3_force_path_style.into(),
+            values.s3_access_key_id.into(),
+            values.s3_secret_access_key.into(),
+            values.retention.map(|r| r.keep_daily).into(),
+            values.retention.map(|r| r.keep_weekly).into(),
+            values.retention.map(|r| r.keep_monthly).into(),
+        ],
+    ))
+    .await?;
+    find_target(&db, &id).await
+}
+
+/// Replaces a backup target's settings. Its history is kept.
+#[instrument(skip(state, input))]
+pub async fn update_backup_target(
+    state: Arc<AppState>,
+    id: String,
+    input: BackupTargetInput,
+) -> AppResult<BackupTarget> {
+    let db = state.db.lock().await.clone();
+    find_row(&db, &id).await?;
+    let values = validate_input(&db, Some(&id), input).await?;
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE backup_targets SET name = ?, kind = ?, is_enabled = ?, local_dir = ?, \
+         s3_bucket = ?, s3_region = ?, s3_endpoint_url = ?, s3_force_path_style = ?, \
+         s3_access_key_id = ?, s3_secret_access_key = ?, keep_daily = ?, keep_weekly = ?, \
+         keep_monthly = ?, updated_at = datetime('now') WHERE id = ?",
+        [
+            values.name.into(),
+            values.kind.as_str().into(),
+            values.is_enabled.into(),
+            values.local_dir.into(),
+            values.s3_bucket.into(),
+            values.s3_region.into(),
+            values.s3_endpoint_url.into(),
+            values.s3_force_path_style.into(),
+            values.s3_access_key_id.into(),
+            values.s3_secret_access_key.into(),
+            values.retention.map(|r| r.keep_daily).into(),
+            values.retention.map(|r| r.keep_weekly).into(),
+            values.retention.map(|r| r.keep_monthly).into(),
+            id.clone().into(),
+        ],
+    ))
+    .await?;
+    find_target(&db, &id).await
+}
+
+/// Deletes a backup target and its history. Backups already uploaded stay
+/// where they are.
+#[instrument(skip(state))]
+pub async fn delete_backup_target(state: Arc<AppState>, id: String) -> AppResult<()> {
+    let db = state.db.lock().await.clone();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "DELETE FROM backup_runs WHERE target_id = ?",
+        [id.clone().into()],
+    ))
+    .await?;
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "DELETE FROM backup_targets WHERE id = ?",
+            [id.into()],
+        ))
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Backup target not found"));
+    }
+    Ok(())
+}
+
+/// Checks that a backup target can be reached with its saved settings.
+#[instrument(skip(state, app))]
+pub async fn test_backup_target(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+) -> AppResult<()> {
+    let db = state.db.lock().await.clone();
+    let row = find_row(&db, &id).await?;
+    row.connect(app).await?.check_connection().await
+}
+
+/// Backs the database up to every enabled target, or to one target when
+/// `target_id` is given, and records the outcome per target. The archive is
+/// created once and uploaded to each target in turn; one target failing does
+/// not stop the others.
+#[instrument(skip(state, app))]
+pub async fn run_backups(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    trigger: BackupTrigger,
+    target_id: Option<String>,
+) -> AppResult<Vec<BackupRun>> {
+    let db = state.db.lock().await.clone();
+    let targets = match &target_id {
+        Some(id) => vec![find_row(&db, id).await?],
+        None => find_rows(&db, "is_enabled = 1").await?,
+    };
+    if targets.is_empty() {
+        return Err(AppError::invalid_input("No backup targets are enabled"));
+    }
+
+    let archive = create_backup_archive(&state, app).await;
+    let mut runs = Vec::with_capacity(targets.len());
+    for target in targets {
+        let started_at = now();
+        let outcome = match &archive {
+            Ok((file_name, bytes)) => upload_to_target(app, &target, file_name, bytes.clone())
+                .await
+                .map(|pruned| (file_name.clone(), bytes.len() as i64, pruned)),
+            Err(err) => Err(AppError::internal(format!(
+                "Failed to create backup archive: {err}"
+            ))),
+        };
+        let (status, file_name, size_bytes, pruned_count, error) = match outcome {
+            Ok((file_name, size, pruned)) => ("success", Some(file_name), Some(size), pruned, None),
+            Err(err) => {
+                tracing::warn!("Backup to \"{}\" failed: {err}", target.name);
+                ("failed", None, None, None, Some(err.to_string()))
+            }
+        };
+
+        let id = Uuid::new_v4().to_string();
+        let finished_at = now();
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO backup_runs (id, target_id, trigger, status, file_name, size_bytes, \
+             pruned_count, error, started_at, finished_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            [
+                id.clone().into(),
+                target.id.clone().into(),
+                trigger.as_str().into(),
+                status.into(),
+                file_name.clone().into(),
+                size_bytes.into(),
+                pruned_count.into(),
+                error.clone().into(),
+                started_at.clone().into(),
+                finished_at.clone().into(),
+            ],
+        ))
+        .await?;
+        runs.push(BackupRun {
+            id,
+            target_id: target.id,
+            target_name: Some(target.name),
+            trigger: trigger.as_str().to_string(),
+            status: status.to_string(),
+            file_name,
+            size_bytes,
+            pruned_count,
+            error,
+            started_at,
+            finished_at,
+        });
+    }
+    Ok(runs)
+}
+
+/// Lists backup attempts, newest first, for one target or all of them.
+#[instrument(skip(state))]
+pub async fn get_backup_runs(
+    state: Arc<AppState>,
+    target_id: Option<String>,
+    limit: Option<u32>,
+) -> AppResult<Vec<BackupRun>> {
+    let db = state.db.lock().await.clone();
+    let limit = limit.unwrap_or(DEFAULT_RUN_LIMIT).clamp(1, MAX_RUN_LIMIT);
+    let statement = match target_id {
+        Some(target_id) => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "{RUN_SELECT} WHERE r.target_id = ? ORDER BY r.started_at DESC, r.rowid DESC LIMIT ?"
+            ),
+            [target_id.into(), limit.into()],
+        ),
+        None => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!("{RUN_SELECT} ORDER BY r.started_at DESC, r.rowid DESC LIMIT ?"),
+            [limit.into()],
+        ),
+    };
+    Ok(BackupRun::find_by_statement(statement).all(&db).await?)
+}
+
+/// Lists the backups stored on a target, newest first.
+#[instrument(skip(state, app))]
+pub async fn list_target_backups(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    target_id: String,
+) -> AppResult<Vec<RemoteBackup>> {
+    let db = state.db.lock().await.clone();
+    let target = find_row(&db, &target_id).await?;
+    let mut backups = target.connect(app).await?.list_backups().await?;
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    Ok(backups)
+}
+
+/// Downloads a backup from a target and restores it through
+/// `restore_database`, which keeps a copy of the current database first.
+#[instrument(skip(state, app, passphrase))]
+pub async fn restore_from_backup_target(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    target_id: String,
+    backup_id: String,
+    passphrase: Option<String>,
+) -> AppResult<()> {
+    let db = state.db.lock().await.clone();
+    let target = find_row(&db, &target_id).await?;
+    let archive = target
+        .connect(app)
+        .await?
+        .download_backup(&backup_id)
+        .await?;
+
+    let download = TempFile::new(app, "download", "zip")?;
+    fs::write(download.path(), archive)?;
+    restore_database(
+        state,
+        app,
+        download.path().to_string_lossy().to_string(),
+        passphrase,
+    )
+    .await
+}
+
+/// Returns what backup archives are encrypted with.
+#[instrument(skip(app))]
+pub fn get_backup_encryption(app: &AppHandle) -> AppResult<BackupEncryption> {
+    BackupEncryption::parse(&get_app_settings(app.clone())?.backup_encryption)
+}
+
+/// Turns backup encryption on or off. The master password is checked before
+/// it is used. Backups already taken keep the passphrase they were encrypted
+/// with.
+#[instrument(skip(state, app, input))]
+pub async fn set_backup_encryption(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    input: BackupEncryptionInput,
+) -> AppResult<BackupEncryption> {
+    let passphrase = match input.mode {
+        BackupEncryption::None => String::new(),
+        BackupEncryption::MasterPassword => {
+            let master_password = input.master_password.unwrap_or_default();
+            let db = state.db.lock().await.clone();
+            verify_master_password(&db, &master_password).await?;
+            master_password
+        }
+        BackupEncryption::Passphrase => {
+            let passphrase = input.passphrase.unwrap_or_default();
+            if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                return Err(AppError::invalid_input(format!(
+                    "The backup passphrase must be at least {MIN_PASSPHRASE_LEN} characters"
+                )));
+            }
+            passphrase
+        }
+    };
+
+    let mut settings = get_app_settings(app.clone())?;
+    settings.backup_encryption = input.mode.as_str().to_string();
+    settings.backup_passphrase = seal_secret(&passphrase)?;
+    write_app_settings(app, &settings)?;
+    Ok(input.mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup(stamp: &str) -> RemoteBackup {
+        RemoteBackup::new(
+            stamp.to_string(),
+            format!("backup_{stamp}.sqlite.zip"),
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn pruned(backups: &[RemoteBackup], policy: RetentionPolicy) -> Vec<&str> {
+        let mut ids: Vec<&str> = backups_to_prune(backups, policy)
+            .into_iter()
+            .map(|backup| backup.id.as_str())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn policy(keep_daily: u32, keep_weekly: u32, keep_monthly: u32) -> RetentionPolicy {
+        RetentionPolicy {
+            keep_daily,
+            keep_weekly,
+            keep_monthly,
+        }
+    }
+
+    #[test]
+    fn tiers_overlap_on_the_newest_backup_of_each_period() {
+        let backups: Vec<_> = [
+            "2025-03-10_1200",
+            "2025-03-10_0900",
+            "2025-03-09_1200",
+            "2025-03-05_1200",
+            "2025-03-03_1200",
+            "2025-02-20_1200",
+            "2025-01-15_1200",
+        ]
+        .into_iter()
+        .map(backup)
+        .collect();
+
+        assert_eq!(
+            pruned(&backups, policy(2, 2, 2)),
+            [
+                "2025-01-15_1200",
+                "2025-03-03_1200",
+                "2025-03-05_1200",
+                "2025-03-10_0900"
+            ]
+        );
+    }
+
+    #[test]
+    fn weeks_follow_the_iso_week_year() {
+        // 2024-12-30 is in week 1 of 2025, together with 2025-01-02.
+        let backups: Vec<_> = ["2025-01-02_1200", "2024-12-30_1200", "2024-12-27_1200"]
+            .into_iter()
+            .map(backup)
+            .collect();
+
+        assert_eq!(pruned(&backups, policy(0, 2, 0)), ["2024-12-30_1200"]);
+    }
+
+    #[test]
+    fn newest_is_kept_and_undated_backups_are_never_pruned() {
+        let undated = RemoteBackup::new(
+            "manual".to_string(),
+            "backup_manual.sqlite.zip".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(undated.created_at, None);
+        let backups = vec![
+            backup("2025-03-01_1200"),
+            undated,
+            backup("2025-03-02_1200"),
+        ];
+
+        assert_eq!(pruned(&backups, policy(0, 0, 0)), ["2025-03-01_1200"]);
+        assert!(pruned(&[], policy(0, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn modified_time_dates_backups_without_a_stamp() {
+        let modified = "2025-03-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let backup = RemoteBackup::new(
+            "manual".to_string(),
+            "backup_manual.sqlite.zip".to_string(),
+            None,
+            Some(modified),
+        )
+        .unwrap();
+
+        assert_eq!(backup.created_at, Some(modified));
+        assert!(RemoteBackup::new("x".to_string(), "notes.txt".to_string(), None, None).is_none());
+    }
+
+    /// Creates a migrated database at `path`, then runs `extra` on it.
+    async fn migrated_database(path: &Path, extra: &str) {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
+        let db = sea_orm::SqlxSqliteConnector::from_sqlx_sqlite_pool(pool.clone());
+        Migrator::up(&db, None).await.unwrap();
+        if !extra.is_empty() {
+            sqlx::query(extra).execute(&pool).await.unwrap();
+        }
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn restore_candidate_must_be_a_sqlite_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.db");
+        fs::write(&path, "not a database").unwrap();
+
+        let err = validate_restore_candidate(&path).await.unwrap_err();
+        assert!(err.to_string().contains("not a database backup"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn restore_candidate_needs_the_core_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("other.db");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        let mut conn = SqliteConnection::connect_with(&options).await.unwrap();
+        sqlx::query("CREATE TABLE notes (id INTEGER PRIMARY KEY)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        conn.close().await.unwrap();
+
+        let err = validate_restore_candidate(&path).await.unwrap_err();
+        assert!(err.to_string().contains("missing tables"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn restore_candidate_from_a_newer_app_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("newer.db");
+        migrated_database(
+            &path,
+            "INSERT INTO seaql_migrations (version, applied_at) VALUES ('m999_future', 0)",
+        )
+        .await;
+
+        let err = validate_restore_candidate(&path).await.unwrap_err();
+        assert!(err.to_string().contains("m999_future"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn migrated_database_is_a_valid_restore_candidate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.db");
+        migrated_database(&path, "").await;
+
+        validate_restore_candidate(&path).await.unwrap();
+    }
+}
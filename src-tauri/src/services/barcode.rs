@@ -0,0 +1,60 @@
+use crate::error::{AppError, AppResult};
+
+/// Bar/space module widths for Code 128 symbol values 0..=106. Each pattern
+/// starts with a bar and alternates; 106 is the stop pattern.
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const START_B: usize = 104;
+const STOP: usize = 106;
+
+/// Quiet zone required on each side of the symbol, in modules.
+pub const CODE128_QUIET_ZONE: usize = 10;
+
+/// Encodes `data` as a Code 128 (code set B) symbol.
+///
+/// Returns alternating bar and space widths in modules, starting with a bar.
+/// Only printable ASCII is supported, which covers the generated customer and
+/// order codes.
+pub fn code128_widths(data: &str) -> AppResult<Vec<u8>> {
+    if data.is_empty() {
+        return Err(AppError::invalid_input("Barcode data is empty"));
+    }
+
+    let mut symbols = Vec::with_capacity(data.len() + 3);
+    symbols.push(START_B);
+    for ch in data.chars() {
+        if !(' '..='~').contains(&ch) {
+            return Err(AppError::invalid_input(format!(
+                "Barcode data contains unsupported character: {ch}"
+            )));
+        }
+        symbols.push(ch as usize - 32);
+    }
+
+    let checksum = symbols
+        .iter()
+        .enumerate()
+        .map(|(position, value)| position.max(1) * value)
+        .sum::<usize>()
+        % 103;
+    symbols.push(checksum);
+    symbols.push(STOP);
+
+    Ok(symbols
+        .into_iter()
+        .flat_map(|symbol| CODE128_PATTERNS[symbol].bytes().map(|b| b - b'0'))
+        .collect())
+}
@@ -8,10 +8,16 @@ use tauri::AppHandle;
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::db::DEFAULT_CUSTOMER_ID_PREFIX;
+use crate::db::{ListQuery, ListSort, DEFAULT_CUSTOMER_ID_PREFIX};
 use crate::entities::customers;
 use crate::error::{AppError, AppResult};
-use crate::models::{Customer, PaginatedCustomers};
+use crate::models::{Customer, CustomerListFilter, PaginatedCustomers};
+use crate::services::attachment::{delete_entity_attachments, AttachmentEntityType};
+use crate::services::customer_dedupe::find_customer_matches;
+use crate::services::pagination::{fetch_cursor_page, CursorPage, ListSource};
+use crate::services::phone::{
+    default_phone_country, normalize_phone, phone_search_digits, try_e164,
+};
 use crate::state::AppState;
 use crate::sync::enqueue_sync;
 
@@ -34,7 +40,40 @@ struct NextSeqRow {
     next_seq: i64,
 }
 
+/// Reads the configured customer code prefix and the next free sequence number for it.
+pub(crate) async fn next_customer_code_seq<C: ConnectionTrait>(conn: &C) -> (String, i64) {
+    let prefix_str = PrefixRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "SELECT customer_id_prefix FROM shop_settings ORDER BY created_at DESC LIMIT 1".to_string(),
+    ))
+    .one(conn)
+    .await
+    .unwrap_or(None)
+    .and_then(|r| r.customer_id_prefix)
+    .filter(|p| !p.is_empty())
+    .unwrap_or_else(|| DEFAULT_CUSTOMER_ID_PREFIX.to_string());
+
+    let like_pattern = format!("{}%", prefix_str);
+    let next_seq = NextSeqRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT COALESCE(MAX(CAST(REPLACE(customer_id, ?, '') AS INTEGER)), 0) + 1 AS next_seq \
+         FROM customers WHERE customer_id LIKE ?",
+        [prefix_str.clone().into(), like_pattern.into()],
+    ))
+    .one(conn)
+    .await
+    .unwrap_or(None)
+    .map(|r| r.next_seq)
+    .unwrap_or(1);
+
+    (prefix_str, next_seq)
+}
+
 /// Creates a customer and optionally enqueues initial sync payload.
+///
+/// New customers must have a valid phone number (stored alongside its E.164
+/// form), and ones whose phone or social profile matches an existing customer
+/// are rejected with `DuplicateCustomer` unless `allow_duplicate` is set.
 #[instrument(skip(state, app))]
 #[allow(clippy::too_many_arguments)]
 pub async fn create_customer(
@@ -51,8 +90,43 @@ pub async fn create_customer(
     created_at: Option<String>,
     updated_at: Option<String>,
     deleted_at: Option<String>,
+    allow_duplicate: bool,
 ) -> AppResult<String> {
     let db = state.db.lock().await.clone();
+    let default_country = default_phone_country(app);
+
+    // Records arriving with an id are restores/imports of known rows; they keep
+    // their phone text as-is and only new entries are validated and checked.
+    let (phone, phone_e164) = if id.is_some() {
+        let phone_e164 = try_e164(phone.as_deref(), &default_country);
+        (phone, phone_e164)
+    } else {
+        normalize_phone(phone, &default_country)?
+    };
+
+    if id.is_none() && !allow_duplicate {
+        let matches = find_customer_matches(
+            &db,
+            &name,
+            phone_e164.as_deref(),
+            social_media_url.as_deref(),
+            None,
+            1.0,
+        )
+        .await?;
+        if let Some(existing) = matches.iter().find(|m| m.is_strong()) {
+            return Err(AppError::DuplicateCustomer(format!(
+                "{} ({})",
+                existing
+                    .customer
+                    .customer_id
+                    .as_deref()
+                    .unwrap_or(&existing.customer.id),
+                existing.customer.name
+            )));
+        }
+    }
+
     let record_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
     let normalized_customer_id = customer_id
         .map(|v| v.trim().to_string())
@@ -64,6 +138,7 @@ pub async fn create_customer(
         customer_id: Set(normalized_customer_id.clone()),
         name: Set(name),
         phone: Set(phone),
+        phone_e164: Set(phone_e164),
         address: Set(address),
         city: Set(city),
         social_media_url: Set(social_media_url),
@@ -79,31 +154,7 @@ pub async fn create_customer(
     .await?;
 
     if normalized_customer_id.is_none() {
-        let prefix_str = PrefixRow::find_by_statement(Statement::from_string(
-            DatabaseBackend::Sqlite,
-            "SELECT customer_id_prefix FROM shop_settings ORDER BY created_at DESC LIMIT 1"
-                .to_string(),
-        ))
-        .one(&db)
-        .await
-        .unwrap_or(None)
-        .and_then(|r| r.customer_id_prefix)
-        .filter(|p| !p.is_empty())
-        .unwrap_or_else(|| DEFAULT_CUSTOMER_ID_PREFIX.to_string());
-
-        let like_pattern = format!("{}%", prefix_str);
-        let next_seq = NextSeqRow::find_by_statement(Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
-            "SELECT COALESCE(MAX(CAST(REPLACE(customer_id, ?, '') AS INTEGER)), 0) + 1 AS next_seq \
-             FROM customers WHERE customer_id LIKE ?",
-            [prefix_str.clone().into(), like_pattern.into()],
-        ))
-        .one(&db)
-        .await
-        .unwrap_or(None)
-        .map(|r| r.next_seq)
-        .unwrap_or(1);
-
+        let (prefix_str, next_seq) = next_customer_code_seq(&db).await;
         let new_customer_id = format!("{}{:05}", prefix_str, next_seq);
         let _ = db
             .execute(Statement::from_sql_and_values(
@@ -153,6 +204,63 @@ pub async fn get_customers(state: Arc<AppState>) -> AppResult<Vec<Customer>> {
     Ok(customers)
 }
 
+pub(crate) const CUSTOMER_SELECT: &str =
+    "SELECT id, customer_id, name, phone, address, city, social_media_url, \
+     platform, created_at, updated_at, deleted_at FROM customers";
+
+/// Builds the WHERE/ORDER BY clauses for the customer list from its filters.
+pub(crate) fn build_customer_list_query(filter: &CustomerListFilter) -> AppResult<ListQuery> {
+    let raw_search = filter
+        .search_term
+        .clone()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let search_column = match filter.search_key.as_deref().unwrap_or("name") {
+        "name" => "name",
+        "customerId" => "customer_id",
+        "phone" => "phone",
+        _ => return Err(AppError::invalid_input("Invalid search key")),
+    };
+
+    let sort_column = match filter.sort_by.as_deref().unwrap_or("customer_id") {
+        "name" => "name",
+        "customer_id" => "customer_id",
+        "created_at" => "created_at",
+        _ => "customer_id",
+    };
+    let sort_dir = if filter.sort_order.as_deref() == Some("asc") {
+        "ASC"
+    } else {
+        "DESC"
+    };
+
+    let mut conditions: Vec<String> = vec![];
+    let mut params: Vec<sea_orm::Value> = vec![];
+    if !raw_search.is_empty() {
+        match phone_search_digits(&raw_search).filter(|_| search_column == "phone") {
+            Some(digits) => {
+                conditions.push(
+                    "(COALESCE(phone, '') LIKE ? OR COALESCE(phone_e164, '') LIKE ?)".to_string(),
+                );
+                params.push(format!("%{raw_search}%").into());
+                params.push(format!("%{digits}%").into());
+            }
+            None => {
+                conditions.push(format!("COALESCE({}, '') LIKE ?", search_column));
+                params.push(format!("%{raw_search}%").into());
+            }
+        }
+    }
+
+    Ok(ListQuery::new(
+        conditions,
+        params,
+        format!("ORDER BY {} {}", sort_column, sort_dir),
+    )
+    .with_sort(ListSort::text(sort_column, sort_dir == "DESC", "id")))
+}
+
 /// Loads customers page with filtering and sorting.
 #[instrument(skip(state))]
 pub async fn get_customers_paginated(
@@ -180,111 +288,53 @@ pub async fn get_customers_paginated(
     };
     let offset = if no_limit { 0 } else { (page - 1) * page_size };
 
-    let raw_search = search_term.unwrap_or_default().trim().to_string();
-    let has_search = !raw_search.is_empty();
-    let search_pattern = format!("%{raw_search}%");
-
-    let search_column = match search_key.as_deref().unwrap_or("name") {
-        "name" => "name",
-        "customerId" => "customer_id",
-        "phone" => "phone",
-        _ => return Err(AppError::invalid_input("Invalid search key")),
-    };
+    let ListQuery {
+        where_clause,
+        order_clause,
+        params,
+        ..
+    } = build_customer_list_query(&CustomerListFilter {
+        search_key,
+        search_term,
+        sort_by,
+        sort_order,
+    })?;
+
+    let total = CountRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        &format!("SELECT COUNT(*) as cnt FROM customers {}", where_clause),
+        params.clone(),
+    ))
+    .one(&db)
+    .await?
+    .unwrap_or(CountRow { cnt: 0 })
+    .cnt;
 
-    let sort_column = match sort_by.as_deref().unwrap_or("customer_id") {
-        "name" => "name",
-        "customer_id" => "customer_id",
-        "created_at" => "created_at",
-        _ => "customer_id",
-    };
-    let sort_dir = if sort_order.as_deref() == Some("asc") {
-        "ASC"
+    let data_sql = if no_limit {
+        format!("{} {} {}", CUSTOMER_SELECT, where_clause, order_clause)
     } else {
-        "DESC"
+        format!(
+            "{} {} {} LIMIT ? OFFSET ?",
+            CUSTOMER_SELECT, where_clause, order_clause
+        )
     };
-
-    let base_select = "SELECT id, customer_id, name, phone, address, city, social_media_url, \
-                       platform, created_at, updated_at, deleted_at FROM customers";
-
-    let (total, customers) = if has_search {
-        let count = CountRow::find_by_statement(Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
-            &format!(
-                "SELECT COUNT(*) as cnt FROM customers WHERE COALESCE({}, '') LIKE ?",
-                search_column
-            ),
-            [search_pattern.clone().into()],
-        ))
-        .one(&db)
-        .await?
-        .unwrap_or(CountRow { cnt: 0 })
-        .cnt;
-
-        let data_sql = if no_limit {
-            format!(
-                "{} WHERE COALESCE({}, '') LIKE ? ORDER BY {} {}",
-                base_select, search_column, sort_column, sort_dir
-            )
-        } else {
-            format!(
-                "{} WHERE COALESCE({}, '') LIKE ? ORDER BY {} {} LIMIT ? OFFSET ?",
-                base_select, search_column, sort_column, sort_dir
-            )
-        };
-
-        let rows = if no_limit {
-            Customer::find_by_statement(Statement::from_sql_and_values(
-                DatabaseBackend::Sqlite,
-                &data_sql,
-                [search_pattern.into()],
-            ))
-            .all(&db)
-            .await?
-        } else {
-            Customer::find_by_statement(Statement::from_sql_and_values(
-                DatabaseBackend::Sqlite,
-                &data_sql,
-                [search_pattern.into(), page_size.into(), offset.into()],
-            ))
-            .all(&db)
-            .await?
-        };
-        (count, rows)
+    let data_params: Vec<sea_orm::Value> = if no_limit {
+        params
     } else {
-        let count = CountRow::find_by_statement(Statement::from_string(
-            DatabaseBackend::Sqlite,
-            "SELECT COUNT(*) as cnt FROM customers".to_string(),
-        ))
-        .one(&db)
-        .await?
-        .unwrap_or(CountRow { cnt: 0 })
-        .cnt;
-
-        let data_sql = if no_limit {
-            format!("{} ORDER BY {} {}", base_select, sort_column, sort_dir)
-        } else {
-            format!(
-                "{} ORDER BY {} {} LIMIT ? OFFSET ?",
-                base_select, sort_column, sort_dir
-            )
-        };
-
-        let rows = if no_limit {
-            Customer::find_by_statement(Statement::from_string(DatabaseBackend::Sqlite, data_sql))
-                .all(&db)
-                .await?
-        } else {
-            Customer::find_by_statement(Statement::from_sql_and_values(
-                DatabaseBackend::Sqlite,
-                &data_sql,
-                [page_size.into(), offset.into()],
-            ))
-            .all(&db)
-            .await?
-        };
-        (count, rows)
+        let mut p = params;
+        p.push(page_size.into());
+        p.push(offset.into());
+        p
     };
 
+    let customers = Customer::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        &data_sql,
+        data_params,
+    ))
+    .all(&db)
+    .await?;
+
     let response_page_size = if no_limit { total.max(0) } else { page_size };
     let total_pages = if total == 0 {
         0
@@ -303,6 +353,32 @@ pub async fn get_customers_paginated(
     })
 }
 
+/// Loads one cursor page of the customer list.
+#[instrument(skip(state))]
+pub async fn get_customers_page(
+    state: Arc<AppState>,
+    filter: CustomerListFilter,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: bool,
+) -> AppResult<CursorPage<Customer>> {
+    let db = state.db.lock().await.clone();
+    let query = build_customer_list_query(&filter)?;
+    fetch_cursor_page(
+        &db,
+        ListSource {
+            select: CUSTOMER_SELECT,
+            group_by: "",
+            count_from: "FROM customers",
+        },
+        query,
+        cursor,
+        limit,
+        include_total,
+    )
+    .await
+}
+
 /// Loads a single customer by id.
 #[instrument(skip(state))]
 pub async fn get_customer(state: Arc<AppState>, id: String) -> AppResult<Customer> {
@@ -314,7 +390,33 @@ pub async fn get_customer(state: Arc<AppState>, id: String) -> AppResult<Custome
         .ok_or_else(|| AppError::not_found("Customer not found"))
 }
 
-/// Updates customer row and enqueues sync payload.
+/// Validates the phone only when it differs from the stored one, so customers
+/// whose phone text predates validation can still be edited.
+async fn phone_for_update<C: ConnectionTrait>(
+    conn: &C,
+    id: &str,
+    phone: Option<String>,
+    default_country: &str,
+) -> AppResult<(Option<String>, Option<String>)> {
+    let stored = customers::Entity::find_by_id(id)
+        .into_model::<Customer>()
+        .one(conn)
+        .await?
+        .and_then(|customer| customer.phone);
+    let unchanged = phone
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .is_some_and(|p| stored.as_deref().map(str::trim) == Some(p));
+    if unchanged {
+        let phone_e164 = try_e164(phone.as_deref(), default_country);
+        return Ok((phone, phone_e164));
+    }
+    normalize_phone(phone, default_country)
+}
+
+/// Updates customer row and enqueues sync payload. A changed phone number
+/// must be valid.
 #[instrument(skip(state, app))]
 #[allow(clippy::too_many_arguments)]
 pub async fn update_customer(
@@ -336,11 +438,13 @@ pub async fn update_customer(
     let normalized_customer_id = customer_id
         .map(|v| v.trim().to_string())
         .filter(|v| !v.is_empty());
+    let (phone, phone_e164) =
+        phone_for_update(&db, &id, phone, &default_phone_country(app)).await?;
 
     db.execute(Statement::from_sql_and_values(
         DatabaseBackend::Sqlite,
-        "UPDATE customers SET customer_id = ?, name = ?, phone = ?, address = ?, city = ?, \
-         social_media_url = ?, platform = ?, \
+        "UPDATE customers SET customer_id = ?, name = ?, phone = ?, phone_e164 = ?, address = ?, \
+         city = ?, social_media_url = ?, platform = ?, \
          created_at = COALESCE(?, created_at), \
          updated_at = COALESCE(?, datetime('now')), \
          deleted_at = ? WHERE id = ?",
@@ -348,6 +452,7 @@ pub async fn update_customer(
             normalized_customer_id.into(),
             name.into(),
             phone.into(),
+            phone_e164.into(),
             address.into(),
             city.into(),
             social_media_url.into(),
@@ -392,6 +497,11 @@ pub async fn delete_customer(state: Arc<AppState>, app: &AppHandle, id: String)
     ))
     .await?;
 
+    if let Err(err) = delete_entity_attachments(&db, app, AttachmentEntityType::Customer, &id).await
+    {
+        tracing::warn!("Failed to remove attachments of customer {id}: {err}");
+    }
+
     if let Ok(Some(record)) = customers::Entity::find_by_id(id.clone())
         .into_model::<Customer>()
         .one(&db)
@@ -411,3 +521,55 @@ pub async fn delete_customer(state: Arc<AppState>, app: &AppHandle, id: String)
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::phone::DEFAULT_PHONE_COUNTRY;
+
+    #[tokio::test]
+    async fn legacy_phone_is_kept_when_only_other_fields_change() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        db.execute_unprepared(
+            "INSERT INTO customers (id, customer_id, name, phone) \
+             VALUES ('c1', 'CUST-1', 'Aung Aung', 'call after 5pm')",
+        )
+        .await
+        .unwrap();
+
+        let unchanged = phone_for_update(
+            &db,
+            "c1",
+            Some("call after 5pm".to_string()),
+            DEFAULT_PHONE_COUNTRY,
+        )
+        .await
+        .unwrap();
+        assert_eq!(unchanged, (Some("call after 5pm".to_string()), None));
+
+        let changed = phone_for_update(
+            &db,
+            "c1",
+            Some("call before 5pm".to_string()),
+            DEFAULT_PHONE_COUNTRY,
+        )
+        .await;
+        assert!(matches!(changed, Err(AppError::InvalidInput(_))));
+
+        let fixed = phone_for_update(
+            &db,
+            "c1",
+            Some("09 791 234 567".to_string()),
+            DEFAULT_PHONE_COUNTRY,
+        )
+        .await
+        .unwrap();
+        assert_eq!(fixed.1.as_deref(), Some("+959791234567"));
+
+        let cleared = phone_for_update(&db, "c1", None, DEFAULT_PHONE_COUNTRY)
+            .await
+            .unwrap();
+        assert_eq!(cleared, (None, None));
+    }
+}
@@ -0,0 +1,822 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement,
+    TransactionTrait,
+};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Customer, Order};
+use crate::services::customer::CUSTOMER_SELECT;
+use crate::services::phone::{default_phone_country, try_e164};
+use crate::state::AppState;
+use crate::sync::enqueue_sync;
+
+/// Names at least this similar (0..=1, edit-distance based) are reported as duplicates.
+const DEFAULT_NAME_SIMILARITY: f64 = 0.85;
+
+/// Shorter names only match when identical after normalization.
+const MIN_FUZZY_NAME_CHARS: usize = 4;
+
+pub const REASON_PHONE: &str = "phone";
+pub const REASON_SOCIAL_MEDIA_URL: &str = "social_media_url";
+pub const REASON_NAME: &str = "name";
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateCandidate {
+    /// The older record, suggested as the merge survivor.
+    pub customer: Customer,
+    pub duplicate: Customer,
+    pub reasons: Vec<&'static str>,
+    pub name_similarity: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CustomerMatch {
+    pub customer: Customer,
+    pub reasons: Vec<&'static str>,
+    pub name_similarity: f64,
+}
+
+impl CustomerMatch {
+    /// Phone or social profile matches are treated as the same person; names alone are not.
+    pub fn is_strong(&self) -> bool {
+        self.reasons
+            .iter()
+            .any(|r| *r == REASON_PHONE || *r == REASON_SOCIAL_MEDIA_URL)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CustomerMergeResult {
+    pub merge_id: String,
+    pub survivor: Customer,
+    pub moved_order_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CustomerMergeRecord {
+    pub id: String,
+    pub survivor_id: String,
+    pub merged_id: String,
+    pub survivor_snapshot: serde_json::Value,
+    pub merged_snapshot: serde_json::Value,
+    pub moved_order_ids: Vec<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CustomerMergeRow {
+    id: String,
+    survivor_id: String,
+    merged_id: String,
+    survivor_snapshot: String,
+    merged_snapshot: String,
+    moved_order_ids: String,
+    created_at: Option<String>,
+}
+
+impl From<CustomerMergeRow> for CustomerMergeRecord {
+    fn from(row: CustomerMergeRow) -> Self {
+        Self {
+            id: row.id,
+            survivor_id: row.survivor_id,
+            merged_id: row.merged_id,
+            survivor_snapshot: serde_json::from_str(&row.survivor_snapshot).unwrap_or_default(),
+            merged_snapshot: serde_json::from_str(&row.merged_snapshot).unwrap_or_default(),
+            moved_order_ids: serde_json::from_str(&row.moved_order_ids).unwrap_or_default(),
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct IdRow {
+    id: String,
+}
+
+/// Comparison keys derived from a customer's identifying fields.
+struct MatchKeys {
+    /// The stored E.164 number, so formatting differences never matter.
+    phone: Option<String>,
+    social: Option<String>,
+    /// The normalized name as chars, so lengths count characters, not bytes.
+    name_chars: Vec<char>,
+}
+
+impl MatchKeys {
+    fn new(name: &str, phone_e164: Option<&str>, social_media_url: Option<&str>) -> Self {
+        Self {
+            phone: phone_e164.map(str::to_string).filter(|p| !p.is_empty()),
+            social: social_media_url.and_then(social_url_key),
+            name_chars: normalize_name(name).chars().collect(),
+        }
+    }
+
+    fn compare(&self, other: &Self, min_similarity: f64) -> (Vec<&'static str>, f64) {
+        let mut reasons = Vec::new();
+        if self.phone.is_some() && self.phone == other.phone {
+            reasons.push(REASON_PHONE);
+        }
+        if self.social.is_some() && self.social == other.social {
+            reasons.push(REASON_SOCIAL_MEDIA_URL);
+        }
+        let similarity = name_similarity(&self.name_chars, &other.name_chars);
+        let fuzzy_allowed =
+            self.name_chars.len().min(other.name_chars.len()) >= MIN_FUZZY_NAME_CHARS;
+        if similarity >= 1.0 || (fuzzy_allowed && similarity >= min_similarity) {
+            reasons.push(REASON_NAME);
+        }
+        (reasons, similarity)
+    }
+}
+
+/// Lowercased host + path without scheme, `www.`/`m.` prefixes, query or trailing slash.
+/// Facebook `profile.php?id=` links keep their id since it is the whole identity.
+fn social_url_key(url: &str) -> Option<String> {
+    let url = url.trim().to_lowercase();
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(&url);
+    let rest = ["www.", "m.", "web.", "mobile."]
+        .iter()
+        .find_map(|prefix| rest.strip_prefix(prefix))
+        .unwrap_or(rest);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let path = path
+        .split('#')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+
+    let profile_id = path
+        .ends_with("/profile.php")
+        .then(|| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("id="))
+                .map(str::to_string)
+        })
+        .flatten();
+    let key = match profile_id {
+        Some(id) => format!("{path}?id={id}"),
+        None => path.to_string(),
+    };
+    key.contains('/').then_some(key)
+}
+
+/// Lowercase with punctuation dropped and whitespace collapsed.
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| !c.is_ascii_punctuation())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn name_similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[derive(Debug, FromQueryResult)]
+struct PhoneKeyRow {
+    id: String,
+    phone_e164: Option<String>,
+}
+
+/// Active customers, oldest first, with their comparison keys.
+async fn load_active_customers<C: ConnectionTrait>(
+    conn: &C,
+) -> AppResult<Vec<(Customer, MatchKeys)>> {
+    let customers = Customer::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{} WHERE deleted_at IS NULL ORDER BY created_at ASC, id ASC",
+            CUSTOMER_SELECT
+        ),
+    ))
+    .all(conn)
+    .await?;
+    let phones: HashMap<String, String> = PhoneKeyRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "SELECT id, phone_e164 FROM customers WHERE deleted_at IS NULL AND phone_e164 IS NOT NULL",
+    ))
+    .all(conn)
+    .await?
+    .into_iter()
+    .filter_map(|row| Some((row.id, row.phone_e164?)))
+    .collect();
+
+    Ok(customers
+        .into_iter()
+        .map(|customer| {
+            let keys = MatchKeys::new(
+                &customer.name,
+                phones.get(&customer.id).map(String::as_str),
+                customer.social_media_url.as_deref(),
+            );
+            (customer, keys)
+        })
+        .collect())
+}
+
+fn normalize_similarity(min_name_similarity: Option<f64>) -> f64 {
+    min_name_similarity
+        .filter(|v| v.is_finite())
+        .map(|v| v.clamp(0.5, 1.0))
+        .unwrap_or(DEFAULT_NAME_SIMILARITY)
+}
+
+/// Finds existing customers that look like the given name/phone/social profile.
+pub(crate) async fn find_customer_matches<C: ConnectionTrait>(
+    conn: &C,
+    name: &str,
+    phone_e164: Option<&str>,
+    social_media_url: Option<&str>,
+    exclude_id: Option<&str>,
+    min_name_similarity: f64,
+) -> AppResult<Vec<CustomerMatch>> {
+    let keys = MatchKeys::new(name, phone_e164, social_media_url);
+    let mut matches: Vec<CustomerMatch> = load_active_customers(conn)
+        .await?
+        .into_iter()
+        .filter(|(c, _)| Some(c.id.as_str()) != exclude_id)
+        .filter_map(|(customer, other)| {
+            let (reasons, name_similarity) = keys.compare(&other, min_name_similarity);
+            (!reasons.is_empty()).then_some(CustomerMatch {
+                customer,
+                reasons,
+                name_similarity,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.is_strong()
+            .cmp(&a.is_strong())
+            .then(b.reasons.len().cmp(&a.reasons.len()))
+            .then(b.name_similarity.total_cmp(&a.name_similarity))
+    });
+    Ok(matches)
+}
+
+/// Lists existing customers that look like the given details, strongest first.
+#[instrument(skip(state, app))]
+pub async fn check_customer_duplicates(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    name: String,
+    phone: Option<String>,
+    social_media_url: Option<String>,
+    exclude_id: Option<String>,
+    min_name_similarity: Option<f64>,
+) -> AppResult<Vec<CustomerMatch>> {
+    let db = state.db.lock().await.clone();
+    let phone_e164 = try_e164(phone.as_deref(), &default_phone_country(app));
+    find_customer_matches(
+        &db,
+        &name,
+        phone_e164.as_deref(),
+        social_media_url.as_deref(),
+        exclude_id.as_deref(),
+        normalize_similarity(min_name_similarity),
+    )
+    .await
+}
+
+/// Scans all active customers for likely duplicate pairs.
+#[instrument(skip(state))]
+pub async fn find_duplicate_customers(
+    state: Arc<AppState>,
+    min_name_similarity: Option<f64>,
+) -> AppResult<Vec<DuplicateCandidate>> {
+    let min_similarity = normalize_similarity(min_name_similarity);
+    let db = state.db.lock().await.clone();
+    let (customers, keys): (Vec<Customer>, Vec<MatchKeys>) =
+        load_active_customers(&db).await?.into_iter().unzip();
+
+    // Customers are ordered oldest first, so `i < j` keeps the older one as survivor.
+    let mut pairs: HashMap<(usize, usize), (Vec<&'static str>, f64)> = HashMap::new();
+    let mut by_phone: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_social: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        if let Some(phone) = key.phone.as_deref() {
+            by_phone.entry(phone).or_default().push(index);
+        }
+        if let Some(social) = key.social.as_deref() {
+            by_social.entry(social).or_default().push(index);
+        }
+    }
+    for group in by_phone.values().chain(by_social.values()) {
+        for (n, &i) in group.iter().enumerate() {
+            for &j in &group[n + 1..] {
+                pairs
+                    .entry((i, j))
+                    .or_insert_with(|| keys[i].compare(&keys[j], min_similarity));
+            }
+        }
+    }
+
+    // Names can only reach the threshold when their lengths are close, so compare
+    // each name against neighbours in length order and stop once they drift apart.
+    let mut by_length: Vec<usize> = (0..keys.len()).collect();
+    by_length.sort_by_key(|&i| keys[i].name_chars.len());
+    for (n, &a) in by_length.iter().enumerate() {
+        let len_a = keys[a].name_chars.len();
+        for &b in &by_length[n + 1..] {
+            let len_b = keys[b].name_chars.len();
+            if len_b > len_a && (len_b - len_a) as f64 > (1.0 - min_similarity) * len_b as f64 {
+                break;
+            }
+            let pair = (a.min(b), a.max(b));
+            if pairs.contains_key(&pair) {
+                continue;
+            }
+            let (reasons, similarity) = keys[pair.0].compare(&keys[pair.1], min_similarity);
+            if !reasons.is_empty() {
+                pairs.insert(pair, (reasons, similarity));
+            }
+        }
+    }
+
+    let mut candidates: Vec<DuplicateCandidate> = pairs
+        .into_iter()
+        .map(|((i, j), (reasons, name_similarity))| DuplicateCandidate {
+            customer: customers[i].clone(),
+            duplicate: customers[j].clone(),
+            reasons,
+            name_similarity,
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.reasons
+            .len()
+            .cmp(&a.reasons.len())
+            .then(b.name_similarity.total_cmp(&a.name_similarity))
+            .then(a.customer.id.cmp(&b.customer.id))
+    });
+    Ok(candidates)
+}
+
+async fn load_customer<C: ConnectionTrait>(conn: &C, id: &str) -> AppResult<Customer> {
+    Customer::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{} WHERE id = ? AND deleted_at IS NULL", CUSTOMER_SELECT),
+        [id.into()],
+    ))
+    .one(conn)
+    .await?
+    .ok_or_else(|| AppError::not_found(format!("Customer not found: {id}")))
+}
+
+/// Merges `merged_id` into `survivor_id`.
+///
+/// Orders and attachments are re-pointed to the survivor, blank survivor
+/// fields are filled from the merged customer, the merged customer is
+/// soft-deleted and the merge is recorded in `customer_merges`.
+#[instrument(skip(state, app))]
+pub async fn merge_customers(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    survivor_id: String,
+    merged_id: String,
+) -> AppResult<CustomerMergeResult> {
+    if survivor_id == merged_id {
+        return Err(AppError::invalid_input(
+            "Cannot merge a customer into itself",
+        ));
+    }
+
+    let db = state.db.lock().await.clone();
+    let (merge_id, moved_order_ids) = merge_records(&db, &survivor_id, &merged_id).await?;
+
+    enqueue_merge_sync(&state, app, &db, &survivor_id, &merged_id, &moved_order_ids).await;
+
+    let survivor = load_customer(&db, &survivor_id).await?;
+    Ok(CustomerMergeResult {
+        merge_id,
+        survivor,
+        moved_order_count: moved_order_ids.len(),
+    })
+}
+
+/// The database side of `merge_customers`, in one transaction. Returns the
+/// merge id and the orders moved to the survivor.
+async fn merge_records(
+    db: &DatabaseConnection,
+    survivor_id: &str,
+    merged_id: &str,
+) -> AppResult<(String, Vec<String>)> {
+    let txn = db.begin().await?;
+    let survivor = load_customer(&txn, survivor_id).await?;
+    let merged = load_customer(&txn, merged_id).await?;
+
+    let moved_order_ids: Vec<String> = IdRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT id FROM orders WHERE customer_id = ?",
+        [merged_id.into()],
+    ))
+    .all(&txn)
+    .await?
+    .into_iter()
+    .map(|row| row.id)
+    .collect();
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE orders SET customer_id = ?, updated_at = datetime('now') WHERE customer_id = ?",
+        [survivor_id.into(), merged_id.into()],
+    ))
+    .await?;
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE customers SET \
+         phone_e164 = CASE WHEN NULLIF(TRIM(phone), '') IS NULL \
+             THEN (SELECT phone_e164 FROM customers WHERE id = ?) ELSE phone_e164 END, \
+         phone = COALESCE(NULLIF(TRIM(phone), ''), ?), \
+         address = COALESCE(NULLIF(TRIM(address), ''), ?), \
+         city = COALESCE(NULLIF(TRIM(city), ''), ?), \
+         social_media_url = COALESCE(NULLIF(TRIM(social_media_url), ''), ?), \
+         platform = COALESCE(NULLIF(TRIM(platform), ''), ?), \
+         updated_at = datetime('now') WHERE id = ?",
+        [
+            merged_id.into(),
+            merged.phone.clone().into(),
+            merged.address.clone().into(),
+            merged.city.clone().into(),
+            merged.social_media_url.clone().into(),
+            merged.platform.clone().into(),
+            survivor_id.into(),
+        ],
+    ))
+    .await?;
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE customers SET deleted_at = datetime('now'), updated_at = datetime('now') WHERE id = ?",
+        [merged_id.into()],
+    ))
+    .await?;
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE attachments SET entity_id = ?, updated_at = datetime('now') \
+         WHERE entity_type = 'customer' AND entity_id = ?",
+        [survivor_id.into(), merged_id.into()],
+    ))
+    .await?;
+
+    let merge_id = Uuid::new_v4().to_string();
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO customer_merges (id, survivor_id, merged_id, survivor_snapshot, \
+         merged_snapshot, moved_order_ids) VALUES (?, ?, ?, ?, ?, ?)",
+        [
+            merge_id.clone().into(),
+            survivor_id.into(),
+            merged_id.into(),
+            serde_json::to_string(&survivor)?.into(),
+            serde_json::to_string(&merged)?.into(),
+            serde_json::to_string(&moved_order_ids)?.into(),
+        ],
+    ))
+    .await?;
+
+    txn.commit().await?;
+    Ok((merge_id, moved_order_ids))
+}
+
+/// Survivor update first, then the moved orders, then the merged customer's
+/// delete, so the remote never sees orders pointing at a deleted customer.
+async fn enqueue_merge_sync(
+    state: &AppState,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    survivor_id: &str,
+    merged_id: &str,
+    moved_order_ids: &[String],
+) {
+    let pool = state.pool.lock().await;
+
+    if let Ok(Some(record)) = Customer::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{} WHERE id = ?", CUSTOMER_SELECT),
+        [survivor_id.into()],
+    ))
+    .one(db)
+    .await
+    {
+        enqueue_sync(
+            &pool,
+            app,
+            "customers",
+            "UPDATE",
+            survivor_id,
+            serde_json::json!(record),
+        )
+        .await;
+    }
+
+    for order_id in moved_order_ids {
+        if let Ok(Some(order)) = Order::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT * FROM orders WHERE id = ?",
+            [order_id.clone().into()],
+        ))
+        .one(db)
+        .await
+        {
+            enqueue_sync(
+                &pool,
+                app,
+                "orders",
+                "UPDATE",
+                order_id,
+                serde_json::json!(order),
+            )
+            .await;
+        }
+    }
+
+    if let Ok(Some(record)) = Customer::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{} WHERE id = ?", CUSTOMER_SELECT),
+        [merged_id.into()],
+    ))
+    .one(db)
+    .await
+    {
+        enqueue_sync(
+            &pool,
+            app,
+            "customers",
+            "DELETE",
+            merged_id,
+            serde_json::json!(record),
+        )
+        .await;
+    }
+}
+
+/// Lists recorded merges, newest first, optionally for one customer.
+#[instrument(skip(state))]
+pub async fn get_customer_merges(
+    state: Arc<AppState>,
+    customer_id: Option<String>,
+) -> AppResult<Vec<CustomerMergeRecord>> {
+    let db = state.db.lock().await.clone();
+    let statement = match customer_id {
+        Some(id) => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT * FROM customer_merges WHERE survivor_id = ? OR merged_id = ? \
+             ORDER BY created_at DESC",
+            [id.clone().into(), id.into()],
+        ),
+        None => Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT * FROM customer_merges ORDER BY created_at DESC",
+        ),
+    };
+
+    Ok(CustomerMergeRow::find_by_statement(statement)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(CustomerMergeRecord::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(value: &str) -> Vec<char> {
+        value.chars().collect()
+    }
+
+    #[test]
+    fn levenshtein_counts_edits_by_character() {
+        assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(levenshtein(&chars(""), &chars("abc")), 3);
+        assert_eq!(levenshtein(&chars("aung"), &chars("aung")), 0);
+        assert_eq!(levenshtein(&chars("အောင်"), &chars("အောင")), 1);
+    }
+
+    #[test]
+    fn normalize_name_drops_punctuation_and_extra_spaces() {
+        assert_eq!(normalize_name("  Aung.  AUNG, "), "aung aung");
+        assert_eq!(normalize_name("U  Ba-Tin"), "u batin");
+        assert_eq!(normalize_name("မောင် မောင်"), "မောင် မောင်");
+    }
+
+    #[test]
+    fn social_url_key_ignores_scheme_prefix_and_query() {
+        assert_eq!(
+            social_url_key("https://www.facebook.com/Aung.Aung/?ref=bookmarks").as_deref(),
+            Some("facebook.com/aung.aung")
+        );
+        assert_eq!(
+            social_url_key("m.facebook.com/aung.aung#about").as_deref(),
+            Some("facebook.com/aung.aung")
+        );
+        assert_eq!(
+            social_url_key("http://web.facebook.com/profile.php?ref=x&id=1001").as_deref(),
+            Some("facebook.com/profile.php?id=1001")
+        );
+        assert_eq!(social_url_key("facebook"), None);
+    }
+
+    #[test]
+    fn compare_reports_each_matching_key() {
+        let keys = MatchKeys::new(
+            "Aung Kyaw",
+            Some("+959791234567"),
+            Some("https://facebook.com/aungkyaw"),
+        );
+        let same_person = MatchKeys::new(
+            "Aung Kyau",
+            Some("+959791234567"),
+            Some("facebook.com/aungkyaw/"),
+        );
+        let (reasons, similarity) = keys.compare(&same_person, DEFAULT_NAME_SIMILARITY);
+        assert_eq!(
+            reasons,
+            [REASON_PHONE, REASON_SOCIAL_MEDIA_URL, REASON_NAME]
+        );
+        assert!((similarity - 8.0 / 9.0).abs() < 1e-9);
+
+        let stranger = MatchKeys::new("Su Su", Some("+959791234568"), None);
+        assert!(keys
+            .compare(&stranger, DEFAULT_NAME_SIMILARITY)
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn short_names_only_match_exactly() {
+        let ko = MatchKeys::new("Ko", None, None);
+        assert!(ko
+            .compare(&MatchKeys::new("Ka", None, None), 0.5)
+            .0
+            .is_empty());
+        assert_eq!(
+            ko.compare(&MatchKeys::new("ko.", None, None), 0.5).0,
+            [REASON_NAME]
+        );
+    }
+
+    async fn insert_customer(
+        db: &DatabaseConnection,
+        id: &str,
+        name: &str,
+        phone_e164: Option<&str>,
+        city: Option<&str>,
+    ) {
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO customers (id, customer_id, name, phone, phone_e164, city, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, datetime('2025-01-01', '+' || ? || ' minutes'))",
+            [
+                id.into(),
+                format!("CUST-{id}").into(),
+                name.into(),
+                phone_e164.into(),
+                phone_e164.into(),
+                city.into(),
+                (id.len() as i64).into(),
+            ],
+        ))
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn finds_duplicates_by_phone_and_by_similar_names() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        insert_customer(&db, "a", "Aung Kyaw Win", Some("+959791234567"), None).await;
+        insert_customer(&db, "bb", "Aung Kyaw Wyn", None, None).await;
+        insert_customer(&db, "ccc", "Zaw", Some("+959791234567"), None).await;
+        insert_customer(&db, "dddd", "Su Su Hlaing Myint", None, None).await;
+        insert_customer(&db, "eeeee", "အောင်ကျော်ဝင်း", None, None).await;
+        insert_customer(&db, "ffffff", "အောင်ကျော်ဝင်", None, None).await;
+
+        let mut pairs: Vec<_> = find_duplicate_customers(state.clone(), None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.customer.id, c.duplicate.id, c.reasons))
+            .collect();
+        pairs.sort();
+
+        let expected = [
+            ("a", "bb", vec![REASON_NAME]),
+            ("a", "ccc", vec![REASON_PHONE]),
+            ("eeeee", "ffffff", vec![REASON_NAME]),
+        ]
+        .map(|(a, b, reasons)| (a.to_string(), b.to_string(), reasons));
+        assert_eq!(pairs, expected);
+    }
+
+    #[tokio::test]
+    async fn merge_moves_orders_and_attachments_and_records_the_merge() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        insert_customer(&db, "keep", "Aung Kyaw", None, None).await;
+        insert_customer(
+            &db,
+            "gone",
+            "Aung Kyau",
+            Some("+959791234567"),
+            Some("Yangon"),
+        )
+        .await;
+        db.execute_unprepared(
+            "INSERT INTO orders (id, order_id, customer_id) VALUES ('o1', 'ORD-1', 'gone'); \
+             INSERT INTO attachments (id, entity_type, entity_id, file_name, content_type, \
+             size_bytes, local_path) VALUES ('f1', 'customer', 'gone', 'id.jpg', 'image/jpeg', \
+             1, 'attachments/id.jpg')",
+        )
+        .await
+        .unwrap();
+
+        let (merge_id, moved) = merge_records(&db, "keep", "gone").await.unwrap();
+        assert_eq!(moved, ["o1"]);
+
+        let scalar = |sql: &'static str| {
+            let db = db.clone();
+            async move {
+                db.query_one(Statement::from_string(DatabaseBackend::Sqlite, sql))
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .try_get_by_index::<Option<String>>(0)
+                    .unwrap()
+            }
+        };
+        assert_eq!(
+            scalar("SELECT customer_id FROM orders WHERE id = 'o1'")
+                .await
+                .as_deref(),
+            Some("keep")
+        );
+        assert_eq!(
+            scalar("SELECT entity_id FROM attachments WHERE id = 'f1'")
+                .await
+                .as_deref(),
+            Some("keep")
+        );
+        assert!(scalar("SELECT deleted_at FROM customers WHERE id = 'gone'")
+            .await
+            .is_some());
+        assert_eq!(
+            scalar("SELECT phone_e164 || ' ' || city FROM customers WHERE id = 'keep'")
+                .await
+                .as_deref(),
+            Some("+959791234567 Yangon")
+        );
+
+        let merges = CustomerMergeRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT * FROM customer_merges",
+        ))
+        .all(&db)
+        .await
+        .unwrap();
+        let record = CustomerMergeRecord::from(merges.into_iter().next().unwrap());
+        assert_eq!(record.id, merge_id);
+        assert_eq!(
+            (record.survivor_id.as_str(), record.merged_id.as_str()),
+            ("keep", "gone")
+        );
+        assert_eq!(record.moved_order_ids, ["o1"]);
+        assert_eq!(record.merged_snapshot["city"], "Yangon");
+
+        let err = merge_records(&db, "keep", "gone").await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)), "{err}");
+    }
+}
@@ -1,19 +1,23 @@
-use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
-use chrono::Utc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tokio::time::timeout;
 use tracing::instrument;
-use zip::write::SimpleFileOptions;
-use zip::ZipWriter;
 
 use crate::error::{AppError, AppResult};
+use crate::services::backup::{
+    archive_content_type, create_backup_archive, BackupDestination, RemoteBackup,
+};
+use crate::services::vault::{is_sealed, reveal_secret, seal_secret};
+use crate::state::AppState;
 
 const REDIRECT_URI: &str = "http://127.0.0.1:3456";
 const AUTH_URI: &str = "https://accounts.google.com/o/oauth2/v2/auth";
@@ -21,6 +25,7 @@ const TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
 const DRIVE_UPLOAD_URI: &str =
     "https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart";
 const USER_INFO_URI: &str = "https://www.googleapis.com/oauth2/v2/userinfo";
+const DRIVE_FILES_URI: &str = "https://www.googleapis.com/drive/v3/files";
 
 fn get_client_id() -> AppResult<String> {
     let _ = dotenvy::dotenv();
@@ -57,6 +62,24 @@ struct TokenResponse {
     expires_in: Option<i64>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveFileList {
+    #[serde(default)]
+    files: Vec<DriveFile>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveFile {
+    id: String,
+    name: String,
+    /// Decimal string.
+    size: Option<String>,
+    created_time: Option<DateTime<Utc>>,
+}
+
 #[derive(Deserialize)]
 struct UserInfoResponse {
     #[serde(default)]
@@ -73,17 +96,46 @@ pub fn read_tokens(app: &AppHandle) -> AppResult<DriveTokens> {
     if !path.exists() {
         return Err(AppError::not_found("No tokens found"));
     }
-    let data = fs::read_to_string(path)?;
+    let data = reveal_secret(fs::read_to_string(path)?.trim())?;
     Ok(serde_json::from_str(&data)?)
 }
 
+/// Like `read_tokens`, but a missing token file means Drive is not connected.
+fn read_connected_tokens(app: &AppHandle) -> AppResult<DriveTokens> {
+    read_tokens(app).map_err(|err| match err {
+        AppError::NotFound(_) => AppError::invalid_input("Google Drive is not connected"),
+        other => other,
+    })
+}
+
 fn save_tokens(app: &AppHandle, tokens: &DriveTokens) -> AppResult<()> {
     let path = get_tokens_path(app)?;
-    let data = serde_json::to_string(tokens)?;
+    let data = seal_secret(&serde_json::to_string(tokens)?)?;
     fs::write(path, data)?;
     Ok(())
 }
 
+/// Encrypts a token file written before the vault existed. Returns whether
+/// there was one.
+pub(crate) fn seal_plaintext_tokens(app: &AppHandle) -> AppResult<bool> {
+    let path = get_tokens_path(app)?;
+    if !path.exists() || is_sealed(fs::read_to_string(&path)?.trim()) {
+        return Ok(false);
+    }
+    let tokens = read_tokens(app)?;
+    save_tokens(app, &tokens)?;
+    Ok(true)
+}
+
+/// Removes a token file the vault can no longer decrypt.
+pub(crate) fn clear_sealed_tokens(app: &AppHandle) -> AppResult<()> {
+    let path = get_tokens_path(app)?;
+    if path.exists() && is_sealed(fs::read_to_string(&path)?.trim()) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
 /// Starts OAuth flow and stores Google Drive tokens.
 #[instrument(skip(app))]
 pub async fn start_google_oauth(app: &AppHandle) -> AppResult<DriveStatus> {
@@ -197,6 +249,10 @@ pub async fn get_drive_connection_status(app: &AppHandle) -> AppResult<DriveStat
             connected: true,
             email: tokens.email,
         }),
+        Err(AppError::VaultLocked) => Ok(DriveStatus {
+            connected: true,
+            email: None,
+        }),
         Err(_) => Ok(DriveStatus {
             connected: false,
             email: None,
@@ -254,54 +310,38 @@ async fn refresh_token_if_needed(app: &AppHandle, tokens: &mut DriveTokens) -> A
 }
 
 /// Triggers an immediate Google Drive backup upload.
-#[instrument(skip(app))]
-pub async fn trigger_drive_backup(app: &AppHandle) -> AppResult<String> {
-    perform_drive_backup(app).await
+#[instrument(skip(state, app))]
+pub async fn trigger_drive_backup(state: Arc<AppState>, app: &AppHandle) -> AppResult<String> {
+    perform_drive_backup(state, app).await
 }
 
 /// Performs DB zip backup and uploads it to Google Drive.
-#[instrument(skip(app))]
-pub async fn perform_drive_backup(app: &AppHandle) -> AppResult<String> {
-    let mut tokens = read_tokens(app)?;
-    refresh_token_if_needed(app, &mut tokens).await?;
-
-    let app_data_dir = app.path().app_data_dir()?;
-    let db_path = app_data_dir.join("shop.db");
-    if !db_path.exists() {
-        return Err(AppError::not_found("Database file not found"));
-    }
+#[instrument(skip(state, app))]
+pub async fn perform_drive_backup(state: Arc<AppState>, app: &AppHandle) -> AppResult<String> {
+    let (zip_filename, zip_content) = create_backup_archive(&state, app).await?;
+    upload_backup_archive(app, &zip_filename, zip_content).await?;
+    Ok("Backup uploaded successfully".into())
+}
 
-    let timestamp = Utc::now().format("%Y-%m-%d_%H%M").to_string();
-    let zip_filename = format!("backup_{}.sqlite.zip", timestamp);
-    let zip_path = app_data_dir.join(&zip_filename);
-
-    {
-        let file = File::create(&zip_path)?;
-        let mut zip = ZipWriter::new(file);
-        let options =
-            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-
-        zip.start_file("shop.db", options)
-            .map_err(|e| AppError::internal(e.to_string()))?;
-        let mut db_file = File::open(&db_path)?;
-        let mut buffer = Vec::new();
-        db_file.read_to_end(&mut buffer)?;
-        zip.write_all(&buffer)?;
-        zip.finish()
-            .map_err(|e| AppError::internal(e.to_string()))?;
-    }
+/// Uploads a backup archive to the root of the connected Drive.
+async fn upload_backup_archive(
+    app: &AppHandle,
+    zip_filename: &str,
+    zip_content: Vec<u8>,
+) -> AppResult<()> {
+    let mut tokens = read_connected_tokens(app)?;
+    refresh_token_if_needed(app, &mut tokens).await?;
 
     let file_metadata = serde_json::json!({
         "name": zip_filename,
         "parents": ["root"]
     });
 
-    let zip_content = fs::read(&zip_path)?;
     let metadata_part = reqwest::multipart::Part::text(file_metadata.to_string())
         .mime_str("application/json")
         .map_err(|e| AppError::internal(e.to_string()))?;
     let file_part = reqwest::multipart::Part::bytes(zip_content)
-        .mime_str("application/zip")
+        .mime_str(archive_content_type(zip_filename))
         .map_err(|e| AppError::internal(e.to_string()))?;
 
     let form = reqwest::multipart::Form::new()
@@ -318,7 +358,6 @@ pub async fn perform_drive_backup(app: &AppHandle) -> AppResult<String> {
 
     let status = res.status();
     let response_text = response_text_or_empty(res).await;
-    let _ = fs::remove_file(&zip_path);
 
     if !status.is_success() {
         return Err(AppError::internal(format!(
@@ -327,7 +366,106 @@ pub async fn perform_drive_backup(app: &AppHandle) -> AppResult<String> {
         )));
     }
 
-    Ok("Backup uploaded successfully".into())
+    Ok(())
+}
+
+/// Sends an authorized Drive API request and fails on an error status.
+async fn drive_request(
+    app: &AppHandle,
+    build: impl FnOnce(&reqwest::Client) -> reqwest::RequestBuilder,
+    action: &str,
+) -> AppResult<reqwest::Response> {
+    let mut tokens = read_connected_tokens(app)?;
+    refresh_token_if_needed(app, &mut tokens).await?;
+
+    let client = reqwest::Client::new();
+    let res = build(&client)
+        .bearer_auth(&tokens.access_token)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        let err_text = response_text_or_empty(res).await;
+        return Err(AppError::internal(format!("{action} failed: {err_text}")));
+    }
+    Ok(res)
+}
+
+/// The connected Google Drive as a backup target.
+pub(crate) struct DriveBackupDestination {
+    app: AppHandle,
+}
+
+impl DriveBackupDestination {
+    pub(crate) fn new(app: AppHandle) -> Self {
+        DriveBackupDestination { app }
+    }
+}
+
+#[async_trait]
+impl BackupDestination for DriveBackupDestination {
+    async fn upload(&self, file_name: &str, archive: Vec<u8>) -> AppResult<()> {
+        upload_backup_archive(&self.app, file_name, archive).await
+    }
+
+    async fn list_backups(&self) -> AppResult<Vec<RemoteBackup>> {
+        // The drive.file scope only shows files this app created.
+        let mut backups = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut query = vec![
+                (
+                    "q",
+                    "name contains 'backup_' and 'root' in parents and trashed = false".to_string(),
+                ),
+                (
+                    "fields",
+                    "nextPageToken, files(id, name, size, createdTime)".to_string(),
+                ),
+                ("pageSize", "1000".to_string()),
+            ];
+            if let Some(token) = page_token.take() {
+                query.push(("pageToken", token));
+            }
+            let url = url::Url::parse_with_params(DRIVE_FILES_URI, &query)
+                .map_err(|e| AppError::internal(e.to_string()))?;
+            let list: DriveFileList =
+                drive_request(&self.app, |client| client.get(url), "Drive listing")
+                    .await?
+                    .json()
+                    .await?;
+
+            backups.extend(list.files.into_iter().filter_map(|file| {
+                RemoteBackup::new(
+                    file.id,
+                    file.name,
+                    file.size.and_then(|size| size.parse().ok()),
+                    file.created_time,
+                )
+            }));
+            match list.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+        Ok(backups)
+    }
+
+    async fn download_backup(&self, id: &str) -> AppResult<Vec<u8>> {
+        let url = format!("{DRIVE_FILES_URI}/{id}?alt=media");
+        let res = drive_request(&self.app, |client| client.get(&url), "Drive download").await?;
+        Ok(res.bytes().await?.to_vec())
+    }
+
+    async fn delete_backup(&self, id: &str) -> AppResult<()> {
+        let url = format!("{DRIVE_FILES_URI}/{id}");
+        drive_request(&self.app, |client| client.delete(&url), "Drive delete").await?;
+        Ok(())
+    }
+
+    async fn check_connection(&self) -> AppResult<()> {
+        let mut tokens = read_connected_tokens(&self.app)?;
+        refresh_token_if_needed(&self.app, &mut tokens).await
+    }
 }
 
 async fn response_text_or_empty(response: reqwest::Response) -> String {
@@ -0,0 +1,258 @@
+const ESC: u8 = 0x1B;
+const GS: u8 = 0x1D;
+const LF: u8 = 0x0A;
+
+/// Printable paper width of the receipt roll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperWidth {
+    Mm58,
+    Mm80,
+}
+
+impl PaperWidth {
+    /// Parses the `receipt_paper_width` setting, defaulting to 80mm.
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "58" | "58mm" => Self::Mm58,
+            _ => Self::Mm80,
+        }
+    }
+
+    /// Characters per line using the printer's default Font A.
+    pub fn chars_per_line(self) -> usize {
+        match self {
+            Self::Mm58 => 32,
+            Self::Mm80 => 48,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+}
+
+/// Accumulates ESC/POS commands for a single print job.
+///
+/// The builder only produces bytes and never talks to a printer, so rendered
+/// receipts can be compared byte-for-byte against known-good output.
+#[derive(Debug, Clone)]
+pub struct EscPosBuilder {
+    buf: Vec<u8>,
+    line_width: usize,
+}
+
+impl EscPosBuilder {
+    /// Starts a new job and resets the printer to its power-on state.
+    pub fn new(paper: PaperWidth) -> Self {
+        let mut builder = Self {
+            buf: Vec::with_capacity(512),
+            line_width: paper.chars_per_line(),
+        };
+        builder.buf.extend_from_slice(&[ESC, b'@']);
+        builder
+    }
+
+    pub fn align(&mut self, align: Align) -> &mut Self {
+        let n = match align {
+            Align::Left => 0,
+            Align::Center => 1,
+        };
+        self.buf.extend_from_slice(&[ESC, b'a', n]);
+        self
+    }
+
+    pub fn bold(&mut self, enabled: bool) -> &mut Self {
+        self.buf.extend_from_slice(&[ESC, b'E', u8::from(enabled)]);
+        self
+    }
+
+    /// Toggles double width and double height characters.
+    pub fn double_size(&mut self, enabled: bool) -> &mut Self {
+        let n = if enabled { 0x11 } else { 0x00 };
+        self.buf.extend_from_slice(&[GS, b'!', n]);
+        self
+    }
+
+    /// Writes text without a trailing line feed.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.buf.extend(encode_text(text));
+        self
+    }
+
+    /// Writes text followed by a line feed.
+    pub fn line(&mut self, text: &str) -> &mut Self {
+        self.text(text);
+        self.buf.push(LF);
+        self
+    }
+
+    /// Writes `left` and `right` on one line, padding between them to fill the
+    /// paper width. `left` is truncated when both do not fit.
+    pub fn columns(&mut self, left: &str, right: &str) -> &mut Self {
+        let left = sanitize(left);
+        let right = sanitize(right);
+        let right_len = right.chars().count().min(self.line_width);
+        let left_room = self.line_width.saturating_sub(right_len + 1);
+        let left: String = left.chars().take(left_room).collect();
+        let padding = self
+            .line_width
+            .saturating_sub(left.chars().count() + right_len);
+
+        let mut row = left;
+        row.push_str(&" ".repeat(padding));
+        row.extend(right.chars().take(right_len));
+        self.line(&row)
+    }
+
+    /// Writes a full-width dashed separator.
+    pub fn separator(&mut self) -> &mut Self {
+        let dashes = "-".repeat(self.line_width);
+        self.line(&dashes)
+    }
+
+    /// Feeds `lines` blank lines.
+    pub fn feed(&mut self, lines: u8) -> &mut Self {
+        self.buf.extend_from_slice(&[ESC, b'd', lines]);
+        self
+    }
+
+    /// Prints `data` as a model 2 QR code with error correction level M.
+    /// `module_size` is clamped to the 1..=16 range supported by the command.
+    pub fn qr_code(&mut self, data: &str, module_size: u8) -> &mut Self {
+        let payload = data.as_bytes();
+        let store_len = payload.len() + 3;
+        let [p_l, p_h] = (store_len.min(u16::MAX as usize) as u16).to_le_bytes();
+
+        // Select model 2.
+        self.buf
+            .extend_from_slice(&[GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+        // Module size.
+        self.buf
+            .extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 67, module_size.clamp(1, 16)]);
+        // Error correction level M.
+        self.buf
+            .extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 69, 49]);
+        // Store the data in the symbol buffer.
+        self.buf
+            .extend_from_slice(&[GS, b'(', b'k', p_l, p_h, 49, 80, 48]);
+        self.buf.extend_from_slice(payload);
+        // Print the stored symbol.
+        self.buf
+            .extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 81, 48]);
+        self.buf.push(LF);
+        self
+    }
+
+    /// Feeds past the tear bar and performs a partial cut.
+    pub fn cut(&mut self) -> &mut Self {
+        self.buf.extend_from_slice(&[GS, b'V', 66, 3]);
+        self
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Replaces characters outside printable ASCII with `?`.
+///
+/// Thermal printers default to code page 437, which cannot render Myanmar
+/// script; substituting keeps column widths predictable.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c == ' ' || c.is_ascii_graphic() {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}
+
+fn encode_text(text: &str) -> Vec<u8> {
+    sanitize(text).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_job_starts_with_initialize() {
+        let bytes = EscPosBuilder::new(PaperWidth::Mm80).into_bytes();
+        assert_eq!(bytes, b"\x1b@");
+    }
+
+    #[test]
+    fn alignment_and_bold_commands() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm80);
+        builder
+            .align(Align::Center)
+            .bold(true)
+            .text("Hi")
+            .bold(false)
+            .align(Align::Left);
+        assert_eq!(
+            builder.into_bytes(),
+            b"\x1b@\x1ba\x01\x1bE\x01Hi\x1bE\x00\x1ba\x00"
+        );
+    }
+
+    #[test]
+    fn qr_code_for_order_id() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm58);
+        builder.qr_code("ORD-0001", 6);
+        let mut expected = b"\x1b@".to_vec();
+        expected.extend_from_slice(b"\x1d(k\x04\x001A2\x00");
+        expected.extend_from_slice(b"\x1d(k\x03\x001C\x06");
+        expected.extend_from_slice(b"\x1d(k\x03\x001E1");
+        expected.extend_from_slice(b"\x1d(k\x0b\x001P0ORD-0001");
+        expected.extend_from_slice(b"\x1d(k\x03\x001Q0\n");
+        assert_eq!(builder.into_bytes(), expected);
+    }
+
+    #[test]
+    fn qr_code_clamps_module_size() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm58);
+        builder.qr_code("A", 40);
+        let bytes = builder.into_bytes();
+        assert_eq!(&bytes[11..19], b"\x1d(k\x03\x001C\x10");
+    }
+
+    #[test]
+    fn feed_and_cut_commands() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm80);
+        builder.feed(3).cut();
+        assert_eq!(builder.into_bytes(), b"\x1b@\x1bd\x03\x1dVB\x03");
+    }
+
+    #[test]
+    fn columns_fill_the_paper_width() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm58);
+        builder.columns("Order", "ORD-0001");
+        assert_eq!(
+            builder.into_bytes(),
+            b"\x1b@Order                   ORD-0001\n"
+        );
+    }
+
+    #[test]
+    fn columns_truncate_the_left_text() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm58);
+        builder.columns("A very long product name that will not fit", "$10");
+        assert_eq!(
+            builder.into_bytes(),
+            b"\x1b@A very long product name tha $10\n"
+        );
+    }
+
+    #[test]
+    fn non_ascii_text_is_replaced() {
+        let mut builder = EscPosBuilder::new(PaperWidth::Mm80);
+        builder.line("Thar \u{1019}\u{103c}\u{1014}\u{103a}");
+        assert_eq!(builder.into_bytes(), b"\x1b@Thar ????\n");
+    }
+}
@@ -8,10 +8,15 @@ use tauri::AppHandle;
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::db::DEFAULT_EXPENSE_ID_PREFIX;
+use crate::db::{ListQuery, ListSort, DEFAULT_EXPENSE_ID_PREFIX};
 use crate::entities::expenses;
 use crate::error::{AppError, AppResult};
-use crate::models::{Expense, PaginatedExpenses};
+use crate::models::{Expense, ExpenseListFilter, PaginatedExpenses};
+use crate::services::attachment::{delete_entity_attachments, AttachmentEntityType};
+use crate::services::expense_category::{
+    canonical_category, canonical_payment_method, register_expense_names,
+};
+use crate::services::pagination::{fetch_cursor_page, CursorPage, ListSource};
 use crate::state::AppState;
 use crate::sync::enqueue_sync;
 
@@ -63,14 +68,16 @@ pub async fn create_expense(
     let db = state.db.lock().await.clone();
     let record_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
     let sanitized_expense_id = sanitize_optional(expense_id);
+    let category = canonical_category(&db, category).await?;
+    let payment_method = canonical_payment_method(&db, payment_method).await?;
 
     expenses::ActiveModel {
         id: Set(record_id.clone()),
         title: Set(trimmed_title),
         amount: Set(amount),
-        category: Set(sanitize_optional(category)),
+        category: Set(category),
         expense_date: Set(sanitize_optional(expense_date)),
-        payment_method: Set(sanitize_optional(payment_method)),
+        payment_method: Set(payment_method),
         notes: Set(sanitize_optional(notes)),
         synced: Set(Some(0)),
         ..Default::default()
@@ -101,6 +108,7 @@ pub async fn create_expense(
         [final_expense_id.into(), record_id.clone().into()],
     ))
     .await?;
+    register_expense_names(&db).await?;
 
     if let Ok(Some(record)) = expenses::Entity::find_by_id(record_id.clone())
         .into_model::<Expense>()
@@ -138,47 +146,27 @@ pub async fn get_expenses(state: Arc<AppState>) -> AppResult<Vec<Expense>> {
     Ok(expenses)
 }
 
-/// Loads paginated expense list with filters.
-#[instrument(skip(state))]
-#[allow(clippy::too_many_arguments)]
-pub async fn get_expenses_paginated(
-    state: Arc<AppState>,
-    page: Option<i64>,
-    page_size: Option<i64>,
-    search_key: Option<String>,
-    search_term: Option<String>,
-    category_filter: Option<String>,
-    date_from: Option<String>,
-    date_to: Option<String>,
-    sort_by: Option<String>,
-    sort_order: Option<String>,
-) -> AppResult<PaginatedExpenses> {
-    let db = state.db.lock().await.clone();
-
-    let requested_page_size = page_size.unwrap_or(DEFAULT_EXPENSES_PAGE_SIZE);
-    let no_limit = requested_page_size <= 0;
-    let page_size = if no_limit {
-        DEFAULT_EXPENSES_PAGE_SIZE
-    } else {
-        requested_page_size.clamp(MIN_EXPENSES_PAGE_SIZE, MAX_EXPENSES_PAGE_SIZE)
-    };
-    let page = if no_limit {
-        1
-    } else {
-        page.unwrap_or(1).max(1)
-    };
-    let offset = if no_limit { 0 } else { (page - 1) * page_size };
-
-    let raw_search = search_term.unwrap_or_default().trim().to_string();
+pub(crate) const EXPENSE_SELECT: &str =
+    "SELECT id, expense_id, title, amount, category, payment_method, notes, expense_date, \
+     created_at, updated_at, deleted_at FROM expenses";
+
+/// Builds the WHERE/ORDER BY clauses for the expense list from its filters.
+pub(crate) fn build_expense_list_query(filter: &ExpenseListFilter) -> AppResult<ListQuery> {
+    let raw_search = filter
+        .search_term
+        .clone()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
     let has_search = !raw_search.is_empty();
     let search_pattern = format!("%{}%", raw_search);
 
     let normalized_category =
-        sanitize_optional(category_filter).filter(|v| v.to_lowercase() != "all");
-    let normalized_date_from = sanitize_optional(date_from);
-    let normalized_date_to = sanitize_optional(date_to);
+        sanitize_optional(filter.category_filter.clone()).filter(|v| v.to_lowercase() != "all");
+    let normalized_date_from = sanitize_optional(filter.date_from.clone());
+    let normalized_date_to = sanitize_optional(filter.date_to.clone());
 
-    let search_column = match search_key.as_deref().unwrap_or("title") {
+    let search_column = match filter.search_key.as_deref().unwrap_or("title") {
         "title" => "title",
         "expenseId" => "expense_id",
         "category" => "category",
@@ -186,7 +174,7 @@ pub async fn get_expenses_paginated(
         _ => return Err(AppError::invalid_input("Invalid search key")),
     };
 
-    let sort_column = match sort_by.as_deref().unwrap_or("expense_date") {
+    let sort_column = match filter.sort_by.as_deref().unwrap_or("expense_date") {
         "title" => "title",
         "amount" => "amount",
         "expense_date" => "COALESCE(expense_date, created_at)",
@@ -194,13 +182,12 @@ pub async fn get_expenses_paginated(
         "expense_id" => "expense_id",
         _ => "COALESCE(expense_date, created_at)",
     };
-    let sort_dir = if sort_order.as_deref() == Some("asc") {
+    let sort_dir = if filter.sort_order.as_deref() == Some("asc") {
         "ASC"
     } else {
         "DESC"
     };
 
-    // Build WHERE conditions and parameter list
     let mut conditions: Vec<String> = vec!["deleted_at IS NULL".to_string()];
     let mut params: Vec<sea_orm::Value> = vec![];
 
@@ -221,11 +208,50 @@ pub async fn get_expenses_paginated(
         params.push(dt.clone().into());
     }
 
-    let where_clause = if conditions.is_empty() {
-        String::new()
+    let descending = sort_dir == "DESC";
+    let sort = if sort_column == "amount" {
+        ListSort::numeric(sort_column, descending, "id")
+    } else {
+        ListSort::text(sort_column, descending, "id")
+    };
+    Ok(ListQuery::new(
+        conditions,
+        params,
+        format!("ORDER BY {} {}", sort_column, sort_dir),
+    )
+    .with_sort(sort))
+}
+
+/// Loads paginated expense list with filters.
+#[instrument(skip(state))]
+pub async fn get_expenses_paginated(
+    state: Arc<AppState>,
+    page: Option<i64>,
+    page_size: Option<i64>,
+    filter: ExpenseListFilter,
+) -> AppResult<PaginatedExpenses> {
+    let db = state.db.lock().await.clone();
+
+    let requested_page_size = page_size.unwrap_or(DEFAULT_EXPENSES_PAGE_SIZE);
+    let no_limit = requested_page_size <= 0;
+    let page_size = if no_limit {
+        DEFAULT_EXPENSES_PAGE_SIZE
+    } else {
+        requested_page_size.clamp(MIN_EXPENSES_PAGE_SIZE, MAX_EXPENSES_PAGE_SIZE)
+    };
+    let page = if no_limit {
+        1
     } else {
-        format!("WHERE {}", conditions.join(" AND "))
+        page.unwrap_or(1).max(1)
     };
+    let offset = if no_limit { 0 } else { (page - 1) * page_size };
+
+    let ListQuery {
+        where_clause,
+        order_clause,
+        params,
+        ..
+    } = build_expense_list_query(&filter)?;
 
     let count_sql = format!("SELECT COUNT(*) as cnt FROM expenses {}", where_clause);
     let total = CountRow::find_by_statement(Statement::from_sql_and_values(
@@ -239,16 +265,11 @@ pub async fn get_expenses_paginated(
     .cnt;
 
     let data_sql = if no_limit {
-        format!(
-            "SELECT id, expense_id, title, amount, category, payment_method, notes, expense_date, \
-             created_at, updated_at, deleted_at FROM expenses {} ORDER BY {} {}",
-            where_clause, sort_column, sort_dir
-        )
+        format!("{} {} {}", EXPENSE_SELECT, where_clause, order_clause)
     } else {
         format!(
-            "SELECT id, expense_id, title, amount, category, payment_method, notes, expense_date, \
-             created_at, updated_at, deleted_at FROM expenses {} ORDER BY {} {} LIMIT ? OFFSET ?",
-            where_clause, sort_column, sort_dir
+            "{} {} {} LIMIT ? OFFSET ?",
+            EXPENSE_SELECT, where_clause, order_clause
         )
     };
 
@@ -287,6 +308,32 @@ pub async fn get_expenses_paginated(
     })
 }
 
+/// Loads one cursor page of the expense list.
+#[instrument(skip(state))]
+pub async fn get_expenses_page(
+    state: Arc<AppState>,
+    filter: ExpenseListFilter,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: bool,
+) -> AppResult<CursorPage<Expense>> {
+    let db = state.db.lock().await.clone();
+    let query = build_expense_list_query(&filter)?;
+    fetch_cursor_page(
+        &db,
+        ListSource {
+            select: EXPENSE_SELECT,
+            group_by: "",
+            count_from: "FROM expenses",
+        },
+        query,
+        cursor,
+        limit,
+        include_total,
+    )
+    .await
+}
+
 /// Loads one expense by id.
 #[instrument(skip(state))]
 pub async fn get_expense(state: Arc<AppState>, id: String) -> AppResult<Expense> {
@@ -327,6 +374,8 @@ pub async fn update_expense(
     }
 
     let db = state.db.lock().await.clone();
+    let category = canonical_category(&db, category).await?;
+    let payment_method = canonical_payment_method(&db, payment_method).await?;
 
     db.execute(Statement::from_sql_and_values(
         DatabaseBackend::Sqlite,
@@ -335,14 +384,15 @@ pub async fn update_expense(
         [
             trimmed_title.into(),
             amount.into(),
-            sanitize_optional(category).into(),
+            category.into(),
             sanitize_optional(expense_date).into(),
-            sanitize_optional(payment_method).into(),
+            payment_method.into(),
             sanitize_optional(notes).into(),
             id.clone().into(),
         ],
     ))
     .await?;
+    register_expense_names(&db).await?;
 
     if let Ok(Some(record)) = expenses::Entity::find_by_id(id.clone())
         .into_model::<Expense>()
@@ -376,6 +426,11 @@ pub async fn delete_expense(state: Arc<AppState>, app: &AppHandle, id: String) -
     ))
     .await?;
 
+    if let Err(err) = delete_entity_attachments(&db, app, AttachmentEntityType::Expense, &id).await
+    {
+        tracing::warn!("Failed to remove attachments of expense {id}: {err}");
+    }
+
     if let Ok(Some(record)) = expenses::Entity::find_by_id(id.clone())
         .into_model::<Expense>()
         .one(&db)
@@ -0,0 +1,883 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{Datelike, NaiveDate};
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait, FromQueryResult, Statement,
+    TransactionTrait,
+};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::entities::expenses;
+use crate::error::{AppError, AppResult};
+use crate::models::Expense;
+use crate::state::AppState;
+use crate::sync::enqueue_sync;
+
+const MAX_NAME_LEN: usize = 60;
+const MAX_REPORT_MONTHS: u32 = 36;
+
+/// A managed lookup table whose names are stored as text on `expenses.<column>`
+/// and `recurring_expenses.<column>`.
+struct NameTable {
+    table: &'static str,
+    column: &'static str,
+    label: &'static str,
+}
+
+const CATEGORIES: NameTable = NameTable {
+    table: "expense_categories",
+    column: "category",
+    label: "Category",
+};
+
+const PAYMENT_METHODS: NameTable = NameTable {
+    table: "payment_methods",
+    column: "payment_method",
+    label: "Payment method",
+};
+
+#[derive(Debug, Clone, Serialize, FromQueryResult)]
+pub struct ExpenseCategory {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub monthly_budget: Option<f64>,
+    /// Live expenses filed under this category, not counting subcategories.
+    pub expense_count: i64,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromQueryResult)]
+pub struct PaymentMethod {
+    pub id: String,
+    pub name: String,
+    pub expense_count: i64,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// Budget against actual spend of one category in one month. The budget of a
+/// parent category covers its subcategories, so it is compared to
+/// `spent_with_children`. Expenses without a category share one row with no id.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetReportRow {
+    /// `YYYY-MM`
+    pub month: String,
+    pub category_id: Option<String>,
+    pub category_name: Option<String>,
+    pub parent_id: Option<String>,
+    pub monthly_budget: Option<f64>,
+    pub spent: f64,
+    pub spent_with_children: f64,
+    pub remaining: Option<f64>,
+    pub over_budget: bool,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct NamedRow {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CategoryNodeRow {
+    id: String,
+    name: String,
+    parent_id: Option<String>,
+    monthly_budget: Option<f64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MonthlySpendRow {
+    month: String,
+    category_id: Option<String>,
+    spent: f64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct IdRow {
+    id: String,
+}
+
+const CATEGORY_SELECT: &str = "SELECT c.id, c.name, c.parent_id, c.monthly_budget, \
+     (SELECT COUNT(*) FROM expenses e WHERE e.deleted_at IS NULL \
+      AND TRIM(e.category) = c.name COLLATE NOCASE) AS expense_count, \
+     c.created_at, c.updated_at FROM expense_categories c";
+
+const PAYMENT_METHOD_SELECT: &str = "SELECT m.id, m.name, \
+     (SELECT COUNT(*) FROM expenses e WHERE e.deleted_at IS NULL \
+      AND TRIM(e.payment_method) = m.name COLLATE NOCASE) AS expense_count, \
+     m.created_at, m.updated_at FROM payment_methods m";
+
+fn normalize_name(table: &NameTable, name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_input(format!(
+            "{} name is required",
+            table.label
+        )));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::invalid_input(format!(
+            "{} name must be at most {MAX_NAME_LEN} characters",
+            table.label
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_budget(budget: Option<f64>) -> AppResult<Option<f64>> {
+    match budget {
+        Some(b) if !b.is_finite() || b < 0.0 => Err(AppError::invalid_input(
+            "Monthly budget must be a valid non-negative number",
+        )),
+        other => Ok(other),
+    }
+}
+
+async fn find_by_name(
+    db: &DatabaseConnection,
+    table: &NameTable,
+    name: &str,
+) -> AppResult<Option<NamedRow>> {
+    Ok(NamedRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "SELECT id, name FROM {} WHERE name = ? COLLATE NOCASE",
+            table.table
+        ),
+        [name.into()],
+    ))
+    .one(db)
+    .await?)
+}
+
+async fn find_by_id<C: ConnectionTrait>(
+    db: &C,
+    table: &NameTable,
+    id: &str,
+) -> AppResult<NamedRow> {
+    NamedRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("SELECT id, name FROM {} WHERE id = ?", table.table),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found(format!("{} not found", table.label)))
+}
+
+async fn ensure_name_available(
+    db: &DatabaseConnection,
+    table: &NameTable,
+    name: &str,
+    except_id: Option<&str>,
+) -> AppResult<()> {
+    match find_by_name(db, table, name).await? {
+        Some(row) if Some(row.id.as_str()) != except_id => Err(AppError::invalid_input(format!(
+            "{} \"{}\" already exists",
+            table.label, row.name
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Adds names that reached `expenses` without going through the forms, e.g.
+/// pulled by sync, so every value in use is listed and reported.
+async fn register_used_names<C: ConnectionTrait>(db: &C, table: &NameTable) -> AppResult<()> {
+    let names = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!(
+                "SELECT DISTINCT TRIM(e.{col}) AS name FROM expenses e \
+                 WHERE e.deleted_at IS NULL AND TRIM(COALESCE(e.{col}, '')) <> '' \
+                 AND NOT EXISTS (SELECT 1 FROM {tbl} t WHERE t.name = TRIM(e.{col}) COLLATE NOCASE)",
+                col = table.column,
+                tbl = table.table,
+            ),
+        ))
+        .await?;
+    for row in names {
+        let name: String = row.try_get("", "name")?;
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "INSERT OR IGNORE INTO {} (id, name, updated_at) VALUES (?, ?, datetime('now'))",
+                table.table
+            ),
+            [Uuid::new_v4().to_string().into(), name.into()],
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+/// Registers every category and payment method used by a live expense. Runs
+/// after expense writes, sync pulls and at startup, never on reads.
+pub(crate) async fn register_expense_names<C: ConnectionTrait>(db: &C) -> AppResult<()> {
+    register_used_names(db, &CATEGORIES).await?;
+    register_used_names(db, &PAYMENT_METHODS).await
+}
+
+/// Maps a typed name to its managed spelling, registering new names on first
+/// use, so "transport" and "Transport " are filed together.
+async fn canonical_name(
+    db: &DatabaseConnection,
+    table: &NameTable,
+    name: Option<String>,
+) -> AppResult<Option<String>> {
+    let Some(name) = name
+        .map(|raw| raw.trim().to_string())
+        .filter(|trimmed| !trimmed.is_empty())
+    else {
+        return Ok(None);
+    };
+    if let Some(existing) = find_by_name(db, table, &name).await? {
+        return Ok(Some(existing.name));
+    }
+    let name = normalize_name(table, &name)?;
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "INSERT INTO {} (id, name, updated_at) VALUES (?, ?, datetime('now'))",
+            table.table
+        ),
+        [Uuid::new_v4().to_string().into(), name.clone().into()],
+    ))
+    .await?;
+    Ok(Some(name))
+}
+
+/// Canonical category name for an expense being saved.
+pub(crate) async fn canonical_category(
+    db: &DatabaseConnection,
+    name: Option<String>,
+) -> AppResult<Option<String>> {
+    canonical_name(db, &CATEGORIES, name).await
+}
+
+/// Canonical payment method name for an expense being saved.
+pub(crate) async fn canonical_payment_method(
+    db: &DatabaseConnection,
+    name: Option<String>,
+) -> AppResult<Option<String>> {
+    canonical_name(db, &PAYMENT_METHODS, name).await
+}
+
+/// Rewrites the name on every live expense and recurring expense filed under
+/// `from`. Run it inside the transaction that renames or deletes the name so
+/// a failure leaves nothing half moved. Returns the ids of changed expenses.
+async fn reassign_expenses<C: ConnectionTrait>(
+    db: &C,
+    table: &NameTable,
+    from: &str,
+    to: &str,
+) -> AppResult<Vec<String>> {
+    let ids = IdRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "SELECT id FROM expenses WHERE deleted_at IS NULL AND TRIM({}) = ? COLLATE NOCASE",
+            table.column
+        ),
+        [from.into()],
+    ))
+    .all(db)
+    .await?;
+
+    for IdRow { id } in &ids {
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "UPDATE expenses SET {} = ?, updated_at = datetime('now') WHERE id = ?",
+                table.column
+            ),
+            [to.into(), id.clone().into()],
+        ))
+        .await?;
+    }
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "UPDATE recurring_expenses SET {col} = ?, updated_at = datetime('now') \
+             WHERE TRIM({col}) = ? COLLATE NOCASE",
+            col = table.column
+        ),
+        [to.into(), from.into()],
+    ))
+    .await?;
+    Ok(ids.into_iter().map(|IdRow { id }| id).collect())
+}
+
+/// Enqueues reassigned expenses for sync once their transaction committed.
+async fn enqueue_reassigned(
+    state: &AppState,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    ids: &[String],
+) {
+    for id in ids {
+        if let Ok(Some(record)) = expenses::Entity::find_by_id(id.clone())
+            .into_model::<Expense>()
+            .one(db)
+            .await
+        {
+            let pool = state.pool.lock().await;
+            enqueue_sync(
+                &pool,
+                app,
+                "expenses",
+                "UPDATE",
+                id,
+                serde_json::json!(record),
+            )
+            .await;
+        }
+    }
+}
+
+async fn load_category_nodes(db: &DatabaseConnection) -> AppResult<Vec<CategoryNodeRow>> {
+    Ok(CategoryNodeRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "SELECT id, name, parent_id, monthly_budget FROM expense_categories",
+    ))
+    .all(db)
+    .await?)
+}
+
+/// Checks that `parent_id` exists and is not `id` itself or one of its descendants.
+async fn validate_parent(
+    db: &DatabaseConnection,
+    id: Option<&str>,
+    parent_id: Option<&str>,
+) -> AppResult<()> {
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+    let parents: HashMap<String, Option<String>> = load_category_nodes(db)
+        .await?
+        .into_iter()
+        .map(|node| (node.id, node.parent_id))
+        .collect();
+    if !parents.contains_key(parent_id) {
+        return Err(AppError::invalid_input("Parent category not found"));
+    }
+    let mut current = Some(parent_id.to_string());
+    let mut steps = 0;
+    while let Some(node) = current {
+        if Some(node.as_str()) == id {
+            return Err(AppError::invalid_input(
+                "A category cannot be nested under itself or its subcategories",
+            ));
+        }
+        steps += 1;
+        if steps > parents.len() {
+            break;
+        }
+        current = parents.get(&node).cloned().flatten();
+    }
+    Ok(())
+}
+
+async fn get_category(db: &DatabaseConnection, id: &str) -> AppResult<ExpenseCategory> {
+    ExpenseCategory::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{CATEGORY_SELECT} WHERE c.id = ?"),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Category not found"))
+}
+
+async fn get_payment_method(db: &DatabaseConnection, id: &str) -> AppResult<PaymentMethod> {
+    PaymentMethod::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{PAYMENT_METHOD_SELECT} WHERE m.id = ?"),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Payment method not found"))
+}
+
+/// Lists all expense categories with their usage, sorted by name.
+#[instrument(skip(state))]
+pub async fn get_expense_categories(state: Arc<AppState>) -> AppResult<Vec<ExpenseCategory>> {
+    let db = state.db.lock().await.clone();
+    Ok(ExpenseCategory::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!("{CATEGORY_SELECT} ORDER BY c.name COLLATE NOCASE ASC"),
+    ))
+    .all(&db)
+    .await?)
+}
+
+/// Creates an expense category, optionally nested under another one.
+#[instrument(skip(state))]
+pub async fn create_expense_category(
+    state: Arc<AppState>,
+    name: String,
+    parent_id: Option<String>,
+    monthly_budget: Option<f64>,
+) -> AppResult<ExpenseCategory> {
+    let name = normalize_name(&CATEGORIES, &name)?;
+    let monthly_budget = validate_budget(monthly_budget)?;
+    let parent_id = parent_id.filter(|p| !p.trim().is_empty());
+
+    let db = state.db.lock().await.clone();
+    ensure_name_available(&db, &CATEGORIES, &name, None).await?;
+    validate_parent(&db, None, parent_id.as_deref()).await?;
+
+    let id = Uuid::new_v4().to_string();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO expense_categories (id, name, parent_id, monthly_budget, updated_at) \
+         VALUES (?, ?, ?, ?, datetime('now'))",
+        [
+            id.clone().into(),
+            name.into(),
+            parent_id.into(),
+            monthly_budget.into(),
+        ],
+    ))
+    .await?;
+    get_category(&db, &id).await
+}
+
+/// Updates a category. A rename is applied to every expense filed under it.
+#[instrument(skip(state, app))]
+pub async fn update_expense_category(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+    name: String,
+    parent_id: Option<String>,
+    monthly_budget: Option<f64>,
+) -> AppResult<ExpenseCategory> {
+    let name = normalize_name(&CATEGORIES, &name)?;
+    let monthly_budget = validate_budget(monthly_budget)?;
+    let parent_id = parent_id.filter(|p| !p.trim().is_empty());
+
+    let db = state.db.lock().await.clone();
+    let current = find_by_id(&db, &CATEGORIES, &id).await?;
+    ensure_name_available(&db, &CATEGORIES, &name, Some(&id)).await?;
+    validate_parent(&db, Some(&id), parent_id.as_deref()).await?;
+
+    let txn = db.begin().await?;
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE expense_categories SET name = ?, parent_id = ?, monthly_budget = ?, \
+         updated_at = datetime('now') WHERE id = ?",
+        [
+            name.clone().into(),
+            parent_id.into(),
+            monthly_budget.into(),
+            id.clone().into(),
+        ],
+    ))
+    .await?;
+    let moved = if current.name != name {
+        reassign_expenses(&txn, &CATEGORIES, &current.name, &name).await?
+    } else {
+        Vec::new()
+    };
+    txn.commit().await?;
+    enqueue_reassigned(&state, app, &db, &moved).await;
+    get_category(&db, &id).await
+}
+
+/// Deletes a category. Its expenses move to `replacement_id`, which also makes
+/// this the way to merge "Taxi" into "Transport"; without a replacement only an
+/// unused category can be deleted. Subcategories move up to its parent.
+#[instrument(skip(state, app))]
+pub async fn delete_expense_category(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+    replacement_id: Option<String>,
+) -> AppResult<u64> {
+    let db = state.db.lock().await.clone();
+    let category = get_category(&db, &id).await?;
+    let replacement_id = replacement_id.filter(|r| !r.trim().is_empty());
+
+    let txn = db.begin().await?;
+    let moved = match replacement_id {
+        Some(replacement_id) if replacement_id == id => {
+            return Err(AppError::invalid_input("A category cannot replace itself"));
+        }
+        Some(replacement_id) => {
+            let replacement = find_by_id(&txn, &CATEGORIES, &replacement_id).await?;
+            reassign_expenses(&txn, &CATEGORIES, &category.name, &replacement.name).await?
+        }
+        None if category.expense_count > 0 => {
+            return Err(AppError::invalid_input(format!(
+                "Category \"{}\" is used by {} expense(s); choose a replacement",
+                category.name, category.expense_count
+            )));
+        }
+        None => Vec::new(),
+    };
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE expense_categories SET parent_id = ?, updated_at = datetime('now') \
+         WHERE parent_id = ?",
+        [category.parent_id.into(), id.clone().into()],
+    ))
+    .await?;
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "DELETE FROM expense_categories WHERE id = ?",
+        [id.into()],
+    ))
+    .await?;
+    txn.commit().await?;
+    enqueue_reassigned(&state, app, &db, &moved).await;
+    Ok(moved.len() as u64)
+}
+
+/// Lists all payment methods with their usage, sorted by name.
+#[instrument(skip(state))]
+pub async fn get_payment_methods(state: Arc<AppState>) -> AppResult<Vec<PaymentMethod>> {
+    let db = state.db.lock().await.clone();
+    Ok(PaymentMethod::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!("{PAYMENT_METHOD_SELECT} ORDER BY m.name COLLATE NOCASE ASC"),
+    ))
+    .all(&db)
+    .await?)
+}
+
+/// Creates a payment method.
+#[instrument(skip(state))]
+pub async fn create_payment_method(state: Arc<AppState>, name: String) -> AppResult<PaymentMethod> {
+    let name = normalize_name(&PAYMENT_METHODS, &name)?;
+    let db = state.db.lock().await.clone();
+    ensure_name_available(&db, &PAYMENT_METHODS, &name, None).await?;
+
+    let id = Uuid::new_v4().to_string();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO payment_methods (id, name, updated_at) VALUES (?, ?, datetime('now'))",
+        [id.clone().into(), name.into()],
+    ))
+    .await?;
+    get_payment_method(&db, &id).await
+}
+
+/// Renames a payment method and every expense paid with it.
+#[instrument(skip(state, app))]
+pub async fn update_payment_method(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+    name: String,
+) -> AppResult<PaymentMethod> {
+    let name = normalize_name(&PAYMENT_METHODS, &name)?;
+    let db = state.db.lock().await.clone();
+    let current = find_by_id(&db, &PAYMENT_METHODS, &id).await?;
+    ensure_name_available(&db, &PAYMENT_METHODS, &name, Some(&id)).await?;
+
+    let txn = db.begin().await?;
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE payment_methods SET name = ?, updated_at = datetime('now') WHERE id = ?",
+        [name.clone().into(), id.clone().into()],
+    ))
+    .await?;
+    let moved = if current.name != name {
+        reassign_expenses(&txn, &PAYMENT_METHODS, &current.name, &name).await?
+    } else {
+        Vec::new()
+    };
+    txn.commit().await?;
+    enqueue_reassigned(&state, app, &db, &moved).await;
+    get_payment_method(&db, &id).await
+}
+
+/// Deletes a payment method, moving its expenses to `replacement_id` first.
+#[instrument(skip(state, app))]
+pub async fn delete_payment_method(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+    replacement_id: Option<String>,
+) -> AppResult<u64> {
+    let db = state.db.lock().await.clone();
+    let method = get_payment_method(&db, &id).await?;
+    let replacement_id = replacement_id.filter(|r| !r.trim().is_empty());
+
+    let txn = db.begin().await?;
+    let moved = match replacement_id {
+        Some(replacement_id) if replacement_id == id => {
+            return Err(AppError::invalid_input(
+                "A payment method cannot replace itself",
+            ));
+        }
+        Some(replacement_id) => {
+            let replacement = find_by_id(&txn, &PAYMENT_METHODS, &replacement_id).await?;
+            reassign_expenses(&txn, &PAYMENT_METHODS, &method.name, &replacement.name).await?
+        }
+        None if method.expense_count > 0 => {
+            return Err(AppError::invalid_input(format!(
+                "Payment method \"{}\" is used by {} expense(s); choose a replacement",
+                method.name, method.expense_count
+            )));
+        }
+        None => Vec::new(),
+    };
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "DELETE FROM payment_methods WHERE id = ?",
+        [id.into()],
+    ))
+    .await?;
+    txn.commit().await?;
+    enqueue_reassigned(&state, app, &db, &moved).await;
+    Ok(moved.len() as u64)
+}
+
+fn parse_month(value: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+        .map_err(|_| AppError::invalid_input(format!("Invalid month (expected YYYY-MM): {value}")))
+}
+
+fn months_between(from: NaiveDate, to: NaiveDate) -> AppResult<Vec<String>> {
+    if from > to {
+        return Err(AppError::invalid_input(
+            "Report start month must not be after its end month",
+        ));
+    }
+    let span = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32 + 1;
+    if span > MAX_REPORT_MONTHS as i32 {
+        return Err(AppError::invalid_input(format!(
+            "Budget report covers at most {MAX_REPORT_MONTHS} months"
+        )));
+    }
+    let mut months = Vec::with_capacity(span as usize);
+    let mut current = from;
+    while current <= to {
+        months.push(current.format("%Y-%m").to_string());
+        current = current
+            .checked_add_months(chrono::Months::new(1))
+            .ok_or_else(|| AppError::internal("Month out of range"))?;
+    }
+    Ok(months)
+}
+
+/// Budget against actual spend per category per month, flagging months where
+/// a category (including its subcategories) went over its budget.
+#[instrument(skip(state))]
+pub async fn get_budget_report(
+    state: Arc<AppState>,
+    from_month: String,
+    to_month: String,
+) -> AppResult<Vec<BudgetReportRow>> {
+    let months = months_between(parse_month(&from_month)?, parse_month(&to_month)?)?;
+    let db = state.db.lock().await.clone();
+
+    let mut nodes = load_category_nodes(&db).await?;
+    nodes.sort_by_key(|node| node.name.to_lowercase());
+    let parents: HashMap<&str, Option<&str>> = nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node.parent_id.as_deref()))
+        .collect();
+
+    let spend_rows = MonthlySpendRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT strftime('%Y-%m', COALESCE(e.expense_date, e.created_at)) AS month, \
+         c.id AS category_id, COALESCE(SUM(e.amount), 0.0) AS spent \
+         FROM expenses e \
+         LEFT JOIN expense_categories c ON c.name = TRIM(e.category) COLLATE NOCASE \
+         WHERE e.deleted_at IS NULL \
+         AND strftime('%Y-%m', COALESCE(e.expense_date, e.created_at)) BETWEEN ? AND ? \
+         GROUP BY month, c.id",
+        [
+            months[0].clone().into(),
+            months[months.len() - 1].clone().into(),
+        ],
+    ))
+    .all(&db)
+    .await?;
+
+    // Direct spend, and spend rolled up into every ancestor.
+    let mut spent: HashMap<(&str, Option<&str>), f64> = HashMap::new();
+    let mut rolled_up: HashMap<(&str, &str), f64> = HashMap::new();
+    for row in &spend_rows {
+        let Some(month) = months.iter().find(|m| **m == row.month) else {
+            continue;
+        };
+        let category = row
+            .category_id
+            .as_deref()
+            .and_then(|id| parents.get_key_value(id).map(|(k, _)| *k));
+        *spent.entry((month.as_str(), category)).or_default() += row.spent;
+
+        let mut current = category;
+        let mut steps = 0;
+        while let Some(id) = current {
+            *rolled_up.entry((month.as_str(), id)).or_default() += row.spent;
+            steps += 1;
+            if steps > parents.len() {
+                break;
+            }
+            current = parents.get(id).copied().flatten();
+        }
+    }
+
+    let mut report = Vec::new();
+    for month in &months {
+        for node in &nodes {
+            let direct = spent
+                .get(&(month.as_str(), Some(node.id.as_str())))
+                .copied()
+                .unwrap_or(0.0);
+            let total = rolled_up
+                .get(&(month.as_str(), node.id.as_str()))
+                .copied()
+                .unwrap_or(0.0);
+            if node.monthly_budget.is_none() && total == 0.0 {
+                continue;
+            }
+            report.push(BudgetReportRow {
+                month: month.clone(),
+                category_id: Some(node.id.clone()),
+                category_name: Some(node.name.clone()),
+                parent_id: node.parent_id.clone(),
+                monthly_budget: node.monthly_budget,
+                spent: direct,
+                spent_with_children: total,
+                remaining: node.monthly_budget.map(|budget| budget - total),
+                over_budget: node.monthly_budget.is_some_and(|budget| total > budget),
+            });
+        }
+        if let Some(uncategorized) = spent.get(&(month.as_str(), None)).copied() {
+            report.push(BudgetReportRow {
+                month: month.clone(),
+                category_id: None,
+                category_name: None,
+                parent_id: None,
+                monthly_budget: None,
+                spent: uncategorized,
+                spent_with_children: uncategorized,
+                remaining: None,
+                over_budget: false,
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, FromQueryResult)]
+    struct CategoryRow {
+        id: String,
+        category: Option<String>,
+    }
+
+    async fn seeded_db() -> DatabaseConnection {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        db.execute_unprepared(
+            "INSERT INTO expenses (id, title, amount, category, deleted_at) VALUES \
+               ('e1', 'Taxi home', 5, 'Taxi', NULL), \
+               ('e2', 'Bus', 1, ' taxi ', NULL), \
+               ('e3', 'Old taxi', 4, 'Taxi', '2026-01-01 00:00:00'), \
+               ('e4', 'Lunch', 3, 'Food', NULL); \
+             INSERT INTO recurring_expenses (id, title, amount, category, frequency, day_of_month, start_date) \
+               VALUES ('r1', 'Weekly taxi', 20, 'Taxi', 'monthly', 1, '2026-01-01');",
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn categories(db: &DatabaseConnection, table: &str) -> Vec<(String, Option<String>)> {
+        CategoryRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!("SELECT id, category FROM {table} ORDER BY id"),
+        ))
+        .all(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.id, row.category))
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn reassign_moves_live_expenses_and_recurring_templates() {
+        let db = seeded_db().await;
+        let txn = db.begin().await.unwrap();
+        let moved = reassign_expenses(&txn, &CATEGORIES, "Taxi", "Transport")
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        assert_eq!(moved, ["e1", "e2"]);
+        assert_eq!(
+            categories(&db, "expenses").await,
+            [
+                ("e1".into(), Some("Transport".into())),
+                ("e2".into(), Some("Transport".into())),
+                ("e3".into(), Some("Taxi".into())),
+                ("e4".into(), Some("Food".into())),
+            ]
+        );
+        assert_eq!(
+            categories(&db, "recurring_expenses").await,
+            [("r1".into(), Some("Transport".into()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn reassign_is_undone_with_its_transaction() {
+        let db = seeded_db().await;
+        let txn = db.begin().await.unwrap();
+        reassign_expenses(&txn, &CATEGORIES, "Taxi", "Transport")
+            .await
+            .unwrap();
+        txn.rollback().await.unwrap();
+
+        let expenses = categories(&db, "expenses").await;
+        assert_eq!(expenses[0], ("e1".into(), Some("Taxi".into())));
+        assert_eq!(
+            categories(&db, "recurring_expenses").await,
+            [("r1".into(), Some("Taxi".into()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn registers_names_used_by_live_expenses() {
+        let db = seeded_db().await;
+        // Start from an empty list instead of the default categories.
+        db.execute_unprepared(
+            "DELETE FROM expense_categories; \
+             INSERT INTO expense_categories (id, name) VALUES ('c1', 'taxi');",
+        )
+        .await
+        .unwrap();
+        register_expense_names(&db).await.unwrap();
+
+        let names: Vec<String> = NamedRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT id, name FROM expense_categories ORDER BY name COLLATE NOCASE",
+        ))
+        .all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.name)
+        .collect();
+        assert_eq!(names, ["Food", "taxi"]);
+    }
+}
@@ -0,0 +1,494 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rust_xlsxwriter::{Format, Workbook};
+use sea_orm::{DatabaseConnection, FromQueryResult};
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::db::{ListQuery, ORDER_WITH_CUSTOMER_GROUP_BY, ORDER_WITH_CUSTOMER_SELECT};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    Customer, CustomerListFilter, Expense, ExpenseListFilter, OrderListFilter, OrderWithCustomer,
+};
+use crate::services::customer::{build_customer_list_query, CUSTOMER_SELECT};
+use crate::services::expense::{build_expense_list_query, EXPENSE_SELECT};
+use crate::services::order::{build_order_list_query, order_grand_total, service_fee_amount};
+use crate::services::pagination::{KeysetBatches, ListSource};
+use crate::state::AppState;
+
+/// Rows fetched per query, so large tables never sit in memory at once.
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    /// Uses the explicit format, otherwise the destination file extension.
+    pub fn resolve(format: Option<&str>, dest_path: &Path) -> AppResult<Self> {
+        let key = match format.map(str::trim).filter(|f| !f.is_empty()) {
+            Some(format) => format.to_ascii_lowercase(),
+            None => dest_path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default(),
+        };
+        match key.as_str() {
+            "csv" => Ok(Self::Csv),
+            "xlsx" => Ok(Self::Xlsx),
+            other => Err(AppError::invalid_input(format!(
+                "Unsupported export format: {other}"
+            ))),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: String,
+    /// Data rows written, excluding the header and totals rows.
+    pub row_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl Cell {
+    fn text(value: &Option<String>) -> Self {
+        value.clone().map(Cell::Text).unwrap_or(Cell::Empty)
+    }
+
+    fn number(value: Option<f64>) -> Self {
+        value.map(Cell::Number).unwrap_or(Cell::Empty)
+    }
+
+    fn integer(value: Option<i64>) -> Self {
+        value.map(|v| Cell::Number(v as f64)).unwrap_or(Cell::Empty)
+    }
+}
+
+struct ExportColumn<R> {
+    key: &'static str,
+    header: &'static str,
+    /// Summed into the totals row.
+    total: bool,
+    value: fn(&R) -> Cell,
+}
+
+impl<R> ExportColumn<R> {
+    fn new(key: &'static str, header: &'static str, value: fn(&R) -> Cell) -> Self {
+        Self {
+            key,
+            header,
+            total: false,
+            value,
+        }
+    }
+
+    fn summed(mut self) -> Self {
+        self.total = true;
+        self
+    }
+}
+
+fn order_columns() -> Vec<ExportColumn<OrderWithCustomer>> {
+    type Column = ExportColumn<OrderWithCustomer>;
+
+    vec![
+        Column::new("order_id", "Order ID", |o| Cell::text(&o.order_id)),
+        Column::new("customer_name", "Customer", |o| {
+            Cell::text(&o.customer_name)
+        }),
+        Column::new("status", "Status", |o| Cell::text(&o.status)),
+        Column::new("order_from", "Order From", |o| Cell::text(&o.order_from)),
+        Column::new("order_date", "Order Date", |o| Cell::text(&o.order_date)),
+        Column::new("arrived_date", "Arrived Date", |o| {
+            Cell::text(&o.arrived_date)
+        }),
+        Column::new("shipment_date", "Shipment Date", |o| {
+            Cell::text(&o.shipment_date)
+        }),
+        Column::new("user_withdraw_date", "User Withdraw Date", |o| {
+            Cell::text(&o.user_withdraw_date)
+        }),
+        Column::new("total_qty", "Qty", |o| Cell::integer(o.total_qty)).summed(),
+        Column::new("total_weight", "Weight", |o| Cell::number(o.total_weight)).summed(),
+        Column::new("items_total", "Items Total", |o| {
+            Cell::number(o.total_price)
+        })
+        .summed(),
+        Column::new("service_fee", "Service Fee", |o| {
+            Cell::Number(service_fee_amount(o))
+        })
+        .summed(),
+        Column::new("product_discount", "Product Discount", |o| {
+            Cell::number(o.product_discount)
+        })
+        .summed(),
+        Column::new("shipping_fee", "Shipping Fee", |o| {
+            Cell::number(o.shipping_fee)
+        })
+        .summed(),
+        Column::new("delivery_fee", "Delivery Fee", |o| {
+            Cell::number(o.delivery_fee)
+        })
+        .summed(),
+        Column::new("cargo_fee", "Cargo Fee", |o| Cell::number(o.cargo_fee)).summed(),
+        Column::new("order_total", "Order Total", |o| {
+            Cell::Number(order_grand_total(o))
+        })
+        .summed(),
+        Column::new("exchange_rate", "Exchange Rate", |o| {
+            Cell::number(o.exchange_rate)
+        }),
+        Column::new("order_total_exchange", "Order Total (Exchange)", |o| {
+            Cell::Number(order_grand_total(o) * o.exchange_rate.unwrap_or(1.0))
+        })
+        .summed(),
+        Column::new("created_at", "Created At", |o| Cell::text(&o.created_at)),
+    ]
+}
+
+fn customer_columns() -> Vec<ExportColumn<Customer>> {
+    type Column = ExportColumn<Customer>;
+
+    vec![
+        Column::new("customer_id", "Customer ID", |c| Cell::text(&c.customer_id)),
+        Column::new("name", "Name", |c| Cell::Text(c.name.clone())),
+        Column::new("phone", "Phone", |c| Cell::text(&c.phone)),
+        Column::new("address", "Address", |c| Cell::text(&c.address)),
+        Column::new("city", "City", |c| Cell::text(&c.city)),
+        Column::new("platform", "Platform", |c| Cell::text(&c.platform)),
+        Column::new("social_media_url", "Social Media URL", |c| {
+            Cell::text(&c.social_media_url)
+        }),
+        Column::new("created_at", "Created At", |c| Cell::text(&c.created_at)),
+    ]
+}
+
+fn expense_columns() -> Vec<ExportColumn<Expense>> {
+    type Column = ExportColumn<Expense>;
+
+    vec![
+        Column::new("expense_id", "Expense ID", |e| Cell::text(&e.expense_id)),
+        Column::new("title", "Title", |e| Cell::Text(e.title.clone())),
+        Column::new("amount", "Amount", |e| Cell::Number(e.amount)).summed(),
+        Column::new("category", "Category", |e| Cell::text(&e.category)),
+        Column::new("payment_method", "Payment Method", |e| {
+            Cell::text(&e.payment_method)
+        }),
+        Column::new("notes", "Notes", |e| Cell::text(&e.notes)),
+        Column::new("expense_date", "Expense Date", |e| {
+            Cell::text(&e.expense_date)
+        }),
+        Column::new("created_at", "Created At", |e| Cell::text(&e.created_at)),
+    ]
+}
+
+fn column_keys<R>(columns: Vec<ExportColumn<R>>) -> Vec<&'static str> {
+    columns.into_iter().map(|column| column.key).collect()
+}
+
+pub(crate) fn order_column_keys() -> Vec<&'static str> {
+    column_keys(order_columns())
+}
+
+pub(crate) fn customer_column_keys() -> Vec<&'static str> {
+    column_keys(customer_columns())
+}
+
+pub(crate) fn expense_column_keys() -> Vec<&'static str> {
+    column_keys(expense_columns())
+}
+
+/// Keeps the requested columns in the requested order; `None` keeps all.
+fn select_columns<R>(
+    available: Vec<ExportColumn<R>>,
+    requested: Option<Vec<String>>,
+) -> AppResult<Vec<ExportColumn<R>>> {
+    let Some(requested) = requested.filter(|keys| !keys.is_empty()) else {
+        return Ok(available);
+    };
+
+    let mut available: Vec<Option<ExportColumn<R>>> = available.into_iter().map(Some).collect();
+    let mut selected = Vec::with_capacity(requested.len());
+    for key in requested {
+        let column = available
+            .iter_mut()
+            .find(|column| column.as_ref().is_some_and(|c| c.key == key.trim()))
+            .and_then(Option::take)
+            .ok_or_else(|| AppError::invalid_input(format!("Unknown export column: {key}")))?;
+        selected.push(column);
+    }
+    Ok(selected)
+}
+
+fn csv_error(error: csv::Error) -> AppError {
+    AppError::internal(format!("Failed to write CSV: {}", error))
+}
+
+fn xlsx_error(error: rust_xlsxwriter::XlsxError) -> AppError {
+    AppError::internal(format!("Failed to write XLSX: {}", error))
+}
+
+/// Destination file. XLSX sheets use constant-memory mode, so rows are
+/// flushed to a temp file as they are written instead of kept in memory.
+enum ExportSink {
+    Csv(csv::Writer<BufWriter<File>>),
+    Xlsx {
+        workbook: Box<Workbook>,
+        path: PathBuf,
+        row: u32,
+        bold: Format,
+    },
+}
+
+impl ExportSink {
+    fn create(format: ExportFormat, path: &Path) -> AppResult<Self> {
+        match format {
+            ExportFormat::Csv => {
+                let mut file = BufWriter::new(File::create(path)?);
+                // BOM so spreadsheet apps detect UTF-8 for Myanmar text.
+                file.write_all(b"\xEF\xBB\xBF")?;
+                Ok(Self::Csv(csv::Writer::from_writer(file)))
+            }
+            ExportFormat::Xlsx => {
+                let mut workbook = Box::new(Workbook::new());
+                workbook.add_worksheet_with_constant_memory();
+                Ok(Self::Xlsx {
+                    workbook,
+                    path: path.to_path_buf(),
+                    row: 0,
+                    bold: Format::new().set_bold(),
+                })
+            }
+        }
+    }
+
+    fn write_row(&mut self, cells: &[Cell], emphasize: bool) -> AppResult<()> {
+        match self {
+            Self::Csv(writer) => writer
+                .write_record(cells.iter().map(|cell| match cell {
+                    Cell::Text(text) => text.clone(),
+                    Cell::Number(number) => number.to_string(),
+                    Cell::Empty => String::new(),
+                }))
+                .map_err(csv_error),
+            Self::Xlsx {
+                workbook,
+                row,
+                bold,
+                ..
+            } => {
+                let sheet = workbook.worksheet_from_index(0).map_err(xlsx_error)?;
+                for (col, cell) in cells.iter().enumerate() {
+                    let col = col as u16;
+                    match (cell, emphasize) {
+                        (Cell::Text(text), false) => {
+                            sheet.write_string(*row, col, text).map_err(xlsx_error)?;
+                        }
+                        (Cell::Text(text), true) => {
+                            sheet
+                                .write_string_with_format(*row, col, text, bold)
+                                .map_err(xlsx_error)?;
+                        }
+                        (Cell::Number(number), false) => {
+                            sheet.write_number(*row, col, *number).map_err(xlsx_error)?;
+                        }
+                        (Cell::Number(number), true) => {
+                            sheet
+                                .write_number_with_format(*row, col, *number, bold)
+                                .map_err(xlsx_error)?;
+                        }
+                        (Cell::Empty, _) => {}
+                    }
+                }
+                *row += 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> AppResult<()> {
+        match self {
+            Self::Csv(mut writer) => writer.flush().map_err(AppError::from),
+            Self::Xlsx {
+                mut workbook, path, ..
+            } => workbook.save(&path).map_err(xlsx_error),
+        }
+    }
+}
+
+/// Streams every row matching `query` into `sink` in batches, then appends a
+/// totals row. Returns the number of data rows written.
+async fn stream_rows<R: FromQueryResult>(
+    db: &DatabaseConnection,
+    source: ListSource<'_>,
+    query: ListQuery,
+    columns: &[ExportColumn<R>],
+    sink: &mut ExportSink,
+) -> AppResult<usize> {
+    let header: Vec<Cell> = columns
+        .iter()
+        .map(|column| Cell::Text(column.header.to_string()))
+        .collect();
+    sink.write_row(&header, true)?;
+
+    let mut batches = KeysetBatches::new(db, source, query, EXPORT_BATCH_SIZE)?;
+    let mut totals = vec![0.0_f64; columns.len()];
+    let mut row_count = 0usize;
+
+    while let Some(rows) = batches.next_batch::<R>().await? {
+        for record in &rows {
+            let cells: Vec<Cell> = columns.iter().map(|c| (c.value)(record)).collect();
+            for ((total, column), cell) in totals.iter_mut().zip(columns).zip(&cells) {
+                if let (true, Cell::Number(number)) = (column.total, cell) {
+                    *total += number;
+                }
+            }
+            sink.write_row(&cells, false)?;
+        }
+
+        row_count += rows.len();
+    }
+
+    let mut totals_row: Vec<Cell> = columns
+        .iter()
+        .zip(totals)
+        .map(|(column, total)| {
+            if column.total {
+                Cell::Number(total)
+            } else {
+                Cell::Empty
+            }
+        })
+        .collect();
+    if let Some(first) = totals_row.first_mut().filter(|cell| **cell == Cell::Empty) {
+        *first = Cell::Text(format!("Total ({} rows)", row_count));
+    }
+    sink.write_row(&totals_row, true)?;
+
+    Ok(row_count)
+}
+
+async fn export_to<R: FromQueryResult>(
+    state: Arc<AppState>,
+    dest_path: String,
+    format: Option<String>,
+    source: ListSource<'_>,
+    query: ListQuery,
+    columns: Vec<ExportColumn<R>>,
+) -> AppResult<ExportSummary> {
+    let dest_path = dest_path.trim().to_string();
+    if dest_path.is_empty() {
+        return Err(AppError::invalid_input("Destination path is required"));
+    }
+    let path = PathBuf::from(&dest_path);
+    let format = ExportFormat::resolve(format.as_deref(), &path)?;
+
+    let db = state.db.lock().await.clone();
+    let mut sink = ExportSink::create(format, &path)?;
+    let row_count = stream_rows(&db, source, query, &columns, &mut sink).await?;
+    sink.finish()?;
+
+    Ok(ExportSummary {
+        path: dest_path,
+        format: format.as_str().to_string(),
+        row_count,
+    })
+}
+
+/// Exports orders matching the order list filters to CSV or XLSX.
+#[instrument(skip(state))]
+pub async fn export_orders(
+    state: Arc<AppState>,
+    dest_path: String,
+    format: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: OrderListFilter,
+) -> AppResult<ExportSummary> {
+    let query = build_order_list_query(&filter)?;
+    let columns = select_columns(order_columns(), columns)?;
+    export_to(
+        state,
+        dest_path,
+        format,
+        ListSource {
+            select: ORDER_WITH_CUSTOMER_SELECT,
+            group_by: ORDER_WITH_CUSTOMER_GROUP_BY,
+            count_from: "FROM orders o LEFT JOIN customers c ON o.customer_id = c.id",
+        },
+        query,
+        columns,
+    )
+    .await
+}
+
+/// Exports customers matching the customer list filters to CSV or XLSX.
+#[instrument(skip(state))]
+pub async fn export_customers(
+    state: Arc<AppState>,
+    dest_path: String,
+    format: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: CustomerListFilter,
+) -> AppResult<ExportSummary> {
+    let query = build_customer_list_query(&filter)?;
+    let columns = select_columns(customer_columns(), columns)?;
+    export_to(
+        state,
+        dest_path,
+        format,
+        ListSource {
+            select: CUSTOMER_SELECT,
+            group_by: "",
+            count_from: "FROM customers",
+        },
+        query,
+        columns,
+    )
+    .await
+}
+
+/// Exports expenses matching the expense list filters to CSV or XLSX.
+#[instrument(skip(state))]
+pub async fn export_expenses(
+    state: Arc<AppState>,
+    dest_path: String,
+    format: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: ExpenseListFilter,
+) -> AppResult<ExportSummary> {
+    let query = build_expense_list_query(&filter)?;
+    let columns = select_columns(expense_columns(), columns)?;
+    export_to(
+        state,
+        dest_path,
+        format,
+        ListSource {
+            select: EXPENSE_SELECT,
+            group_by: "",
+            count_from: "FROM expenses",
+        },
+        query,
+        columns,
+    )
+    .await
+}
@@ -0,0 +1,1350 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::NaiveTime;
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Set,
+    Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::entities::customers;
+use crate::error::{AppError, AppResult};
+use crate::models::{Customer, Order, OrderItem, OrderItemPayload};
+use crate::services::customer::{next_customer_code_seq, CUSTOMER_SELECT};
+use crate::services::order::{next_order_code_seq, normalize_order_status, parse_flexible_date};
+use crate::services::phone::{default_phone_country, to_e164};
+use crate::state::AppState;
+use crate::sync::enqueue_sync;
+
+/// Data rows returned with the preview so the mapping step can show examples.
+const PREVIEW_SAMPLE_ROWS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportEntity {
+    Customers,
+    Orders,
+    OrderItems,
+}
+
+impl ImportEntity {
+    fn fields(self) -> &'static [ImportField] {
+        match self {
+            Self::Customers => CUSTOMER_FIELDS,
+            Self::Orders => ORDER_FIELDS,
+            Self::OrderItems => ORDER_ITEM_FIELDS,
+        }
+    }
+}
+
+/// A target field that a source column can be mapped onto.
+struct ImportField {
+    key: &'static str,
+    label: &'static str,
+    required: bool,
+    /// Extra header spellings recognised when suggesting a mapping.
+    aliases: &'static [&'static str],
+}
+
+const fn field(
+    key: &'static str,
+    label: &'static str,
+    required: bool,
+    aliases: &'static [&'static str],
+) -> ImportField {
+    ImportField {
+        key,
+        label,
+        required,
+        aliases,
+    }
+}
+
+const CUSTOMER_FIELDS: &[ImportField] = &[
+    field("name", "Name", true, &["customer_name", "full_name"]),
+    field("phone", "Phone", false, &["phone_number", "mobile", "tel"]),
+    field("address", "Address", false, &[]),
+    field("city", "City", false, &["township"]),
+    field(
+        "social_media_url",
+        "Social Media URL",
+        false,
+        &["social_media", "facebook", "profile_url"],
+    ),
+    field("platform", "Platform", false, &[]),
+];
+
+const ORDER_FIELDS: &[ImportField] = &[
+    field(
+        "order_ref",
+        "Order Ref",
+        false,
+        &["order_id", "order_no", "order_number", "reference"],
+    ),
+    field(
+        "customer",
+        "Customer",
+        true,
+        &["customer_id", "customer_code", "customer_name"],
+    ),
+    field("status", "Status", false, &[]),
+    field("order_from", "Order From", false, &["source"]),
+    field("exchange_rate", "Exchange Rate", false, &["rate"]),
+    field("shipping_fee", "Shipping Fee", false, &[]),
+    field("delivery_fee", "Delivery Fee", false, &[]),
+    field("cargo_fee", "Cargo Fee", false, &[]),
+    field("service_fee", "Service Fee", false, &[]),
+    field("service_fee_type", "Service Fee Type", false, &[]),
+    field("product_discount", "Product Discount", false, &["discount"]),
+    field("order_date", "Order Date", false, &["date"]),
+    field("arrived_date", "Arrived Date", false, &[]),
+    field("shipment_date", "Shipment Date", false, &[]),
+    field("user_withdraw_date", "User Withdraw Date", false, &[]),
+    field(
+        "product_url",
+        "Product URL",
+        false,
+        &["product", "url", "link"],
+    ),
+    field("product_qty", "Qty", false, &["quantity", "item_qty"]),
+    field("price", "Price", false, &["item_price", "unit_price"]),
+    field("product_weight", "Weight", false, &["item_weight"]),
+];
+
+const ORDER_ITEM_FIELDS: &[ImportField] = &[
+    field(
+        "order",
+        "Order",
+        true,
+        &["order_id", "order_ref", "order_code"],
+    ),
+    field(
+        "product_url",
+        "Product URL",
+        false,
+        &["product", "url", "link"],
+    ),
+    field("product_qty", "Qty", false, &["quantity", "item_qty"]),
+    field("price", "Price", false, &["item_price", "unit_price"]),
+    field("product_weight", "Weight", false, &["item_weight"]),
+];
+
+const ORDER_DATE_FIELDS: [&str; 4] = [
+    "order_date",
+    "arrived_date",
+    "shipment_date",
+    "user_withdraw_date",
+];
+
+#[derive(Debug, Serialize)]
+pub struct ImportFieldInfo {
+    pub key: String,
+    pub label: String,
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub sample_rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+    pub fields: Vec<ImportFieldInfo>,
+    /// Field key -> source header, guessed from the header names.
+    pub suggested_mapping: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportIssue {
+    pub field: String,
+    /// One of `missing_name`, `missing_value`, `invalid_phone`, `unknown_customer`,
+    /// `ambiguous_customer`, `unknown_order`, `invalid_date`, `invalid_number`,
+    /// `invalid_status`, `invalid_value` or `conflicting_order`.
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowReport {
+    /// Source row number, counting the header as row 1.
+    pub row: usize,
+    /// Customer or order code assigned (or, on a dry run, that would be assigned).
+    pub record_code: Option<String>,
+    pub issues: Vec<ImportIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub entity: ImportEntity,
+    pub dry_run: bool,
+    /// False on dry runs and whenever any row failed validation.
+    pub committed: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub invalid_rows: usize,
+    /// Customers, orders or order items inserted.
+    pub created_count: usize,
+    pub rows: Vec<ImportRowReport>,
+}
+
+impl ImportReport {
+    fn new(entity: ImportEntity, dry_run: bool, rows: Vec<ImportRowReport>) -> Self {
+        let invalid_rows = rows.iter().filter(|r| !r.issues.is_empty()).count();
+        Self {
+            entity,
+            dry_run,
+            committed: false,
+            total_rows: rows.len(),
+            valid_rows: rows.len() - invalid_rows,
+            invalid_rows,
+            created_count: 0,
+            rows,
+        }
+    }
+
+    /// Commits only a real run where every row validated.
+    fn should_commit(&self) -> bool {
+        !self.dry_run && self.invalid_rows == 0 && self.valid_rows > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportFormat {
+    Csv,
+    Spreadsheet,
+}
+
+impl ImportFormat {
+    /// Uses the explicit format, otherwise the source file extension.
+    fn resolve(format: Option<&str>, path: &Path) -> AppResult<Self> {
+        let key = match format.map(str::trim).filter(|f| !f.is_empty()) {
+            Some(format) => format.to_ascii_lowercase(),
+            None => path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default(),
+        };
+        match key.as_str() {
+            "csv" => Ok(Self::Csv),
+            "xlsx" | "xlsm" | "xls" | "ods" => Ok(Self::Spreadsheet),
+            other => Err(AppError::invalid_input(format!(
+                "Unsupported import format: {other}"
+            ))),
+        }
+    }
+}
+
+struct SourceRow {
+    number: usize,
+    cells: Vec<String>,
+}
+
+struct ImportTable {
+    headers: Vec<String>,
+    rows: Vec<SourceRow>,
+}
+
+fn read_table(path: &Path, format: Option<&str>) -> AppResult<ImportTable> {
+    if !path.is_file() {
+        return Err(AppError::not_found(format!(
+            "Import file not found: {}",
+            path.display()
+        )));
+    }
+
+    let mut lines: Vec<SourceRow> = match ImportFormat::resolve(format, path)? {
+        ImportFormat::Csv => read_csv_rows(path)?,
+        ImportFormat::Spreadsheet => read_sheet_rows(path)?,
+    };
+    lines.retain(|row| row.cells.iter().any(|cell| !cell.is_empty()));
+    if lines.is_empty() {
+        return Err(AppError::invalid_input("Import file has no header row"));
+    }
+
+    let mut headers = lines.remove(0).cells;
+    if let Some(first) = headers.first_mut() {
+        *first = first.trim_start_matches('\u{feff}').to_string();
+    }
+    Ok(ImportTable {
+        headers,
+        rows: lines,
+    })
+}
+
+fn read_csv_rows(path: &Path) -> AppResult<Vec<SourceRow>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)
+        .map_err(csv_error)?;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let number = record
+            .position()
+            .map(|p| p.line() as usize)
+            .unwrap_or(rows.len() + 1);
+        rows.push(SourceRow {
+            number,
+            cells: record.iter().map(|cell| cell.trim().to_string()).collect(),
+        });
+    }
+    Ok(rows)
+}
+
+fn read_sheet_rows(path: &Path) -> AppResult<Vec<SourceRow>> {
+    let mut workbook = open_workbook_auto(path).map_err(sheet_error)?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| AppError::invalid_input("Spreadsheet has no worksheets"))?
+        .map_err(sheet_error)?;
+    let first_row = range.start().map(|(row, _)| row as usize).unwrap_or(0);
+
+    Ok(range
+        .rows()
+        .enumerate()
+        .map(|(index, cells)| SourceRow {
+            number: first_row + index + 1,
+            cells: cells.iter().map(cell_text).collect(),
+        })
+        .collect())
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(text) | Data::DateTimeIso(text) | Data::DurationIso(text) => {
+            text.trim().to_string()
+        }
+        // Whole numbers lose their ".0" so codes and phone numbers read naturally.
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+            (*value as i64).to_string()
+        }
+        Data::Float(value) => value.to_string(),
+        Data::Int(value) => value.to_string(),
+        Data::Bool(value) => value.to_string(),
+        Data::DateTime(value) => value
+            .as_datetime()
+            .map(|dt| {
+                if dt.time() == NaiveTime::MIN {
+                    dt.format("%Y-%m-%d").to_string()
+                } else {
+                    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+                }
+            })
+            .unwrap_or_default(),
+        Data::Error(error) => format!("#{error}"),
+    }
+}
+
+fn csv_error(error: csv::Error) -> AppError {
+    AppError::invalid_input(format!("Failed to read CSV: {}", error))
+}
+
+fn sheet_error(error: impl std::fmt::Display) -> AppError {
+    AppError::invalid_input(format!("Failed to read spreadsheet: {}", error))
+}
+
+fn normalize_header(value: &str) -> String {
+    value
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Matches each field to the first unused header named like its key, label or aliases.
+fn suggest_mapping(entity: ImportEntity, headers: &[String]) -> HashMap<String, String> {
+    let normalized: Vec<String> = headers.iter().map(|h| normalize_header(h)).collect();
+    let mut used = HashSet::new();
+    let mut mapping = HashMap::new();
+
+    for field in entity.fields() {
+        let candidates = [field.key, field.label]
+            .into_iter()
+            .chain(field.aliases.iter().copied())
+            .map(normalize_header);
+        for candidate in candidates {
+            let found = normalized
+                .iter()
+                .enumerate()
+                .find(|(index, header)| **header == candidate && !used.contains(index));
+            if let Some((index, _)) = found {
+                used.insert(index);
+                mapping.insert(field.key.to_string(), headers[index].clone());
+                break;
+            }
+        }
+    }
+    mapping
+}
+
+/// Field key -> column index, validated against the entity's fields.
+struct ColumnMap(HashMap<&'static str, usize>);
+
+impl ColumnMap {
+    fn resolve(
+        entity: ImportEntity,
+        headers: &[String],
+        mapping: Option<HashMap<String, String>>,
+    ) -> AppResult<Self> {
+        let mapping = mapping
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| suggest_mapping(entity, headers));
+
+        let mut columns = HashMap::new();
+        for (key, header) in mapping {
+            let field = entity
+                .fields()
+                .iter()
+                .find(|f| f.key == key.trim())
+                .ok_or_else(|| AppError::invalid_input(format!("Unknown import field: {key}")))?;
+            let index = headers
+                .iter()
+                .position(|h| h.trim() == header.trim())
+                .ok_or_else(|| {
+                    AppError::invalid_input(format!("Column not found in file: {header}"))
+                })?;
+            columns.insert(field.key, index);
+        }
+
+        if let Some(missing) = entity
+            .fields()
+            .iter()
+            .find(|f| f.required && !columns.contains_key(f.key))
+        {
+            return Err(AppError::invalid_input(format!(
+                "Required field is not mapped: {}",
+                missing.label
+            )));
+        }
+        Ok(Self(columns))
+    }
+}
+
+/// Typed access to one source row; parse failures are collected as issues.
+struct RowValues<'a> {
+    columns: &'a ColumnMap,
+    cells: &'a [String],
+    issues: Vec<ImportIssue>,
+}
+
+impl<'a> RowValues<'a> {
+    fn new(columns: &'a ColumnMap, row: &'a SourceRow) -> Self {
+        Self {
+            columns,
+            cells: &row.cells,
+            issues: Vec::new(),
+        }
+    }
+
+    fn issue(&mut self, field: &str, code: &'static str, message: impl Into<String>) {
+        self.issues.push(ImportIssue {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    /// Trimmed cell value; blanks and "-" count as empty.
+    fn text(&self, key: &str) -> Option<String> {
+        let index = *self.columns.0.get(key)?;
+        self.cells
+            .get(index)
+            .map(|cell| cell.trim())
+            .filter(|cell| !cell.is_empty() && *cell != "-")
+            .map(str::to_string)
+    }
+
+    fn number(&mut self, key: &str) -> Option<f64> {
+        let raw = self.text(key)?;
+        match raw.replace(',', "").parse::<f64>() {
+            Ok(value) if value.is_finite() => Some(value),
+            _ => {
+                self.issue(key, "invalid_number", format!("Not a number: {raw}"));
+                None
+            }
+        }
+    }
+
+    fn integer(&mut self, key: &str) -> Option<i64> {
+        let value = self.number(key)?;
+        if value.fract() != 0.0 {
+            self.issue(
+                key,
+                "invalid_number",
+                format!("Not a whole number: {value}"),
+            );
+            return None;
+        }
+        Some(value as i64)
+    }
+
+    /// Parses any supported date layout and stores it as `YYYY-MM-DD`.
+    fn date(&mut self, key: &str) -> Option<String> {
+        let raw = self.text(key)?;
+        match parse_flexible_date(Some(&raw)) {
+            Some(date) => Some(date.format("%Y-%m-%d").to_string()),
+            None => {
+                self.issue(key, "invalid_date", format!("Unrecognised date: {raw}"));
+                None
+            }
+        }
+    }
+
+    fn item(&mut self) -> Option<OrderItemPayload> {
+        let item = OrderItemPayload {
+            id: None,
+            product_url: self.text("product_url"),
+            product_qty: self.integer("product_qty"),
+            price: self.number("price"),
+            product_weight: self.number("product_weight"),
+        };
+        let has_values = item.product_url.is_some()
+            || item.product_qty.is_some()
+            || item.price.is_some()
+            || item.product_weight.is_some();
+        has_values.then_some(item)
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CustomerRefRow {
+    id: String,
+    customer_id: Option<String>,
+    name: String,
+}
+
+/// Resolves the customer column by customer code, record id or unique name.
+struct CustomerLookup {
+    by_code: HashMap<String, String>,
+    ids: HashSet<String>,
+    by_name: HashMap<String, Vec<String>>,
+}
+
+impl CustomerLookup {
+    async fn load<C: ConnectionTrait>(conn: &C) -> AppResult<Self> {
+        let rows = CustomerRefRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT id, customer_id, name FROM customers WHERE deleted_at IS NULL",
+        ))
+        .all(conn)
+        .await?;
+
+        let mut lookup = Self {
+            by_code: HashMap::new(),
+            ids: HashSet::new(),
+            by_name: HashMap::new(),
+        };
+        for row in rows {
+            if let Some(code) = row.customer_id.filter(|c| !c.is_empty()) {
+                lookup.by_code.insert(code.to_uppercase(), row.id.clone());
+            }
+            lookup
+                .by_name
+                .entry(row.name.trim().to_lowercase())
+                .or_default()
+                .push(row.id.clone());
+            lookup.ids.insert(row.id);
+        }
+        Ok(lookup)
+    }
+
+    fn resolve(&self, value: &str) -> Result<String, (&'static str, String)> {
+        if let Some(id) = self.by_code.get(&value.to_uppercase()) {
+            return Ok(id.clone());
+        }
+        if self.ids.contains(value) {
+            return Ok(value.to_string());
+        }
+        match self.by_name.get(&value.to_lowercase()).map(Vec::as_slice) {
+            Some([id]) => Ok(id.clone()),
+            Some(_) => Err((
+                "ambiguous_customer",
+                format!("Several customers are named {value}; use the customer ID"),
+            )),
+            None => Err(("unknown_customer", format!("Unknown customer: {value}"))),
+        }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct OrderRefRow {
+    id: String,
+    order_id: Option<String>,
+}
+
+struct CustomerDraft {
+    row_index: usize,
+    name: String,
+    phone: Option<String>,
+    phone_e164: Option<String>,
+    address: Option<String>,
+    city: Option<String>,
+    social_media_url: Option<String>,
+    platform: Option<String>,
+}
+
+/// One order; `row_indexes` lists every source row grouped into it.
+struct OrderDraft {
+    row_indexes: Vec<usize>,
+    customer_id: Option<String>,
+    status: String,
+    order_from: Option<String>,
+    exchange_rate: Option<f64>,
+    shipping_fee: Option<f64>,
+    delivery_fee: Option<f64>,
+    cargo_fee: Option<f64>,
+    service_fee: Option<f64>,
+    service_fee_type: Option<String>,
+    product_discount: Option<f64>,
+    /// Indexed like `ORDER_DATE_FIELDS`.
+    dates: [Option<String>; 4],
+    items: Vec<OrderItemPayload>,
+}
+
+struct ItemDraft {
+    order_id: String,
+    item: OrderItemPayload,
+}
+
+/// Reads the file header and a few rows and suggests a column mapping.
+#[instrument]
+pub async fn preview_import(
+    path: String,
+    entity: ImportEntity,
+    format: Option<String>,
+) -> AppResult<ImportPreview> {
+    let table = read_table(Path::new(&path), format.as_deref())?;
+    let suggested_mapping = suggest_mapping(entity, &table.headers);
+
+    Ok(ImportPreview {
+        sample_rows: table
+            .rows
+            .iter()
+            .take(PREVIEW_SAMPLE_ROWS)
+            .map(|row| row.cells.clone())
+            .collect(),
+        total_rows: table.rows.len(),
+        headers: table.headers,
+        fields: entity
+            .fields()
+            .iter()
+            .map(|f| ImportFieldInfo {
+                key: f.key.to_string(),
+                label: f.label.to_string(),
+                required: f.required,
+            })
+            .collect(),
+        suggested_mapping,
+    })
+}
+
+/// Validates and, unless a dry run, inserts customers from a CSV/XLSX file.
+#[instrument(skip(state, app))]
+pub async fn import_customers(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    path: String,
+    format: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    dry_run: bool,
+) -> AppResult<ImportReport> {
+    let table = read_table(Path::new(&path), format.as_deref())?;
+    let columns = ColumnMap::resolve(ImportEntity::Customers, &table.headers, mapping)?;
+    let db = state.db.lock().await.clone();
+    let (report, record_ids) =
+        write_customers(&db, &table, &columns, &default_phone_country(app), dry_run).await?;
+    enqueue_customer_inserts(&state, app, &db, &record_ids).await;
+    Ok(report)
+}
+
+/// Validates customer rows and, unless a dry run or any row failed, inserts
+/// them all in one transaction. Returns the ids of the inserted customers.
+async fn write_customers(
+    db: &DatabaseConnection,
+    table: &ImportTable,
+    columns: &ColumnMap,
+    default_country: &str,
+    dry_run: bool,
+) -> AppResult<(ImportReport, Vec<String>)> {
+    let mut reports = Vec::with_capacity(table.rows.len());
+    let mut drafts = Vec::new();
+    for row in &table.rows {
+        let mut values = RowValues::new(columns, row);
+        let name = values.text("name");
+        if name.is_none() {
+            values.issue("name", "missing_name", "Customer name is required");
+        }
+        let phone = values.text("phone");
+        let phone_e164 = match phone.as_deref().map(|p| to_e164(p, default_country)) {
+            Some(Ok(e164)) => Some(e164),
+            Some(Err(err)) => {
+                values.issue("phone", "invalid_phone", err.to_string());
+                None
+            }
+            None => None,
+        };
+
+        if let (Some(name), true) = (name, values.issues.is_empty()) {
+            drafts.push(CustomerDraft {
+                row_index: reports.len(),
+                name,
+                phone,
+                phone_e164,
+                address: values.text("address"),
+                city: values.text("city"),
+                social_media_url: values.text("social_media_url"),
+                platform: values.text("platform"),
+            });
+        }
+        reports.push(ImportRowReport {
+            row: row.number,
+            record_code: None,
+            issues: values.issues,
+        });
+    }
+
+    let txn = db.begin().await?;
+    let (prefix, first_seq) = next_customer_code_seq(&txn).await;
+    let mut report = ImportReport::new(ImportEntity::Customers, dry_run, reports);
+    for (offset, draft) in drafts.iter().enumerate() {
+        report.rows[draft.row_index].record_code =
+            Some(format!("{}{:05}", prefix, first_seq + offset as i64));
+    }
+    if !report.should_commit() {
+        txn.rollback().await?;
+        return Ok((report, Vec::new()));
+    }
+
+    let mut record_ids = Vec::with_capacity(drafts.len());
+    for draft in drafts {
+        let record_id = Uuid::new_v4().to_string();
+        customers::ActiveModel {
+            id: Set(record_id.clone()),
+            customer_id: Set(report.rows[draft.row_index].record_code.clone()),
+            name: Set(draft.name),
+            phone: Set(draft.phone),
+            phone_e164: Set(draft.phone_e164),
+            address: Set(draft.address),
+            city: Set(draft.city),
+            social_media_url: Set(draft.social_media_url),
+            platform: Set(draft.platform),
+            created_at: sea_orm::ActiveValue::NotSet,
+            updated_at: Set(None),
+            deleted_at: Set(None),
+            synced: Set(Some(0)),
+        }
+        .insert(&txn)
+        .await?;
+        record_ids.push(record_id);
+    }
+    txn.commit().await?;
+
+    report.committed = true;
+    report.created_count = record_ids.len();
+    Ok((report, record_ids))
+}
+
+/// Validates and, unless a dry run, inserts orders with their items.
+///
+/// Rows sharing an order ref become one order with several items; rows
+/// without one each become their own order.
+#[instrument(skip(state, app))]
+pub async fn import_orders(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    path: String,
+    format: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    dry_run: bool,
+) -> AppResult<ImportReport> {
+    let table = read_table(Path::new(&path), format.as_deref())?;
+    let columns = ColumnMap::resolve(ImportEntity::Orders, &table.headers, mapping)?;
+    let db = state.db.lock().await.clone();
+    let (report, order_ids, item_ids) = write_orders(&db, &table, &columns, dry_run).await?;
+    enqueue_order_inserts(&state, app, &db, &order_ids).await;
+    enqueue_item_inserts(&state, app, &db, &item_ids).await;
+    Ok(report)
+}
+
+/// Validates order rows and, unless a dry run or any row failed, inserts the
+/// orders and their items in one transaction. Returns the ids of the
+/// inserted orders and items.
+async fn write_orders(
+    db: &DatabaseConnection,
+    table: &ImportTable,
+    columns: &ColumnMap,
+    dry_run: bool,
+) -> AppResult<(ImportReport, Vec<String>, Vec<String>)> {
+    let customers = CustomerLookup::load(db).await?;
+
+    let mut reports = Vec::with_capacity(table.rows.len());
+    let mut drafts: Vec<OrderDraft> = Vec::new();
+    let mut draft_by_ref: HashMap<String, usize> = HashMap::new();
+    for row in &table.rows {
+        let mut values = RowValues::new(columns, row);
+        let row_index = reports.len();
+
+        let customer_id = match values.text("customer") {
+            Some(customer) => match customers.resolve(&customer) {
+                Ok(id) => Some(id),
+                Err((code, message)) => {
+                    values.issue("customer", code, message);
+                    None
+                }
+            },
+            None => {
+                values.issue("customer", "missing_value", "Customer is required");
+                None
+            }
+        };
+        let item = values.item();
+        let order_ref = values.text("order_ref").map(|r| r.to_uppercase());
+
+        match order_ref
+            .as_ref()
+            .and_then(|r| draft_by_ref.get(r).copied())
+        {
+            Some(draft_index) => {
+                let draft = &mut drafts[draft_index];
+                if let (Some(id), Some(order_customer)) = (&customer_id, &draft.customer_id) {
+                    if id != order_customer {
+                        values.issue(
+                            "customer",
+                            "conflicting_order",
+                            "Rows of the same order name different customers",
+                        );
+                    }
+                }
+                draft.row_indexes.push(row_index);
+                draft.items.extend(item);
+            }
+            None => {
+                let raw_status = values.text("status");
+                let status = match normalize_order_status(raw_status.clone()) {
+                    Ok(status) => status.unwrap_or_else(|| "pending".to_string()),
+                    Err(_) => {
+                        values.issue(
+                            "status",
+                            "invalid_status",
+                            format!("Unknown order status: {}", raw_status.unwrap_or_default()),
+                        );
+                        "pending".to_string()
+                    }
+                };
+                let service_fee_type = values.text("service_fee_type").map(|t| t.to_lowercase());
+                if let Some(fee_type) = service_fee_type
+                    .as_deref()
+                    .filter(|t| !matches!(*t, "fixed" | "percent"))
+                {
+                    values.issue(
+                        "service_fee_type",
+                        "invalid_value",
+                        format!("Service fee type must be fixed or percent, got {fee_type}"),
+                    );
+                }
+
+                if let Some(order_ref) = order_ref {
+                    draft_by_ref.insert(order_ref, drafts.len());
+                }
+                drafts.push(OrderDraft {
+                    row_indexes: vec![row_index],
+                    customer_id,
+                    status,
+                    order_from: values.text("order_from"),
+                    exchange_rate: values.number("exchange_rate"),
+                    shipping_fee: values.number("shipping_fee"),
+                    delivery_fee: values.number("delivery_fee"),
+                    cargo_fee: values.number("cargo_fee"),
+                    service_fee: values.number("service_fee"),
+                    service_fee_type,
+                    product_discount: values.number("product_discount"),
+                    dates: ORDER_DATE_FIELDS.map(|key| values.date(key)),
+                    items: item.into_iter().collect(),
+                });
+            }
+        }
+
+        reports.push(ImportRowReport {
+            row: row.number,
+            record_code: None,
+            issues: values.issues,
+        });
+    }
+
+    let txn = db.begin().await?;
+    let (prefix, mut next_seq) = next_order_code_seq(&txn).await;
+    let mut codes = Vec::with_capacity(drafts.len());
+    for draft in &drafts {
+        let valid = draft.customer_id.is_some()
+            && draft
+                .row_indexes
+                .iter()
+                .all(|&index| reports[index].issues.is_empty());
+        let code = valid.then(|| {
+            next_seq += 1;
+            format!("{}{:05}", prefix, next_seq - 1)
+        });
+        for &index in &draft.row_indexes {
+            reports[index].record_code = code.clone();
+        }
+        codes.push(code);
+    }
+
+    let mut report = ImportReport::new(ImportEntity::Orders, dry_run, reports);
+    if !report.should_commit() {
+        txn.rollback().await?;
+        return Ok((report, Vec::new(), Vec::new()));
+    }
+
+    let mut order_ids = Vec::with_capacity(drafts.len());
+    let mut item_ids = Vec::new();
+    for (draft, code) in drafts.into_iter().zip(codes) {
+        let (Some(customer_id), Some(code)) = (draft.customer_id, code) else {
+            continue;
+        };
+        let record_id = Uuid::new_v4().to_string();
+        let [order_date, arrived_date, shipment_date, user_withdraw_date] = draft.dates;
+
+        txn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO orders (id, order_id, customer_id, status, order_from, exchange_rate, \
+                 shipping_fee, delivery_fee, cargo_fee, order_date, arrived_date, shipment_date, \
+                 user_withdraw_date, service_fee, product_discount, service_fee_type, \
+                 shipping_fee_paid, delivery_fee_paid, cargo_fee_paid, service_fee_paid, \
+                 shipping_fee_by_shop, delivery_fee_by_shop, cargo_fee_by_shop, exclude_cargo_fee) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, 0, 0, 0, 0, 0, 0, 0)",
+            [
+                record_id.clone().into(),
+                code.into(),
+                customer_id.into(),
+                draft.status.into(),
+                draft.order_from.into(),
+                draft.exchange_rate.into(),
+                draft.shipping_fee.into(),
+                draft.delivery_fee.into(),
+                draft.cargo_fee.into(),
+                order_date.into(),
+                arrived_date.into(),
+                shipment_date.into(),
+                user_withdraw_date.into(),
+                draft.service_fee.into(),
+                draft.product_discount.into(),
+                draft.service_fee_type.into(),
+            ],
+        ))
+        .await?;
+
+        for item in draft.items {
+            item_ids.push(insert_order_item(&txn, &record_id, item).await?);
+        }
+        order_ids.push(record_id);
+    }
+    txn.commit().await?;
+
+    report.committed = true;
+    report.created_count = order_ids.len();
+    Ok((report, order_ids, item_ids))
+}
+
+/// Validates and, unless a dry run, adds items to existing orders.
+#[instrument(skip(state, app))]
+pub async fn import_order_items(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    path: String,
+    format: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    dry_run: bool,
+) -> AppResult<ImportReport> {
+    let table = read_table(Path::new(&path), format.as_deref())?;
+    let columns = ColumnMap::resolve(ImportEntity::OrderItems, &table.headers, mapping)?;
+
+    let db = state.db.lock().await.clone();
+    let orders = OrderRefRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "SELECT id, order_id FROM orders WHERE deleted_at IS NULL",
+    ))
+    .all(&db)
+    .await?;
+    let mut order_lookup: HashMap<String, (String, Option<String>)> = HashMap::new();
+    for order in orders {
+        if let Some(code) = order.order_id.as_ref().filter(|c| !c.is_empty()) {
+            order_lookup.insert(
+                code.to_uppercase(),
+                (order.id.clone(), order.order_id.clone()),
+            );
+        }
+        order_lookup.insert(order.id.clone(), (order.id, order.order_id));
+    }
+
+    let mut reports = Vec::with_capacity(table.rows.len());
+    let mut drafts = Vec::new();
+    for row in &table.rows {
+        let mut values = RowValues::new(&columns, row);
+        let order = match values.text("order") {
+            Some(order) => {
+                let found = order_lookup
+                    .get(&order.to_uppercase())
+                    .or_else(|| order_lookup.get(&order))
+                    .cloned();
+                if found.is_none() {
+                    values.issue("order", "unknown_order", format!("Unknown order: {order}"));
+                }
+                found
+            }
+            None => {
+                values.issue("order", "missing_value", "Order is required");
+                None
+            }
+        };
+        let item = values.item();
+        if item.is_none() {
+            values.issue("product_url", "missing_value", "Row has no item details");
+        }
+
+        let record_code = order.as_ref().and_then(|(_, code)| code.clone());
+        if let (Some((order_id, _)), Some(item), true) = (order, item, values.issues.is_empty()) {
+            drafts.push(ItemDraft { order_id, item });
+        }
+        reports.push(ImportRowReport {
+            row: row.number,
+            record_code,
+            issues: values.issues,
+        });
+    }
+
+    let mut report = ImportReport::new(ImportEntity::OrderItems, dry_run, reports);
+    if !report.should_commit() {
+        return Ok(report);
+    }
+
+    let txn = db.begin().await?;
+    let mut item_ids = Vec::with_capacity(drafts.len());
+    for draft in drafts {
+        item_ids.push(insert_order_item(&txn, &draft.order_id, draft.item).await?);
+    }
+    txn.commit().await?;
+
+    report.committed = true;
+    report.created_count = item_ids.len();
+    enqueue_item_inserts(&state, app, &db, &item_ids).await;
+    Ok(report)
+}
+
+async fn insert_order_item<C: ConnectionTrait>(
+    conn: &C,
+    order_id: &str,
+    item: OrderItemPayload,
+) -> AppResult<String> {
+    let item_id = Uuid::new_v4().to_string();
+    conn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO order_items (id, order_id, product_url, product_qty, price, product_weight) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+        [
+            item_id.clone().into(),
+            order_id.into(),
+            item.product_url.into(),
+            item.product_qty.into(),
+            item.price.into(),
+            item.product_weight.into(),
+        ],
+    ))
+    .await?;
+    Ok(item_id)
+}
+
+async fn enqueue_customer_inserts(
+    state: &AppState,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    record_ids: &[String],
+) {
+    let pool = state.pool.lock().await;
+    for record_id in record_ids {
+        if let Ok(Some(record)) = Customer::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!("{} WHERE id = ?", CUSTOMER_SELECT),
+            [record_id.clone().into()],
+        ))
+        .one(db)
+        .await
+        {
+            enqueue_sync(
+                &pool,
+                app,
+                "customers",
+                "INSERT",
+                record_id,
+                serde_json::json!(record),
+            )
+            .await;
+        }
+    }
+}
+
+async fn enqueue_order_inserts(
+    state: &AppState,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    record_ids: &[String],
+) {
+    let pool = state.pool.lock().await;
+    for record_id in record_ids {
+        if let Ok(Some(order)) = Order::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT * FROM orders WHERE id = ?",
+            [record_id.clone().into()],
+        ))
+        .one(db)
+        .await
+        {
+            enqueue_sync(
+                &pool,
+                app,
+                "orders",
+                "INSERT",
+                record_id,
+                serde_json::json!(order),
+            )
+            .await;
+        }
+    }
+}
+
+async fn enqueue_item_inserts(
+    state: &AppState,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    item_ids: &[String],
+) {
+    let pool = state.pool.lock().await;
+    for item_id in item_ids {
+        if let Ok(Some(item)) = OrderItem::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT * FROM order_items WHERE id = ?",
+            [item_id.clone().into()],
+        ))
+        .one(db)
+        .await
+        {
+            enqueue_sync(
+                &pool,
+                app,
+                "order_items",
+                "INSERT",
+                item_id,
+                serde_json::json!(item),
+            )
+            .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::phone::DEFAULT_PHONE_COUNTRY;
+
+    fn table(csv: &str) -> ImportTable {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("import.csv");
+        std::fs::write(&path, csv).unwrap();
+        read_table(&path, None).unwrap()
+    }
+
+    fn columns(entity: ImportEntity, table: &ImportTable) -> ColumnMap {
+        ColumnMap::resolve(entity, &table.headers, None).unwrap()
+    }
+
+    async fn import_customer_rows(
+        db: &DatabaseConnection,
+        csv: &str,
+        dry_run: bool,
+    ) -> ImportReport {
+        let table = table(csv);
+        let columns = columns(ImportEntity::Customers, &table);
+        write_customers(db, &table, &columns, DEFAULT_PHONE_COUNTRY, dry_run)
+            .await
+            .unwrap()
+            .0
+    }
+
+    async fn import_order_rows(db: &DatabaseConnection, csv: &str, dry_run: bool) -> ImportReport {
+        let table = table(csv);
+        let columns = columns(ImportEntity::Orders, &table);
+        write_orders(db, &table, &columns, dry_run).await.unwrap().0
+    }
+
+    async fn count(db: &DatabaseConnection, table: &str) -> i64 {
+        db.query_one(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!("SELECT COUNT(*) AS n FROM {table}"),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get("", "n")
+        .unwrap()
+    }
+
+    fn issue_codes(report: &ImportReport) -> Vec<Vec<&'static str>> {
+        report
+            .rows
+            .iter()
+            .map(|row| row.issues.iter().map(|issue| issue.code).collect())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn customer_dry_run_reports_row_issues_and_writes_nothing() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+
+        let report = import_customer_rows(
+            &db,
+            "Name,Phone,City\n\
+             Aung Aung,09 791 234 567,Yangon\n\
+             ,09 791 234 568,Mandalay\n\
+             Ma Ma,12,Yangon\n",
+            true,
+        )
+        .await;
+
+        assert_eq!(
+            issue_codes(&report),
+            [vec![], vec!["missing_name"], vec!["invalid_phone"]]
+        );
+        assert_eq!(
+            report.rows.iter().map(|row| row.row).collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+        assert!(report.rows[0].record_code.is_some());
+        assert_eq!((report.valid_rows, report.invalid_rows), (1, 2));
+        assert!(!report.committed);
+        assert_eq!(count(&db, "customers").await, 0);
+    }
+
+    #[tokio::test]
+    async fn customer_import_assigns_the_next_customer_codes() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        let (prefix, first_seq) = next_customer_code_seq(&db).await;
+        let code = |seq: i64| format!("{prefix}{seq:05}");
+
+        let report =
+            import_customer_rows(&db, "Name,Phone\nAung Aung,09 791 234 567\nMa Ma,\n", false)
+                .await;
+
+        assert!(report.committed);
+        assert_eq!(report.created_count, 2);
+        let codes: Vec<_> = report
+            .rows
+            .iter()
+            .map(|row| row.record_code.clone())
+            .collect();
+        assert_eq!(codes, [Some(code(first_seq)), Some(code(first_seq + 1))]);
+        let stored = db
+            .query_one(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                "SELECT phone_e164 FROM customers WHERE customer_id = ?",
+                [code(first_seq).into()],
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored
+                .try_get::<Option<String>>("", "phone_e164")
+                .unwrap()
+                .as_deref(),
+            Some("+959791234567")
+        );
+
+        let next = import_customer_rows(&db, "Name\nKo Ko\n", true).await;
+        assert_eq!(next.rows[0].record_code, Some(code(first_seq + 2)));
+    }
+
+    #[tokio::test]
+    async fn one_failing_row_keeps_the_whole_import_out() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+
+        let report =
+            import_customer_rows(&db, "Name,Phone\nAung Aung,\n,09 791 234 567\n", false).await;
+
+        assert!(!report.committed);
+        assert_eq!(report.created_count, 0);
+        assert_eq!(count(&db, "customers").await, 0);
+    }
+
+    #[tokio::test]
+    async fn order_import_checks_customers_and_dates() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        let customer = import_customer_rows(&db, "Name\nAung Aung\n", false)
+            .await
+            .rows[0]
+            .record_code
+            .clone()
+            .unwrap();
+
+        let header = "Order Ref,Customer,Order Date,Product URL,Qty,Price\n";
+        let valid = format!(
+            "A1,{customer},15/03/2025,https://shop.example/1,1,1000\n\
+             a1,{customer},,https://shop.example/2,2,500\n"
+        );
+        let report = import_order_rows(
+            &db,
+            &format!(
+                "{header}{valid}\
+                 B1,C99999,2025-03-16,https://shop.example/3,1,10\n\
+                 C1,{customer},someday,https://shop.example/4,1,10\n"
+            ),
+            false,
+        )
+        .await;
+        assert_eq!(
+            issue_codes(&report),
+            [
+                vec![],
+                vec![],
+                vec!["unknown_customer"],
+                vec!["invalid_date"]
+            ]
+        );
+        assert!(!report.committed);
+        assert_eq!(count(&db, "orders").await, 0);
+        assert_eq!(count(&db, "order_items").await, 0);
+
+        let report = import_order_rows(&db, &format!("{header}{valid}"), false).await;
+        assert!(report.committed);
+        assert_eq!(report.created_count, 1);
+        assert_eq!(report.rows[0].record_code, report.rows[1].record_code);
+        assert_eq!(count(&db, "order_items").await, 2);
+        let order_date: Option<String> = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Sqlite,
+                "SELECT order_date FROM orders",
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get("", "order_date")
+            .unwrap();
+        assert_eq!(order_date.as_deref(), Some("2025-03-15"));
+    }
+}
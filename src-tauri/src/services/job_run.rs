@@ -0,0 +1,225 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::state::AppState;
+
+/// Emitted with a `JobCatchUpEvent` when a missed run is made up for.
+pub const JOB_CATCH_UP_EVENT: &str = "jobs://catch-up";
+/// Emitted with the `JobRun` when a scheduled job finishes.
+pub const JOB_COMPLETED_EVENT: &str = "jobs://completed";
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Older runs are deleted; the hourly recurring expense job adds ~700 a month.
+const KEEP_DAYS: i64 = 90;
+const DEFAULT_RUN_LIMIT: u32 = 50;
+const MAX_RUN_LIMIT: u32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledJob {
+    Backup,
+    RecurringExpenses,
+}
+
+impl ScheduledJob {
+    fn as_str(self) -> &'static str {
+        match self {
+            ScheduledJob::Backup => "backup",
+            ScheduledJob::RecurringExpenses => "recurring_expenses",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobTrigger {
+    Scheduled,
+    /// Run on startup for a time the app was closed or asleep.
+    CatchUp,
+}
+
+impl JobTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobTrigger::Scheduled => "scheduled",
+            JobTrigger::CatchUp => "catch_up",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromQueryResult)]
+pub struct JobRun {
+    pub id: String,
+    pub job: String,
+    pub trigger: String,
+    /// `running`, `success` or `failed`.
+    pub status: String,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobCatchUpEvent {
+    pub job: ScheduledJob,
+    /// The scheduled time that was missed.
+    pub missed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct LastSuccessRow {
+    finished_at: Option<String>,
+}
+
+const RUN_SELECT: &str =
+    "SELECT id, job, trigger, status, error, started_at, finished_at FROM job_runs";
+
+fn now() -> String {
+    Utc::now().format(TIME_FORMAT).to_string()
+}
+
+/// Runs a scheduled job and records it in `job_runs`, then emits
+/// `JOB_COMPLETED_EVENT`. The job's own error ends up in the run, not in the
+/// result.
+pub(crate) async fn record_job_run<F>(
+    state: &AppState,
+    app: &AppHandle,
+    job: ScheduledJob,
+    trigger: JobTrigger,
+    run: F,
+) -> AppResult<JobRun>
+where
+    F: Future<Output = AppResult<()>>,
+{
+    let db = state.db.lock().await.clone();
+    let id = Uuid::new_v4().to_string();
+    let started_at = now();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO job_runs (id, job, trigger, status, started_at) \
+         VALUES (?, ?, ?, 'running', ?)",
+        [
+            id.clone().into(),
+            job.as_str().into(),
+            trigger.as_str().into(),
+            started_at.clone().into(),
+        ],
+    ))
+    .await?;
+
+    let outcome = run.await;
+
+    // A restore may have swapped the connection while the job ran.
+    let db = state.db.lock().await.clone();
+    let (status, error) = match outcome {
+        Ok(()) => ("success", None),
+        Err(err) => ("failed", Some(err.to_string())),
+    };
+    let finished_at = now();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE job_runs SET status = ?, error = ?, finished_at = ? WHERE id = ?",
+        [
+            status.into(),
+            error.clone().into(),
+            finished_at.clone().into(),
+            id.clone().into(),
+        ],
+    ))
+    .await?;
+    let cutoff = (Utc::now() - Duration::days(KEEP_DAYS))
+        .format(TIME_FORMAT)
+        .to_string();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "DELETE FROM job_runs WHERE started_at < ?",
+        [cutoff.into()],
+    ))
+    .await?;
+
+    let job_run = JobRun {
+        id,
+        job: job.as_str().to_string(),
+        trigger: trigger.as_str().to_string(),
+        status: status.to_string(),
+        error,
+        started_at,
+        finished_at: Some(finished_at),
+    };
+    let _ = app.emit(JOB_COMPLETED_EVENT, job_run.clone());
+    Ok(job_run)
+}
+
+/// Marks runs left `running` by an app that closed mid-job as failed.
+pub(crate) async fn fail_interrupted_runs(db: &DatabaseConnection) -> AppResult<u64> {
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE job_runs SET status = 'failed', \
+             error = 'The app closed before the job finished', finished_at = ? \
+             WHERE status = 'running'",
+            [now().into()],
+        ))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// When a job last finished successfully. Scheduled backups recorded in
+/// `backup_runs` before `job_runs` existed count too.
+pub(crate) async fn last_success(
+    db: &DatabaseConnection,
+    job: ScheduledJob,
+) -> AppResult<Option<DateTime<Utc>>> {
+    let row = LastSuccessRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT MAX(finished_at) AS finished_at FROM ( \
+           SELECT finished_at FROM job_runs WHERE job = ? AND status = 'success' \
+           UNION ALL \
+           SELECT finished_at FROM backup_runs \
+           WHERE ? = 'backup' AND trigger = 'scheduled' AND status = 'success')",
+        [job.as_str().into(), job.as_str().into()],
+    ))
+    .one(db)
+    .await?;
+    Ok(row
+        .and_then(|row| row.finished_at)
+        .and_then(|time| NaiveDateTime::parse_from_str(&time, TIME_FORMAT).ok())
+        .map(|time| time.and_utc()))
+}
+
+/// Emits `JOB_CATCH_UP_EVENT` before a catch-up run.
+pub(crate) fn emit_catch_up(app: &AppHandle, job: ScheduledJob, missed_at: DateTime<Utc>) {
+    let _ = app.emit(JOB_CATCH_UP_EVENT, JobCatchUpEvent { job, missed_at });
+}
+
+/// Lists scheduled job runs, newest first, for one job or all of them.
+#[instrument(skip(state))]
+pub async fn get_job_runs(
+    state: Arc<AppState>,
+    job: Option<ScheduledJob>,
+    limit: Option<u32>,
+) -> AppResult<Vec<JobRun>> {
+    let db = state.db.lock().await.clone();
+    let limit = limit.unwrap_or(DEFAULT_RUN_LIMIT).clamp(1, MAX_RUN_LIMIT);
+    let statement = match job {
+        Some(job) => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!("{RUN_SELECT} WHERE job = ? ORDER BY started_at DESC, rowid DESC LIMIT ?"),
+            [job.as_str().into(), limit.into()],
+        ),
+        None => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!("{RUN_SELECT} ORDER BY started_at DESC, rowid DESC LIMIT ?"),
+            [limit.into()],
+        ),
+    };
+    Ok(JobRun::find_by_statement(statement).all(&db).await?)
+}
@@ -0,0 +1,618 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::sync::Arc;
+
+use printpdf::{
+    BuiltinFont, Color, Greyscale, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Rect,
+};
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::instrument;
+
+use crate::error::{AppError, AppResult};
+use crate::services::barcode::{code128_widths, CODE128_QUIET_ZONE};
+use crate::services::order::normalize_order_status_filter;
+use crate::services::settings::get_app_settings;
+use crate::state::AppState;
+
+const PT_TO_MM: f32 = 0.3528;
+const LINE_SPACING: f32 = 1.2;
+/// Rough Helvetica advance width as a fraction of the font size, used to
+/// wrap and truncate text since builtin fonts carry no metrics.
+const AVG_CHAR_WIDTH: f32 = 0.5;
+const LABEL_PADDING_MM: f32 = 3.0;
+const MAX_BARCODE_MODULE_MM: f32 = 0.5;
+/// Narrowest bar handheld scanners still read reliably off a label printer.
+const MIN_BARCODE_MODULE_MM: f32 = 0.25;
+
+const A4: (f32, f32) = (210.0, 297.0);
+const LETTER: (f32, f32) = (215.9, 279.4);
+
+/// Physical layout of a label sheet. All dimensions are in millimetres and
+/// rows are assumed to butt against each other, as on common sheets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelSheetLayout {
+    pub page_width: f32,
+    pub page_height: f32,
+    pub columns: usize,
+    pub rows: usize,
+    pub label_width: f32,
+    pub label_height: f32,
+    pub margin_left: f32,
+    pub margin_top: f32,
+    pub gap_x: f32,
+}
+
+impl LabelSheetLayout {
+    /// Looks up a supported sheet by its `label_sheet_size` key.
+    pub fn from_key(key: &str) -> AppResult<Self> {
+        let layout = match key.trim().to_ascii_lowercase().as_str() {
+            // Avery L7160: 21 labels, 63.5 x 38.1mm.
+            "a4_21" => Self::sheet(A4, (3, 7), (63.5, 38.1), (7.2, 15.15), 2.5),
+            // Avery L7163: 14 labels, 99.1 x 38.1mm.
+            "" | "a4_14" => Self::sheet(A4, (2, 7), (99.1, 38.1), (4.65, 15.15), 2.5),
+            // Avery L7165: 8 labels, 99.1 x 67.7mm.
+            "a4_8" => Self::sheet(A4, (2, 4), (99.1, 67.7), (4.65, 13.0), 2.5),
+            // Avery 5163: 10 labels, 4 x 2in.
+            "letter_10" => Self::sheet(LETTER, (2, 5), (101.6, 50.8), (3.97, 12.7), 4.76),
+            // Avery 5164: 6 labels, 4 x 3 1/3in.
+            "letter_6" => Self::sheet(LETTER, (2, 3), (101.6, 84.7), (3.97, 12.7), 4.76),
+            // Single 4 x 6in thermal shipping label.
+            "label_4x6" => Self::sheet((101.6, 152.4), (1, 1), (101.6, 152.4), (0.0, 0.0), 0.0),
+            other => {
+                return Err(AppError::invalid_input(format!(
+                    "Unsupported label sheet size: {other}"
+                )))
+            }
+        };
+        Ok(layout)
+    }
+
+    fn sheet(
+        page: (f32, f32),
+        grid: (usize, usize),
+        label: (f32, f32),
+        margins: (f32, f32),
+        gap_x: f32,
+    ) -> Self {
+        Self {
+            page_width: page.0,
+            page_height: page.1,
+            columns: grid.0,
+            rows: grid.1,
+            label_width: label.0,
+            label_height: label.1,
+            margin_left: margins.0,
+            margin_top: margins.1,
+            gap_x,
+        }
+    }
+
+    pub fn labels_per_page(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    fn inner_width(&self) -> f32 {
+        self.label_width - 2.0 * LABEL_PADDING_MM
+    }
+
+    /// Lower-left corner of the label in slot `index` on its page.
+    fn label_origin(&self, index: usize) -> (f32, f32) {
+        let column = index % self.columns;
+        let row = index / self.columns;
+        let x = self.margin_left + column as f32 * (self.label_width + self.gap_x);
+        let top = self.page_height - self.margin_top - row as f32 * self.label_height;
+        (x, top - self.label_height)
+    }
+}
+
+/// One shipping label; orders for the same customer share a label, with one
+/// barcode per order so each scan resolves to an order.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParcelLabel {
+    pub customer_code: Option<String>,
+    pub customer_name: String,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    pub order_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelSheetResult {
+    pub path: String,
+    pub label_count: usize,
+    pub page_count: usize,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct LabelRow {
+    id: String,
+    order_id: Option<String>,
+    customer_id: Option<String>,
+    customer_code: Option<String>,
+    customer_name: Option<String>,
+    phone: Option<String>,
+    address: Option<String>,
+    city: Option<String>,
+}
+
+/// Loads orders by id or status and groups them into one label per customer.
+pub async fn collect_parcel_labels(
+    state: Arc<AppState>,
+    order_ids: Option<Vec<String>>,
+    status: Option<String>,
+) -> AppResult<Vec<ParcelLabel>> {
+    let order_ids: Vec<String> = order_ids
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    let status = normalize_order_status_filter(status)?;
+
+    let mut conditions = vec!["o.deleted_at IS NULL".to_string()];
+    let mut params: Vec<sea_orm::Value> = vec![];
+    if !order_ids.is_empty() {
+        let placeholders = vec!["?"; order_ids.len()].join(", ");
+        conditions.push(format!("o.id IN ({})", placeholders));
+        params.extend(order_ids.iter().cloned().map(Into::into));
+    } else if let Some(status) = status {
+        conditions.push("o.status = ?".to_string());
+        params.push(status.into());
+    } else {
+        return Err(AppError::invalid_input(
+            "Select orders or a status to print labels for",
+        ));
+    }
+
+    let sql = format!(
+        "SELECT o.id, o.order_id, o.customer_id, c.customer_id as customer_code, \
+         c.name as customer_name, c.phone, c.address, c.city \
+         FROM orders o LEFT JOIN customers c ON o.customer_id = c.id \
+         WHERE {} ORDER BY c.name COLLATE NOCASE ASC, o.order_id ASC",
+        conditions.join(" AND ")
+    );
+
+    let db = state.db.lock().await.clone();
+    let rows = LabelRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        &sql,
+        params,
+    ))
+    .all(&db)
+    .await?;
+
+    let mut labels: Vec<(String, ParcelLabel)> = Vec::new();
+    for row in rows {
+        let order_code = row.order_id.unwrap_or_else(|| row.id.clone());
+        let group_key = row.customer_id.unwrap_or(row.id);
+        if let Some((_, label)) = labels.iter_mut().find(|(key, _)| *key == group_key) {
+            label.order_ids.push(order_code);
+            continue;
+        }
+        labels.push((
+            group_key,
+            ParcelLabel {
+                customer_code: row.customer_code,
+                customer_name: row.customer_name.unwrap_or_default(),
+                phone: row.phone,
+                address: row.address,
+                city: row.city,
+                order_ids: vec![order_code],
+            },
+        ));
+    }
+
+    Ok(labels.into_iter().map(|(_, label)| label).collect())
+}
+
+struct LabelFonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Builtin fonts only cover WinAnsi, so text is sanitized unless a TTF
+    /// with the needed glyphs was embedded.
+    builtin: bool,
+}
+
+fn load_fonts(doc: &PdfDocumentReference, font_path: &str) -> AppResult<LabelFonts> {
+    let font_path = font_path.trim();
+    if !font_path.is_empty() {
+        let file = File::open(font_path).map_err(|e| {
+            AppError::invalid_input(format!("Failed to open label font {}: {}", font_path, e))
+        })?;
+        let font = doc
+            .add_external_font(BufReader::new(file))
+            .map_err(|e| AppError::internal(e.to_string()))?;
+        return Ok(LabelFonts {
+            regular: font.clone(),
+            bold: font,
+            builtin: false,
+        });
+    }
+
+    Ok(LabelFonts {
+        regular: doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| AppError::internal(e.to_string()))?,
+        bold: doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| AppError::internal(e.to_string()))?,
+        builtin: true,
+    })
+}
+
+fn sanitize_text(text: &str, builtin: bool) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !builtin {
+        return text;
+    }
+    text.chars()
+        .map(|c| if (c as u32) < 0x100 { c } else { '?' })
+        .collect()
+}
+
+fn max_chars(width_mm: f32, font_size: f32) -> usize {
+    (width_mm / (font_size * PT_TO_MM * AVG_CHAR_WIDTH)).max(1.0) as usize
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max.saturating_sub(3)).collect();
+    truncated.push_str("...");
+    truncated
+}
+
+/// Greedy word wrap limited to `max_lines`; the last line is truncated.
+fn wrap(text: &str, max: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate_len = current.chars().count() + word.chars().count() + 1;
+        if !current.is_empty() && candidate_len > max {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        let rest = lines.split_off(max_lines - 1).join(" ");
+        lines.push(rest);
+    }
+    lines.into_iter().map(|line| truncate(&line, max)).collect()
+}
+
+fn format_order_ids(order_ids: &[String], max: usize) -> String {
+    let mut text = String::from("Orders: ");
+    for (index, id) in order_ids.iter().enumerate() {
+        let remaining = order_ids.len() - index;
+        let separator = if index == 0 { "" } else { ", " };
+        let suffix = format!(" +{} more", remaining);
+        if text.chars().count() + separator.len() + id.chars().count() + suffix.len() > max
+            && remaining > 1
+        {
+            text.push_str(&suffix);
+            return text;
+        }
+        text.push_str(separator);
+        text.push_str(id);
+    }
+    truncate(&text, max)
+}
+
+fn barcode_modules(widths: &[u8]) -> usize {
+    widths.iter().map(|w| *w as usize).sum::<usize>() + 2 * CODE128_QUIET_ZONE
+}
+
+/// Splits labels whose order barcodes do not fit side by side at
+/// `MIN_BARCODE_MODULE_MM` into several labels for the same customer.
+fn fit_barcodes(
+    labels: Vec<ParcelLabel>,
+    layout: &LabelSheetLayout,
+) -> AppResult<Vec<ParcelLabel>> {
+    let max_modules = (layout.inner_width() / MIN_BARCODE_MODULE_MM) as usize;
+    let mut fitted = Vec::with_capacity(labels.len());
+    for label in labels {
+        let mut chunk: Vec<String> = Vec::new();
+        let mut modules = 0;
+        for order_id in &label.order_ids {
+            let needed = barcode_modules(&code128_widths(order_id)?);
+            if !chunk.is_empty() && modules + needed > max_modules {
+                fitted.push(ParcelLabel {
+                    order_ids: std::mem::take(&mut chunk),
+                    ..label.clone()
+                });
+                modules = 0;
+            }
+            modules += needed;
+            chunk.push(order_id.clone());
+        }
+        fitted.push(ParcelLabel {
+            order_ids: chunk,
+            ..label
+        });
+    }
+    Ok(fitted)
+}
+
+fn draw_barcode(layer: &PdfLayerReference, widths: &[u8], x: f32, y: f32, width: f32, height: f32) {
+    let modules = barcode_modules(widths);
+    let module = (width / modules as f32).min(MAX_BARCODE_MODULE_MM);
+    let symbol_width = module * modules as f32;
+    let mut cursor = x + (width - symbol_width) / 2.0 + module * CODE128_QUIET_ZONE as f32;
+
+    for (index, w) in widths.iter().enumerate() {
+        let bar_width = module * *w as f32;
+        if index % 2 == 0 {
+            layer.add_rect(Rect::new(
+                Mm(cursor),
+                Mm(y),
+                Mm(cursor + bar_width),
+                Mm(y + height),
+            ));
+        }
+        cursor += bar_width;
+    }
+}
+
+fn draw_label(
+    layer: &PdfLayerReference,
+    fonts: &LabelFonts,
+    layout: &LabelSheetLayout,
+    label: &ParcelLabel,
+    origin: (f32, f32),
+) -> AppResult<()> {
+    let (x0, y0) = origin;
+    let inner_width = layout.inner_width();
+    let scale = (layout.label_height / 38.1).clamp(1.0, 1.6);
+    let name_size = 10.0 * scale;
+    let body_size = 8.0 * scale;
+    let small_size = 7.0 * scale;
+    let line_height = |size: f32| size * PT_TO_MM * LINE_SPACING;
+
+    // The barcodes are reserved first so the text block never pushes them
+    // off the label; the address gets whatever lines are left. Each order
+    // gets a slot proportional to its symbol so all bars share one width.
+    let mut floor = y0 + LABEL_PADDING_MM;
+    if !label.order_ids.is_empty() {
+        let symbols = label
+            .order_ids
+            .iter()
+            .map(|order_id| Ok((order_id, code128_widths(order_id)?)))
+            .collect::<AppResult<Vec<_>>>()?;
+        let total_modules: usize = symbols
+            .iter()
+            .map(|(_, widths)| barcode_modules(widths))
+            .sum();
+        let barcode_height = (layout.label_height * 0.2).min(15.0);
+        let barcode_y = floor + line_height(small_size);
+        let mut slot_x = x0 + LABEL_PADDING_MM;
+        for (order_id, widths) in &symbols {
+            let slot_width = inner_width * barcode_modules(widths) as f32 / total_modules as f32;
+            draw_barcode(layer, widths, slot_x, barcode_y, slot_width, barcode_height);
+            let caption = truncate(order_id, max_chars(slot_width, small_size));
+            let caption_width =
+                caption.chars().count() as f32 * small_size * PT_TO_MM * AVG_CHAR_WIDTH;
+            layer.use_text(
+                caption,
+                small_size,
+                Mm(slot_x + (slot_width - caption_width) / 2.0),
+                Mm(floor),
+                &fonts.regular,
+            );
+            slot_x += slot_width;
+        }
+        floor = barcode_y + barcode_height + 1.0;
+    }
+
+    let phone = label.phone.as_deref().filter(|p| !p.trim().is_empty());
+    let address = label.address.as_deref().filter(|a| !a.trim().is_empty());
+    let city = label.city.as_deref().filter(|c| !c.trim().is_empty());
+
+    let top = y0 + layout.label_height - LABEL_PADDING_MM;
+    let fixed_height = line_height(name_size)
+        + line_height(small_size)
+        + [phone, city].iter().flatten().count() as f32 * line_height(body_size);
+    let address_lines =
+        ((top - floor - fixed_height) / line_height(body_size)).clamp(1.0, 3.0) as usize;
+
+    let mut y = top;
+    let x = x0 + LABEL_PADDING_MM;
+    let mut write = |text: &str, size: f32, font: &IndirectFontRef| {
+        y -= size * PT_TO_MM;
+        let text = truncate(
+            &sanitize_text(text, fonts.builtin),
+            max_chars(inner_width, size),
+        );
+        layer.use_text(text, size, Mm(x), Mm(y), font);
+        y -= size * PT_TO_MM * (LINE_SPACING - 1.0);
+    };
+
+    write(&label.customer_name, name_size, &fonts.bold);
+    if let Some(phone) = phone {
+        write(phone, body_size, &fonts.regular);
+    }
+    if let Some(address) = address {
+        let address = sanitize_text(address, fonts.builtin);
+        for line in wrap(&address, max_chars(inner_width, body_size), address_lines) {
+            write(&line, body_size, &fonts.regular);
+        }
+    }
+    if let Some(city) = city {
+        write(city, body_size, &fonts.bold);
+    }
+    write(
+        &format_order_ids(&label.order_ids, max_chars(inner_width, small_size)),
+        small_size,
+        &fonts.regular,
+    );
+
+    Ok(())
+}
+
+/// Renders labels onto label sheets and returns the PDF bytes with the page count.
+pub fn render_label_sheets(
+    labels: &[ParcelLabel],
+    layout: &LabelSheetLayout,
+    font_path: &str,
+) -> AppResult<(Vec<u8>, usize)> {
+    let (doc, first_page, first_layer) = PdfDocument::new(
+        "Parcel labels",
+        Mm(layout.page_width),
+        Mm(layout.page_height),
+        "Labels",
+    );
+    let fonts = load_fonts(&doc, font_path)?;
+
+    let per_page = layout.labels_per_page();
+    let mut page_count = 0;
+    for (page_index, page_labels) in labels.chunks(per_page).enumerate() {
+        let layer = if page_index == 0 {
+            doc.get_page(first_page).get_layer(first_layer)
+        } else {
+            let (page, layer) =
+                doc.add_page(Mm(layout.page_width), Mm(layout.page_height), "Labels");
+            doc.get_page(page).get_layer(layer)
+        };
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+
+        for (slot, label) in page_labels.iter().enumerate() {
+            draw_label(&layer, &fonts, layout, label, layout.label_origin(slot))?;
+        }
+        page_count += 1;
+    }
+
+    let bytes = doc
+        .save_to_bytes()
+        .map_err(|e| AppError::internal(e.to_string()))?;
+    Ok((bytes, page_count.max(1)))
+}
+
+/// Generates a PDF of shipping labels for the selected orders, or for every
+/// order with `status` when no ids are given.
+#[instrument(skip(state, app))]
+pub async fn generate_parcel_labels(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_ids: Option<Vec<String>>,
+    status: Option<String>,
+    sheet_size: Option<String>,
+    dest_path: String,
+) -> AppResult<LabelSheetResult> {
+    let dest_path = dest_path.trim().to_string();
+    if dest_path.is_empty() {
+        return Err(AppError::invalid_input("Destination path is required"));
+    }
+
+    let settings = get_app_settings(app.clone())?;
+    let layout = LabelSheetLayout::from_key(
+        sheet_size
+            .as_deref()
+            .unwrap_or(settings.label_sheet_size.as_str()),
+    )?;
+
+    let labels = collect_parcel_labels(state, order_ids, status).await?;
+    if labels.is_empty() {
+        return Err(AppError::not_found("No orders matched the label selection"));
+    }
+    let labels = fit_barcodes(labels, &layout)?;
+
+    let (bytes, page_count) = render_label_sheets(&labels, &layout, &settings.label_font_path)?;
+    fs::write(&dest_path, bytes)?;
+
+    Ok(LabelSheetResult {
+        path: dest_path,
+        label_count: labels.len(),
+        page_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::ConnectionTrait;
+
+    use super::*;
+    use crate::services::scan::resolve_scanned_code;
+
+    async fn seed(state: &AppState, sql: &str) {
+        let db = state.db.lock().await.clone();
+        db.execute_unprepared(sql).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn every_label_barcode_resolves_to_its_order() {
+        let state = AppState::for_tests().await;
+        seed(
+            &state,
+            "INSERT INTO customers (id, customer_id, name) VALUES ('c1', 'CUST-0001', 'Aung Aung'); \
+             INSERT INTO orders (id, order_id, customer_id, status) VALUES \
+               ('o1', 'ORD-0001', 'c1', 'pending'), \
+               ('o2', 'ORD-0002', 'c1', 'pending'), \
+               ('o3', NULL, NULL, 'pending');",
+        )
+        .await;
+
+        let labels = collect_parcel_labels(state.clone(), None, Some("pending".into()))
+            .await
+            .unwrap();
+        let layout = LabelSheetLayout::from_key("a4_14").unwrap();
+        let labels = fit_barcodes(labels, &layout).unwrap();
+
+        let db = state.db.lock().await.clone();
+        let mut resolved = Vec::new();
+        for label in &labels {
+            for code in &label.order_ids {
+                let order = resolve_scanned_code(&db, code).await.unwrap();
+                resolved.push(order.map(|order| order.id));
+            }
+        }
+        resolved.sort();
+        assert_eq!(
+            resolved,
+            [Some("o1".into()), Some("o2".into()), Some("o3".into())]
+        );
+        // The customer code is not a scannable order.
+        assert!(resolve_scanned_code(&db, "CUST-0001")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn splits_labels_whose_barcodes_do_not_fit() {
+        let layout = LabelSheetLayout::from_key("a4_21").unwrap();
+        let label = ParcelLabel {
+            customer_code: Some("CUST-0001".into()),
+            customer_name: "Aung Aung".into(),
+            phone: None,
+            address: None,
+            city: None,
+            order_ids: (1..=5).map(|n| format!("ORD-000{n}")).collect(),
+        };
+        let fitted = fit_barcodes(vec![label], &layout).unwrap();
+
+        let max_modules = (layout.inner_width() / MIN_BARCODE_MODULE_MM) as usize;
+        let mut order_ids = Vec::new();
+        for label in &fitted {
+            let modules: usize = label
+                .order_ids
+                .iter()
+                .map(|id| barcode_modules(&code128_widths(id).unwrap()))
+                .sum();
+            assert!(modules <= max_modules);
+            order_ids.extend(label.order_ids.iter().cloned());
+        }
+        assert!(fitted.len() > 1);
+        assert_eq!(order_ids.len(), 5);
+    }
+}
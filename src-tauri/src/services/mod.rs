@@ -1,10 +1,31 @@
 pub mod account;
+pub mod attachment;
 pub mod auth;
+pub mod backup;
+pub mod barcode;
 pub mod customer;
+pub mod customer_dedupe;
 pub mod drive;
+pub mod escpos;
 pub mod expense;
+pub mod expense_category;
+pub mod export;
+pub mod import;
+pub mod job_run;
+pub mod labels;
 pub mod order;
+pub mod pagination;
+pub mod phone;
+pub mod printer;
+pub mod product_photo;
+pub mod receipt;
+pub mod recurring_expense;
+pub mod saved_view;
+pub mod scan;
+pub mod search;
 pub mod settings;
 pub mod shop;
 pub mod staff;
+pub mod storage;
 pub mod system;
+pub mod vault;
@@ -8,13 +8,19 @@ use tauri::AppHandle;
 use uuid::Uuid;
 
 use crate::db::{
-    DEFAULT_ORDER_ID_PREFIX, ORDER_WITH_CUSTOMER_GROUP_BY, ORDER_WITH_CUSTOMER_SELECT,
+    ListQuery, ListSort, DEFAULT_ORDER_ID_PREFIX, ORDER_WITH_CUSTOMER_GROUP_BY,
+    ORDER_WITH_CUSTOMER_SELECT,
 };
 use crate::error::{AppError, AppResult};
 use crate::models::{
-    DashboardDetailRecord, DashboardStats, OrderDetail, OrderExportRow, OrderItem,
-    OrderItemPayload, OrderWithCustomer, PaginatedOrders,
+    DashboardDetailRecord, DashboardStats, NumberRange, OrderConditions, OrderDetail,
+    OrderExportRow, OrderFeeFlags, OrderItem, OrderItemPayload, OrderListFilter, OrderWithCustomer,
+    PaginatedOrders,
 };
+use crate::services::attachment::{delete_entity_attachments, AttachmentEntityType};
+use crate::services::pagination::{fetch_cursor_page, CursorPage, ListSource};
+use crate::services::phone::phone_search_digits;
+use crate::services::product_photo::{delete_item_photos, delete_order_photos, move_item_photos};
 use crate::state::AppState;
 use crate::sync::enqueue_sync;
 
@@ -37,7 +43,7 @@ struct NextSeqRow {
     next_seq: i64,
 }
 
-fn normalize_order_status(status: Option<String>) -> AppResult<Option<String>> {
+pub(crate) fn normalize_order_status(status: Option<String>) -> AppResult<Option<String>> {
     let normalized = status
         .map(|value| value.trim().to_lowercase())
         .filter(|value| !value.is_empty());
@@ -49,7 +55,7 @@ fn normalize_order_status(status: Option<String>) -> AppResult<Option<String>> {
     }
 }
 
-fn normalize_order_status_filter(status: Option<String>) -> AppResult<Option<String>> {
+pub(crate) fn normalize_order_status_filter(status: Option<String>) -> AppResult<Option<String>> {
     let normalized = status
         .map(|value| value.trim().to_lowercase())
         .filter(|value| !value.is_empty());
@@ -61,7 +67,7 @@ fn normalize_order_status_filter(status: Option<String>) -> AppResult<Option<Str
     normalize_order_status(normalized)
 }
 
-fn parse_flexible_date(value: Option<&str>) -> Option<NaiveDate> {
+pub(crate) fn parse_flexible_date(value: Option<&str>) -> Option<NaiveDate> {
     let raw = value?.trim();
     if raw.is_empty() {
         return None;
@@ -105,6 +111,94 @@ fn parse_flexible_date(value: Option<&str>) -> Option<NaiveDate> {
     None
 }
 
+/// Order date columns; free-form text, so dates are kept as `YYYY-MM-DD`
+/// where possible for SQL to compare.
+const ORDER_DATE_COLUMNS: [&str; 4] = [
+    "order_date",
+    "arrived_date",
+    "shipment_date",
+    "user_withdraw_date",
+];
+
+/// Rewrites an order date in any `parse_flexible_date` layout to
+/// `YYYY-MM-DD`. Values already starting with one keep their time; blank and
+/// unrecognised values are kept as they are.
+pub(crate) fn iso_order_date(value: Option<String>) -> Option<String> {
+    let raw = value.as_deref()?.trim();
+    let iso_prefix = raw
+        .get(..10)
+        .is_some_and(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok());
+    if iso_prefix {
+        return Some(raw.to_string());
+    }
+    match parse_flexible_date(Some(raw)) {
+        Some(date) => Some(date.format("%Y-%m-%d").to_string()),
+        None => value,
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct OrderDatesRow {
+    id: String,
+    order_date: Option<String>,
+    arrived_date: Option<String>,
+    shipment_date: Option<String>,
+    user_withdraw_date: Option<String>,
+}
+
+/// Rewrites stored order dates with `iso_order_date`, for rows written before
+/// dates were normalized or pulled from devices that still store other
+/// layouts. Returns how many orders changed.
+pub(crate) async fn normalize_order_dates<C: ConnectionTrait>(conn: &C) -> AppResult<u64> {
+    let non_iso = ORDER_DATE_COLUMNS
+        .map(|column| {
+            format!(
+                "(TRIM(COALESCE({column}, '')) <> '' AND {column} NOT GLOB \
+                 '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]*')"
+            )
+        })
+        .join(" OR ");
+    let rows = OrderDatesRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!(
+            "SELECT id, order_date, arrived_date, shipment_date, user_withdraw_date \
+             FROM orders WHERE {non_iso}"
+        ),
+    ))
+    .all(conn)
+    .await?;
+
+    let mut changed = 0;
+    for row in rows {
+        let stored = [
+            row.order_date,
+            row.arrived_date,
+            row.shipment_date,
+            row.user_withdraw_date,
+        ];
+        let normalized = stored.clone().map(iso_order_date);
+        if normalized == stored {
+            continue;
+        }
+        let [order_date, arrived_date, shipment_date, user_withdraw_date] = normalized;
+        conn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE orders SET order_date = ?, arrived_date = ?, shipment_date = ?, \
+             user_withdraw_date = ? WHERE id = ?",
+            [
+                order_date.into(),
+                arrived_date.into(),
+                shipment_date.into(),
+                user_withdraw_date.into(),
+                row.id.into(),
+            ],
+        ))
+        .await?;
+        changed += 1;
+    }
+    Ok(changed)
+}
+
 fn normalized_dashboard_date_field(date_field: Option<String>) -> &'static str {
     match date_field.as_deref() {
         Some("created_at") => "created_at",
@@ -205,6 +299,36 @@ fn calculate_effective_cargo_fee(order: &OrderWithCustomer) -> f64 {
     order.cargo_fee.unwrap_or(0.0)
 }
 
+/// Reads the configured order code prefix and the next free sequence number for it.
+pub(crate) async fn next_order_code_seq<C: ConnectionTrait>(conn: &C) -> (String, i64) {
+    let prefix_str = PrefixRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "SELECT order_id_prefix FROM shop_settings ORDER BY created_at DESC LIMIT 1",
+    ))
+    .one(conn)
+    .await
+    .ok()
+    .flatten()
+    .and_then(|r| r.order_id_prefix)
+    .filter(|p| !p.is_empty())
+    .unwrap_or_else(|| DEFAULT_ORDER_ID_PREFIX.to_string());
+
+    let like_pattern = format!("{}%", prefix_str);
+    let next_seq = NextSeqRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT COALESCE(MAX(CAST(REPLACE(order_id, ?, '') AS INTEGER)), 0) + 1 AS next_seq \
+         FROM orders WHERE order_id LIKE ?",
+        [prefix_str.clone().into(), like_pattern.into()],
+    ))
+    .one(conn)
+    .await
+    .unwrap_or(None)
+    .map(|r| r.next_seq)
+    .unwrap_or(1);
+
+    (prefix_str, next_seq)
+}
+
 pub async fn create_order(
     state: Arc<AppState>,
     app: &AppHandle,
@@ -238,6 +362,8 @@ pub async fn create_order(
     let record_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
     let normalized_status =
         normalize_order_status(status)?.unwrap_or_else(|| "pending".to_string());
+    let [order_date, arrived_date, shipment_date, user_withdraw_date] =
+        [order_date, arrived_date, shipment_date, user_withdraw_date].map(iso_order_date);
 
     let txn = db.begin().await?;
 
@@ -304,33 +430,7 @@ pub async fn create_order(
             ))
             .await;
     } else {
-        let prefix_row = PrefixRow::find_by_statement(Statement::from_string(
-            DatabaseBackend::Sqlite,
-            "SELECT order_id_prefix FROM shop_settings ORDER BY created_at DESC LIMIT 1",
-        ))
-        .one(&txn)
-        .await
-        .ok()
-        .flatten();
-
-        let prefix_str = prefix_row
-            .and_then(|r| r.order_id_prefix)
-            .filter(|p| !p.is_empty())
-            .unwrap_or_else(|| DEFAULT_ORDER_ID_PREFIX.to_string());
-
-        let like_pattern = format!("{}%", prefix_str);
-        let next_seq = NextSeqRow::find_by_statement(Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
-            "SELECT COALESCE(MAX(CAST(REPLACE(order_id, ?, '') AS INTEGER)), 0) + 1 AS next_seq \
-             FROM orders WHERE order_id LIKE ?",
-            [prefix_str.clone().into(), like_pattern.into()],
-        ))
-        .one(&txn)
-        .await
-        .unwrap_or(None)
-        .map(|r| r.next_seq)
-        .unwrap_or(1);
-
+        let (prefix_str, next_seq) = next_order_code_seq(&txn).await;
         let new_order_id = format!("{}{:05}", prefix_str, next_seq);
         let _ = txn
             .execute(Statement::from_sql_and_values(
@@ -407,37 +507,38 @@ pub async fn get_orders(state: Arc<AppState>) -> AppResult<Vec<OrderWithCustomer
     Ok(orders)
 }
 
-pub async fn get_orders_paginated(
-    state: Arc<AppState>,
-    page: Option<i64>,
-    page_size: Option<i64>,
-    search_key: Option<String>,
-    search_term: Option<String>,
-    status_filter: Option<String>,
-    sort_by: Option<String>,
-    sort_order: Option<String>,
-) -> AppResult<PaginatedOrders> {
-    let db = state.db.lock().await.clone();
-
-    let requested_page_size = page_size.unwrap_or(DEFAULT_ORDERS_PAGE_SIZE);
-    let no_limit = requested_page_size <= 0;
-    let page_size = if no_limit {
-        DEFAULT_ORDERS_PAGE_SIZE
-    } else {
-        requested_page_size.clamp(MIN_ORDERS_PAGE_SIZE, MAX_ORDERS_PAGE_SIZE)
-    };
-    let page = if no_limit {
-        1
+/// Service fee in currency; percentage fees apply to the item total.
+pub fn service_fee_amount(order: &OrderWithCustomer) -> f64 {
+    let service_fee = order.service_fee.unwrap_or(0.0);
+    if order.service_fee_type.as_deref() == Some("percent") {
+        order.total_price.unwrap_or(0.0) * (service_fee / 100.0)
     } else {
-        page.unwrap_or(1).max(1)
-    };
-    let offset = if no_limit { 0 } else { (page - 1) * page_size };
+        service_fee
+    }
+}
 
-    let raw_search = search_term.unwrap_or_default().trim().to_string();
+/// Order total as shown on the order detail screen: items plus shipping,
+/// delivery, cargo and service fees.
+pub fn order_grand_total(order: &OrderWithCustomer) -> f64 {
+    order.total_price.unwrap_or(0.0)
+        + order.shipping_fee.unwrap_or(0.0)
+        + order.delivery_fee.unwrap_or(0.0)
+        + order.cargo_fee.unwrap_or(0.0)
+        + service_fee_amount(order)
+}
+
+/// Builds the WHERE/ORDER BY clauses for the order list from its filters.
+pub(crate) fn build_order_list_query(filter: &OrderListFilter) -> AppResult<ListQuery> {
+    let raw_search = filter
+        .search_term
+        .clone()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
     let has_search = !raw_search.is_empty();
     let search_pattern = format!("%{}%", raw_search);
-    let normalized_status_filter = normalize_order_status_filter(status_filter)?;
-    let search_column = match search_key.as_deref().unwrap_or("customerName") {
+    let normalized_status_filter = normalize_order_status_filter(filter.status_filter.clone())?;
+    let search_column = match filter.search_key.as_deref().unwrap_or("customerName") {
         "customerName" => "c.name",
         "orderId" => "o.order_id",
         "customerId" => "c.customer_id",
@@ -445,7 +546,7 @@ pub async fn get_orders_paginated(
         _ => return Err("Invalid search key".into()),
     };
 
-    let sort_column = match sort_by.as_deref().unwrap_or("order_id") {
+    let sort_column = match filter.sort_by.as_deref().unwrap_or("order_id") {
         "customer_name" => "c.name",
         "order_id" => "o.order_id",
         "created_at" => "o.created_at",
@@ -453,7 +554,7 @@ pub async fn get_orders_paginated(
         _ => "o.created_at",
     };
 
-    let sort_direction = match sort_order.as_deref().unwrap_or("desc") {
+    let sort_direction = match filter.sort_order.as_deref().unwrap_or("desc") {
         "asc" => "ASC",
         _ => "DESC",
     };
@@ -464,19 +565,292 @@ pub async fn get_orders_paginated(
     let mut params: Vec<sea_orm::Value> = vec![];
 
     if has_search {
-        conditions.push(format!("COALESCE({}, '') LIKE ?", search_column));
-        params.push(search_pattern.into());
+        match phone_search_digits(&raw_search).filter(|_| search_column == "c.phone") {
+            // Phone searches also match the stored E.164 form, so spacing and
+            // a leading 0 or country code don't matter.
+            Some(digits) => {
+                conditions.push(
+                    "(COALESCE(c.phone, '') LIKE ? OR COALESCE(c.phone_e164, '') LIKE ?)"
+                        .to_string(),
+                );
+                params.push(search_pattern.into());
+                params.push(format!("%{digits}%").into());
+            }
+            None => {
+                conditions.push(format!("COALESCE({}, '') LIKE ?", search_column));
+                params.push(search_pattern.into());
+            }
+        }
     }
     if let Some(status) = normalized_status_filter.as_deref() {
         conditions.push("o.status = ?".to_string());
         params.push(status.to_string().into());
     }
+    if let Some(extra) = filter.conditions.as_ref() {
+        push_order_conditions(extra, &mut conditions, &mut params)?;
+    }
+
+    Ok(
+        ListQuery::new(conditions, params, order_clause).with_sort(ListSort::text(
+            sort_column,
+            sort_direction == "DESC",
+            "o.id",
+        )),
+    )
+}
+
+const ORDER_ITEMS_TOTAL_PRICE: &str = "(SELECT COALESCE(SUM(price * product_qty), 0) \
+     FROM order_items WHERE order_id = o.id AND deleted_at IS NULL)";
+const ORDER_ITEMS_TOTAL_WEIGHT: &str = "(SELECT COALESCE(SUM(product_weight), 0) \
+     FROM order_items WHERE order_id = o.id AND deleted_at IS NULL)";
+
+fn order_date_column(field: &str) -> AppResult<&'static str> {
+    match field {
+        "order_date" => Ok("o.order_date"),
+        "arrived_date" => Ok("o.arrived_date"),
+        "shipment_date" => Ok("o.shipment_date"),
+        "user_withdraw_date" => Ok("o.user_withdraw_date"),
+        "created_at" => Ok("o.created_at"),
+        _ => Err(AppError::invalid_input(format!(
+            "Invalid date field: {field}"
+        ))),
+    }
+}
+
+/// Trimmed, non-empty values of a list condition.
+fn condition_values(values: &Option<Vec<String>>) -> Vec<String> {
+    values
+        .iter()
+        .flatten()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn push_in_condition(
+    expr: &str,
+    values: Vec<String>,
+    conditions: &mut Vec<String>,
+    params: &mut Vec<sea_orm::Value>,
+) {
+    if values.is_empty() {
+        return;
+    }
+    let placeholders = vec!["?"; values.len()].join(", ");
+    conditions.push(format!("{expr} IN ({placeholders})"));
+    params.extend(values.into_iter().map(sea_orm::Value::from));
+}
+
+fn push_number_range(
+    label: &str,
+    expr: &str,
+    range: Option<NumberRange>,
+    conditions: &mut Vec<String>,
+    params: &mut Vec<sea_orm::Value>,
+) -> AppResult<()> {
+    let Some(NumberRange { min, max }) = range else {
+        return Ok(());
+    };
+    if min.is_some_and(|v| !v.is_finite()) || max.is_some_and(|v| !v.is_finite()) {
+        return Err(AppError::invalid_input(format!("Invalid {label} range")));
+    }
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            return Err(AppError::invalid_input(format!(
+                "Invalid {label} range: minimum is greater than maximum"
+            )));
+        }
+    }
+    if let Some(min) = min {
+        conditions.push(format!("{expr} >= ?"));
+        params.push(min.into());
+    }
+    if let Some(max) = max {
+        conditions.push(format!("{expr} <= ?"));
+        params.push(max.into());
+    }
+    Ok(())
+}
+
+fn push_fee_flags(
+    suffix: &str,
+    flags: Option<OrderFeeFlags>,
+    conditions: &mut Vec<String>,
+    params: &mut Vec<sea_orm::Value>,
+) -> AppResult<()> {
+    let Some(flags) = flags else {
+        return Ok(());
+    };
+    if suffix == "by_shop" && flags.service_fee.is_some() {
+        return Err(AppError::invalid_input(
+            "The service fee cannot be paid by the shop",
+        ));
+    }
+    let fees = [
+        ("shipping_fee", flags.shipping_fee),
+        ("delivery_fee", flags.delivery_fee),
+        ("cargo_fee", flags.cargo_fee),
+        ("service_fee", flags.service_fee),
+    ];
+    for (fee, value) in fees {
+        if let Some(value) = value {
+            conditions.push(format!("COALESCE(o.{fee}_{suffix}, 0) = ?"));
+            params.push(i64::from(value).into());
+        }
+    }
+    Ok(())
+}
+
+/// Validates structured order conditions and appends them as parameterized SQL.
+/// Column names only ever come from the fixed lists above.
+fn push_order_conditions(
+    extra: &OrderConditions,
+    conditions: &mut Vec<String>,
+    params: &mut Vec<sea_orm::Value>,
+) -> AppResult<()> {
+    let statuses = condition_values(&extra.statuses)
+        .into_iter()
+        .map(|status| {
+            normalize_order_status(Some(status)).map(|normalized| normalized.unwrap_or_default())
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    push_in_condition("o.status", statuses, conditions, params);
+
+    let sources = condition_values(&extra.order_from)
+        .into_iter()
+        .map(|v| v.to_lowercase())
+        .collect();
+    push_in_condition(
+        "LOWER(TRIM(COALESCE(o.order_from, '')))",
+        sources,
+        conditions,
+        params,
+    );
+
+    let cities = condition_values(&extra.cities)
+        .into_iter()
+        .map(|v| v.to_lowercase())
+        .collect();
+    push_in_condition(
+        "LOWER(TRIM(COALESCE(c.city, '')))",
+        cities,
+        conditions,
+        params,
+    );
+
+    push_in_condition(
+        "o.customer_id",
+        condition_values(&extra.customer_ids),
+        conditions,
+        params,
+    );
+
+    for range in extra.date_ranges.iter().flatten() {
+        let column = order_date_column(range.field.trim())?;
+        let parse = |value: &Option<String>| -> AppResult<Option<NaiveDate>> {
+            match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                None => Ok(None),
+                Some(raw) => parse_flexible_date(Some(raw))
+                    .map(Some)
+                    .ok_or_else(|| AppError::invalid_input(format!("Invalid date: {raw}"))),
+            }
+        };
+        let from = parse(&range.from)?;
+        let to = parse(&range.to)?;
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(AppError::invalid_input(format!(
+                    "Invalid {} range: start is after end",
+                    range.field.trim()
+                )));
+            }
+        }
+        // Stored dates start with `YYYY-MM-DD` (see `iso_order_date`); the
+        // prefix keeps any time or offset from shifting the day.
+        if let Some(from) = from {
+            conditions.push(format!("DATE(SUBSTR({column}, 1, 10)) >= ?"));
+            params.push(from.format("%Y-%m-%d").to_string().into());
+        }
+        if let Some(to) = to {
+            conditions.push(format!("DATE(SUBSTR({column}, 1, 10)) <= ?"));
+            params.push(to.format("%Y-%m-%d").to_string().into());
+        }
+    }
 
-    let where_clause = if conditions.is_empty() {
-        String::new()
+    push_fee_flags("paid", extra.paid, conditions, params)?;
+    push_fee_flags("by_shop", extra.by_shop, conditions, params)?;
+    if let Some(exclude) = extra.exclude_cargo_fee {
+        conditions.push("COALESCE(o.exclude_cargo_fee, 0) = ?".to_string());
+        params.push(i64::from(exclude).into());
+    }
+
+    push_number_range(
+        "total price",
+        ORDER_ITEMS_TOTAL_PRICE,
+        extra.total_price,
+        conditions,
+        params,
+    )?;
+    push_number_range(
+        "total weight",
+        ORDER_ITEMS_TOTAL_WEIGHT,
+        extra.total_weight,
+        conditions,
+        params,
+    )?;
+
+    if let Some(has_items) = extra.has_items {
+        let exists =
+            "EXISTS (SELECT 1 FROM order_items WHERE order_id = o.id AND deleted_at IS NULL)";
+        conditions.push(if has_items {
+            exists.to_string()
+        } else {
+            format!("NOT {exists}")
+        });
+    }
+    Ok(())
+}
+
+pub async fn get_orders_paginated(
+    state: Arc<AppState>,
+    page: Option<i64>,
+    page_size: Option<i64>,
+    search_key: Option<String>,
+    search_term: Option<String>,
+    status_filter: Option<String>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    conditions: Option<OrderConditions>,
+) -> AppResult<PaginatedOrders> {
+    let db = state.db.lock().await.clone();
+
+    let requested_page_size = page_size.unwrap_or(DEFAULT_ORDERS_PAGE_SIZE);
+    let no_limit = requested_page_size <= 0;
+    let page_size = if no_limit {
+        DEFAULT_ORDERS_PAGE_SIZE
     } else {
-        format!("WHERE {}", conditions.join(" AND "))
+        requested_page_size.clamp(MIN_ORDERS_PAGE_SIZE, MAX_ORDERS_PAGE_SIZE)
     };
+    let page = if no_limit {
+        1
+    } else {
+        page.unwrap_or(1).max(1)
+    };
+    let offset = if no_limit { 0 } else { (page - 1) * page_size };
+
+    let ListQuery {
+        where_clause,
+        order_clause,
+        params,
+        ..
+    } = build_order_list_query(&OrderListFilter {
+        search_key,
+        search_term,
+        status_filter,
+        sort_by,
+        sort_order,
+        conditions,
+    })?;
 
     let count_sql = format!(
         "SELECT COUNT(*) as cnt FROM orders o LEFT JOIN customers c ON o.customer_id = c.id {}",
@@ -539,6 +913,31 @@ pub async fn get_orders_paginated(
     })
 }
 
+/// Loads one cursor page of the order list; see [`fetch_cursor_page`].
+pub async fn get_orders_page(
+    state: Arc<AppState>,
+    filter: OrderListFilter,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: bool,
+) -> AppResult<CursorPage<OrderWithCustomer>> {
+    let db = state.db.lock().await.clone();
+    let query = build_order_list_query(&filter)?;
+    fetch_cursor_page(
+        &db,
+        ListSource {
+            select: ORDER_WITH_CUSTOMER_SELECT,
+            group_by: ORDER_WITH_CUSTOMER_GROUP_BY,
+            count_from: "FROM orders o LEFT JOIN customers c ON o.customer_id = c.id",
+        },
+        query,
+        cursor,
+        limit,
+        include_total,
+    )
+    .await
+}
+
 pub async fn get_customer_orders(
     state: Arc<AppState>,
     customer_id: String,
@@ -618,6 +1017,8 @@ pub async fn update_order(
     let db = state.db.lock().await.clone();
     let normalized_status =
         normalize_order_status(status)?.unwrap_or_else(|| "pending".to_string());
+    let [order_date, arrived_date, shipment_date, user_withdraw_date] =
+        [order_date, arrived_date, shipment_date, user_withdraw_date].map(iso_order_date);
 
     let txn = db.begin().await?;
 
@@ -673,14 +1074,32 @@ pub async fn update_order(
     ))
     .await?;
 
+    // Items are re-inserted with new ids, so photos follow the edited item:
+    // by the id the form sent, else by an unclaimed old item with the same URL.
+    let mut unclaimed: Vec<&OrderItem> = old_items
+        .iter()
+        .filter(|old| !items.iter().any(|item| item.id.as_ref() == Some(&old.id)))
+        .collect();
+    let mut carried_over = HashSet::new();
     for item in items {
         let item_id = Uuid::new_v4().to_string();
+        let previous_id = match item
+            .id
+            .as_ref()
+            .filter(|item_id| old_items.iter().any(|old| &old.id == *item_id))
+        {
+            Some(previous_id) => Some(previous_id.clone()),
+            None => unclaimed
+                .iter()
+                .position(|old| old.product_url.is_some() && old.product_url == item.product_url)
+                .map(|index| unclaimed.remove(index).id.clone()),
+        };
         txn.execute(Statement::from_sql_and_values(
             DatabaseBackend::Sqlite,
             "INSERT INTO order_items (id, order_id, product_url, product_qty, price, product_weight) \
              VALUES (?, ?, ?, ?, ?, ?)",
             [
-                item_id.into(),
+                item_id.clone().into(),
                 id.clone().into(),
                 item.product_url.into(),
                 item.product_qty.into(),
@@ -689,10 +1108,23 @@ pub async fn update_order(
             ],
         ))
         .await?;
+        if let Some(previous_id) = previous_id {
+            move_item_photos(&txn, &previous_id, &item_id).await?;
+            carried_over.insert(previous_id);
+        }
     }
 
     txn.commit().await?;
 
+    let removed_items: Vec<String> = old_items
+        .iter()
+        .map(|old| old.id.clone())
+        .filter(|old_id| !carried_over.contains(old_id))
+        .collect();
+    if let Err(err) = delete_item_photos(&db, app, &removed_items).await {
+        tracing::warn!("Failed to remove photos of items dropped from order {id}: {err}");
+    }
+
     let pool = state.pool.lock().await;
 
     if let Ok(Some(order)) =
@@ -773,6 +1205,13 @@ pub async fn delete_order(state: Arc<AppState>, app: &AppHandle, id: String) ->
     ))
     .await?;
 
+    if let Err(err) = delete_entity_attachments(&db, app, AttachmentEntityType::Order, &id).await {
+        tracing::warn!("Failed to remove attachments of order {id}: {err}");
+    }
+    if let Err(err) = delete_order_photos(&db, app, &id).await {
+        tracing::warn!("Failed to remove photos of order {id}: {err}");
+    }
+
     let pool = state.pool.lock().await;
 
     if let Ok(Some(order)) =
@@ -1079,3 +1518,127 @@ pub async fn get_orders_for_export(state: Arc<AppState>) -> AppResult<Vec<OrderE
 
     Ok(rows)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DateRangeCondition;
+
+    fn date_range(field: &str, from: Option<&str>, to: Option<&str>) -> OrderConditions {
+        OrderConditions {
+            date_ranges: Some(vec![DateRangeCondition {
+                field: field.to_string(),
+                from: from.map(str::to_string),
+                to: to.map(str::to_string),
+            }]),
+            ..OrderConditions::default()
+        }
+    }
+
+    fn compile(extra: &OrderConditions) -> AppResult<(Vec<String>, Vec<sea_orm::Value>)> {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        push_order_conditions(extra, &mut conditions, &mut params)?;
+        Ok((conditions, params))
+    }
+
+    fn text(value: &str) -> sea_orm::Value {
+        value.to_string().into()
+    }
+
+    #[test]
+    fn date_ranges_compile_for_each_field_and_bound() {
+        for field in [
+            "order_date",
+            "arrived_date",
+            "shipment_date",
+            "user_withdraw_date",
+            "created_at",
+        ] {
+            let column = format!("DATE(SUBSTR(o.{field}, 1, 10))");
+            let (conditions, params) =
+                compile(&date_range(field, Some("15/03/2025"), Some("2025-03-31"))).unwrap();
+            assert_eq!(
+                conditions,
+                [format!("{column} >= ?"), format!("{column} <= ?")]
+            );
+            assert_eq!(params, [text("2025-03-15"), text("2025-03-31")]);
+
+            let (conditions, params) =
+                compile(&date_range(field, None, Some("31.03.2025"))).unwrap();
+            assert_eq!(conditions, [format!("{column} <= ?")]);
+            assert_eq!(params, [text("2025-03-31")]);
+        }
+
+        let (conditions, _) = compile(&date_range("order_date", Some(" "), None)).unwrap();
+        assert!(conditions.is_empty());
+    }
+
+    #[test]
+    fn invalid_date_ranges_are_rejected() {
+        for extra in [
+            date_range("deleted_at", Some("2025-03-01"), None),
+            date_range("order_date", Some("someday"), None),
+            date_range("order_date", Some("2025-03-31"), Some("2025-03-01")),
+        ] {
+            let err = compile(&extra).unwrap_err();
+            assert!(matches!(err, AppError::InvalidInput(_)), "{err}");
+        }
+    }
+
+    #[test]
+    fn iso_order_date_rewrites_other_layouts() {
+        let iso = |value: &str| iso_order_date(Some(value.to_string()));
+        assert_eq!(iso("15/03/2025").as_deref(), Some("2025-03-15"));
+        assert_eq!(iso("15-03-2025").as_deref(), Some("2025-03-15"));
+        assert_eq!(iso("15.03.2025 10:30").as_deref(), Some("2025-03-15"));
+        assert_eq!(iso("2025-3-5").as_deref(), Some("2025-03-05"));
+        assert_eq!(
+            iso(" 2025-03-15 10:00:00").as_deref(),
+            Some("2025-03-15 10:00:00")
+        );
+        assert_eq!(iso("someday").as_deref(), Some("someday"));
+        assert_eq!(iso_order_date(None), None);
+    }
+
+    #[tokio::test]
+    async fn non_iso_stored_dates_match_date_filters_once_normalized() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        db.execute_unprepared(
+            "INSERT INTO orders (id, order_date) VALUES \
+             ('slash', '15/03/2025'), \
+             ('offset', '2025-03-31T23:30:00+06:30'), \
+             ('april', '01.04.2025'), \
+             ('unknown', 'someday')",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(normalize_order_dates(&db).await.unwrap(), 2);
+        assert_eq!(normalize_order_dates(&db).await.unwrap(), 0);
+
+        let (conditions, params) = compile(&date_range(
+            "order_date",
+            Some("2025-03-01"),
+            Some("2025-03-31"),
+        ))
+        .unwrap();
+        let ids: Vec<String> = db
+            .query_all(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                format!(
+                    "SELECT o.id FROM orders o LEFT JOIN customers c ON c.id = o.customer_id \
+                     WHERE {} ORDER BY o.id",
+                    conditions.join(" AND ")
+                ),
+                params,
+            ))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.try_get("", "id").unwrap())
+            .collect();
+        assert_eq!(ids, ["offset", "slash"]);
+    }
+}
@@ -0,0 +1,426 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::db::{ListQuery, ListSort};
+use crate::error::{AppError, AppResult};
+
+const DEFAULT_CURSOR_PAGE_SIZE: i64 = 20;
+const MAX_CURSOR_PAGE_SIZE: i64 = 100;
+
+/// One page of a keyset-paginated list. Cursors are opaque; pass one back as
+/// `cursor` to load the neighbouring page. `total` is only counted on request.
+#[derive(Debug, Serialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+    pub total: Option<i64>,
+}
+
+/// Position of a row in its sort, plus which side of it to load.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    #[serde(rename = "s")]
+    sort: String,
+    #[serde(rename = "v")]
+    value: JsonValue,
+    id: String,
+    #[serde(rename = "b", default)]
+    before: bool,
+}
+
+impl Cursor {
+    fn encode(&self) -> AppResult<String> {
+        Ok(URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?))
+    }
+
+    fn decode(raw: &str, sort: &ListSort) -> AppResult<Self> {
+        let cursor: Cursor = URL_SAFE_NO_PAD
+            .decode(raw.trim())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| AppError::invalid_input("Invalid page cursor"))?;
+        if cursor.sort != sort.key {
+            return Err(AppError::invalid_input(
+                "Page cursor belongs to a different sort; start from the first page",
+            ));
+        }
+        let value_matches = if sort.numeric {
+            cursor.value.is_number()
+        } else {
+            cursor.value.is_string()
+        };
+        if !value_matches {
+            return Err(AppError::invalid_input("Invalid page cursor"));
+        }
+        Ok(cursor)
+    }
+
+    fn value_param(&self) -> sea_orm::Value {
+        match &self.value {
+            JsonValue::Number(n) => n.as_f64().unwrap_or_default().into(),
+            JsonValue::String(s) => s.clone().into(),
+            _ => sea_orm::Value::String(None),
+        }
+    }
+}
+
+/// Where a list reads its rows from; `select` must start with `SELECT`.
+pub(crate) struct ListSource<'a> {
+    pub select: &'a str,
+    pub group_by: &'a str,
+    /// `FROM … JOIN …` part used for the optional total count.
+    pub count_from: &'a str,
+}
+
+fn sort_position(row: &sea_orm::QueryResult, sort: &ListSort) -> AppResult<(JsonValue, String)> {
+    let value = if sort.numeric {
+        JsonValue::from(row.try_get::<f64>("", "cursor_sort_value")?)
+    } else {
+        JsonValue::from(row.try_get::<String>("", "cursor_sort_value")?)
+    };
+    Ok((value, row.try_get::<String>("", "cursor_id")?))
+}
+
+/// Reads up to `limit` rows past `cursor` in display order, and whether more
+/// rows lie beyond them in the scan direction.
+async fn seek(
+    db: &DatabaseConnection,
+    source: &ListSource<'_>,
+    query: &ListQuery,
+    sort: &ListSort,
+    cursor: Option<&Cursor>,
+    limit: i64,
+) -> AppResult<(Vec<sea_orm::QueryResult>, bool)> {
+    let backwards = cursor.is_some_and(|c| c.before);
+    // Walking backwards scans in the opposite order, then flips the page.
+    let scan_descending = sort.descending != backwards;
+    let direction = if scan_descending { "DESC" } else { "ASC" };
+
+    let mut where_clause = query.where_clause.clone();
+    let mut params = query.params.clone();
+    if let Some(cursor) = cursor {
+        let op = if scan_descending { "<" } else { ">" };
+        let seek = format!("({}, {}) {op} (?, ?)", sort.expr, sort.tiebreaker);
+        where_clause = if where_clause.is_empty() {
+            format!("WHERE {seek}")
+        } else {
+            format!("{where_clause} AND {seek}")
+        };
+        params.push(cursor.value_param());
+        params.push(cursor.id.clone().into());
+    }
+
+    let columns = source
+        .select
+        .trim_start()
+        .strip_prefix("SELECT")
+        .ok_or_else(|| AppError::internal("List source must start with SELECT"))?;
+    let sql = format!(
+        "SELECT {expr} AS cursor_sort_value, {tb} AS cursor_id, {columns} {where_clause} {group_by} \
+         ORDER BY {expr} {direction}, {tb} {direction} LIMIT ?",
+        expr = sort.expr,
+        tb = sort.tiebreaker,
+        group_by = source.group_by,
+    );
+    params.push((limit + 1).into());
+
+    let mut rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            sql,
+            params,
+        ))
+        .await?;
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    if backwards {
+        rows.reverse();
+    }
+    Ok((rows, has_more))
+}
+
+/// Loads the page after (or, for a `prev_cursor`, before) `cursor` by seeking
+/// on `(sort value, id)` instead of skipping rows with OFFSET, so pages stay
+/// put when sync inserts records concurrently.
+pub(crate) async fn fetch_cursor_page<R: FromQueryResult>(
+    db: &DatabaseConnection,
+    source: ListSource<'_>,
+    query: ListQuery,
+    cursor: Option<String>,
+    limit: Option<i64>,
+    include_total: bool,
+) -> AppResult<CursorPage<R>> {
+    let sort = query
+        .sort
+        .clone()
+        .ok_or_else(|| AppError::internal("This list does not support cursor pagination"))?;
+    let mut cursor = cursor
+        .filter(|c| !c.trim().is_empty())
+        .map(|raw| Cursor::decode(&raw, &sort))
+        .transpose()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CURSOR_PAGE_SIZE)
+        .clamp(1, MAX_CURSOR_PAGE_SIZE);
+
+    let (mut rows, mut has_more) = seek(db, &source, &query, &sort, cursor.as_ref(), limit).await?;
+    let mut backwards = cursor.as_ref().is_some_and(|c| c.before);
+    if backwards && !has_more && (rows.len() as i64) < limit {
+        // Walked back past the start: serve a full first page instead of a stub.
+        cursor = None;
+        backwards = false;
+        (rows, has_more) = seek(db, &source, &query, &sort, None, limit).await?;
+    }
+
+    let edge = |row: Option<&sea_orm::QueryResult>, before: bool| -> AppResult<Option<String>> {
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let (value, id) = sort_position(row, &sort)?;
+        Cursor {
+            sort: sort.key.clone(),
+            value,
+            id,
+            before,
+        }
+        .encode()
+        .map(Some)
+    };
+    let (has_next, has_prev) = if backwards {
+        (true, has_more)
+    } else {
+        (has_more, cursor.is_some())
+    };
+    let next_cursor = if has_next {
+        edge(rows.last(), false)?
+    } else {
+        None
+    };
+    let prev_cursor = if has_prev {
+        edge(rows.first(), true)?
+    } else {
+        None
+    };
+
+    let items = rows
+        .iter()
+        .map(|row| R::from_query_result(row, ""))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let total = if include_total {
+        let count = db
+            .query_one(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                format!(
+                    "SELECT COUNT(*) AS cnt {} {}",
+                    source.count_from, query.where_clause
+                ),
+                query.params,
+            ))
+            .await?;
+        Some(match count {
+            Some(row) => row.try_get::<i64>("", "cnt")?,
+            None => 0,
+        })
+    } else {
+        None
+    };
+
+    Ok(CursorPage {
+        items,
+        next_cursor,
+        prev_cursor,
+        total,
+    })
+}
+
+/// Walks every row of a list in keyset order, one batch at a time. Exports use
+/// it so rows inserted or deleted by sync mid-export neither shift later
+/// batches nor get written twice, as they would with OFFSET.
+pub(crate) struct KeysetBatches<'a> {
+    db: &'a DatabaseConnection,
+    source: ListSource<'a>,
+    query: ListQuery,
+    sort: ListSort,
+    batch_size: i64,
+    cursor: Option<Cursor>,
+    done: bool,
+}
+
+impl<'a> KeysetBatches<'a> {
+    pub(crate) fn new(
+        db: &'a DatabaseConnection,
+        source: ListSource<'a>,
+        query: ListQuery,
+        batch_size: i64,
+    ) -> AppResult<Self> {
+        let sort = query
+            .sort
+            .clone()
+            .ok_or_else(|| AppError::internal("This list does not support cursor pagination"))?;
+        Ok(Self {
+            db,
+            source,
+            query,
+            sort,
+            batch_size: batch_size.max(1),
+            cursor: None,
+            done: false,
+        })
+    }
+
+    /// Returns the next batch, or `None` once every row has been read.
+    pub(crate) async fn next_batch<R: FromQueryResult>(&mut self) -> AppResult<Option<Vec<R>>> {
+        if self.done {
+            return Ok(None);
+        }
+        let (rows, has_more) = seek(
+            self.db,
+            &self.source,
+            &self.query,
+            &self.sort,
+            self.cursor.as_ref(),
+            self.batch_size,
+        )
+        .await?;
+        self.done = !has_more;
+        if let Some(last) = rows.last() {
+            let (value, id) = sort_position(last, &self.sort)?;
+            self.cursor = Some(Cursor {
+                sort: self.sort.key.clone(),
+                value,
+                id,
+                before: false,
+            });
+        }
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let items = rows
+            .iter()
+            .map(|row| R::from_query_result(row, ""))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Customer, CustomerListFilter};
+    use crate::services::customer::{build_customer_list_query, CUSTOMER_SELECT};
+    use crate::state::AppState;
+
+    const CUSTOMERS: ListSource<'static> = ListSource {
+        select: CUSTOMER_SELECT,
+        group_by: "",
+        count_from: "FROM customers",
+    };
+
+    fn cursor(sort: &ListSort, value: JsonValue) -> Cursor {
+        Cursor {
+            sort: sort.key.clone(),
+            value,
+            id: "c1".into(),
+            before: true,
+        }
+    }
+
+    fn decode_error(raw: &str, sort: &ListSort) -> String {
+        Cursor::decode(raw, sort).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let sort = ListSort::text("name", false, "id");
+        let raw = cursor(&sort, JsonValue::from("Aung")).encode().unwrap();
+        let decoded = Cursor::decode(&format!(" {raw}\n"), &sort).unwrap();
+        assert_eq!(decoded.value, JsonValue::from("Aung"));
+        assert_eq!(decoded.id, "c1");
+        assert!(decoded.before);
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        let sort = ListSort::text("name", false, "id");
+        assert_eq!(
+            decode_error("not a cursor!", &sort),
+            "Invalid input: Invalid page cursor"
+        );
+        let not_json = URL_SAFE_NO_PAD.encode(b"{\"s\":");
+        assert_eq!(
+            decode_error(&not_json, &sort),
+            "Invalid input: Invalid page cursor"
+        );
+    }
+
+    #[test]
+    fn cursor_rejects_another_sort() {
+        let by_name = ListSort::text("name", false, "id");
+        let by_name_desc = ListSort::text("name", true, "id");
+        let raw = cursor(&by_name, JsonValue::from("Aung")).encode().unwrap();
+        assert!(decode_error(&raw, &by_name_desc).contains("different sort"));
+    }
+
+    #[test]
+    fn cursor_value_must_match_the_sort_type() {
+        let by_amount = ListSort::numeric("amount", true, "id");
+        let raw = cursor(&by_amount, JsonValue::from("12.5"))
+            .encode()
+            .unwrap();
+        assert_eq!(
+            decode_error(&raw, &by_amount),
+            "Invalid input: Invalid page cursor"
+        );
+        let raw = cursor(&by_amount, JsonValue::from(12.5)).encode().unwrap();
+        assert!(Cursor::decode(&raw, &by_amount).is_ok());
+    }
+
+    #[test]
+    fn cursor_direction_defaults_to_forward() {
+        let sort = ListSort::text("name", false, "id");
+        let raw = URL_SAFE_NO_PAD.encode(br#"{"s":"name asc","v":"Aung","id":"c1"}"#);
+        assert!(!Cursor::decode(&raw, &sort).unwrap().before);
+    }
+
+    #[tokio::test]
+    async fn keyset_batches_ignore_rows_inserted_mid_walk() {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        db.execute_unprepared(
+            "INSERT INTO customers (id, customer_id, name) VALUES \
+               ('c1', 'SSC-1', 'A'), ('c2', 'SSC-2', 'B'), ('c3', 'SSC-3', 'C'), \
+               ('c4', 'SSC-4', 'D'), ('c5', 'SSC-5', 'E');",
+        )
+        .await
+        .unwrap();
+        let filter = CustomerListFilter {
+            sort_order: Some("asc".into()),
+            ..Default::default()
+        };
+        let query = build_customer_list_query(&filter).unwrap();
+
+        let mut batches = KeysetBatches::new(&db, CUSTOMERS, query, 2).unwrap();
+        let mut seen = Vec::new();
+        let mut batch_sizes = Vec::new();
+        while let Some(batch) = batches.next_batch::<Customer>().await.unwrap() {
+            if seen.is_empty() {
+                // A row sorting before the cursor would shift an OFFSET walk
+                // and repeat a row.
+                db.execute_unprepared(
+                    "INSERT INTO customers (id, customer_id, name) VALUES ('c0', 'SSC-0', 'Z')",
+                )
+                .await
+                .unwrap();
+            }
+            batch_sizes.push(batch.len());
+            seen.extend(batch.into_iter().map(|customer| customer.id));
+        }
+
+        assert_eq!(seen, ["c1", "c2", "c3", "c4", "c5"]);
+        assert_eq!(batch_sizes, [2, 2, 1]);
+    }
+}
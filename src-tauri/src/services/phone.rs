@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+use phonenumber::{country, Mode};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use tauri::AppHandle;
+use tracing::warn;
+
+use crate::error::{AppError, AppResult};
+use crate::services::settings::get_app_settings;
+
+pub const DEFAULT_PHONE_COUNTRY: &str = "MM";
+
+fn country_id(default_country: &str) -> Option<country::Id> {
+    country::Id::from_str(&default_country.trim().to_uppercase()).ok()
+}
+
+/// Reads the default phone country from settings, falling back to Myanmar.
+pub(crate) fn default_phone_country(app: &AppHandle) -> String {
+    get_app_settings(app.clone())
+        .map(|settings| settings.default_phone_country)
+        .ok()
+        .filter(|country| country_id(country).is_some())
+        .unwrap_or_else(|| DEFAULT_PHONE_COUNTRY.to_string())
+}
+
+/// Parses `raw` in the default country and returns its E.164 form, e.g. `+959123456789`.
+pub fn to_e164(raw: &str, default_country: &str) -> AppResult<String> {
+    let trimmed = raw.trim();
+    // The parser trips over separators right after the country code ("+959-…").
+    let compact: String = trimmed
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+    let number = phonenumber::parse(country_id(default_country), &compact)
+        .ok()
+        .filter(phonenumber::is_valid)
+        .ok_or_else(|| {
+            AppError::invalid_input(format!(
+                "Invalid phone number: {trimmed} (use a local number or include the country code)"
+            ))
+        })?;
+    Ok(number.format().mode(Mode::E164).to_string())
+}
+
+/// Validates an optional phone field.
+///
+/// Returns the trimmed display text as entered together with its E.164 form,
+/// or `(None, None)` when blank.
+pub fn normalize_phone(
+    phone: Option<String>,
+    default_country: &str,
+) -> AppResult<(Option<String>, Option<String>)> {
+    match phone
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+    {
+        Some(display) => {
+            let e164 = to_e164(&display, default_country)?;
+            Ok((Some(display), Some(e164)))
+        }
+        None => Ok((None, None)),
+    }
+}
+
+/// E.164 form when the number parses, without rejecting anything.
+///
+/// Used for records that arrive with an id (restores, imports of existing rows),
+/// which keep whatever phone text they carry.
+pub fn try_e164(phone: Option<&str>, default_country: &str) -> Option<String> {
+    phone
+        .filter(|p| !p.trim().is_empty())
+        .and_then(|p| to_e164(p, default_country).ok())
+}
+
+/// Digits of a phone search term without trunk zeros, matched against `phone_e164`.
+///
+/// "09 123-456", "+959123456" and "9123456" all reduce to a substring of
+/// `+959123456…`; terms without digits return `None`.
+pub(crate) fn phone_search_digits(term: &str) -> Option<String> {
+    let digits: String = term.chars().filter(char::is_ascii_digit).collect();
+    let digits = digits.trim_start_matches('0');
+    (!digits.is_empty()).then(|| digits.to_string())
+}
+
+#[derive(Debug, FromQueryResult)]
+struct PhoneRow {
+    id: String,
+    phone: String,
+}
+
+/// Fills `phone_e164` for customers saved before it existed or whose number
+/// has not been parsed yet. Unparseable numbers are left as they are.
+pub async fn backfill_phone_e164(db: &DatabaseConnection, default_country: &str) -> AppResult<()> {
+    let rows = PhoneRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "SELECT id, phone FROM customers \
+         WHERE phone_e164 IS NULL AND phone IS NOT NULL AND TRIM(phone) <> ''",
+    ))
+    .all(db)
+    .await?;
+
+    let mut skipped = 0;
+    for row in rows {
+        let Some(e164) = try_e164(Some(&row.phone), default_country) else {
+            skipped += 1;
+            continue;
+        };
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE customers SET phone_e164 = ? WHERE id = ?",
+            [e164.into(), row.id.into()],
+        ))
+        .await?;
+    }
+    if skipped > 0 {
+        warn!(skipped, "customer phone numbers could not be normalized");
+    }
+    Ok(())
+}
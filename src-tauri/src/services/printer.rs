@@ -0,0 +1,421 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tracing::instrument;
+
+use crate::error::{AppError, AppResult};
+use crate::services::settings::get_app_settings;
+
+/// A printer destination reported by `lpstat`.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct PrinterInfo {
+    pub name: String,
+    /// One of `idle`, `printing`, `disabled` or `unknown`.
+    pub status: String,
+    pub is_default: bool,
+}
+
+/// A submitted print job. `job_id` is `None` when the destination does not
+/// report one (direct device/network printing, or Windows).
+#[derive(Debug, Serialize, Clone)]
+pub struct PrintJob {
+    pub job_id: Option<String>,
+    pub printer: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PrintJobStatus {
+    pub job_id: String,
+    /// One of `queued`, `printing`, `completed` or `unknown`.
+    pub state: String,
+}
+
+fn sanitize_printer_name(printer_name: Option<String>) -> Option<String> {
+    printer_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn command_failure_details(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !stderr.is_empty() {
+        stderr
+    } else {
+        stdout
+    }
+}
+
+/// Parses `lpstat -p -d` output into printer entries.
+pub fn parse_lpstat_printers(output: &str) -> Vec<PrinterInfo> {
+    let default_printer = output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("system default destination:")
+            .map(|name| name.trim().to_string())
+    });
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("printer ")?;
+            let (name, description) = rest.split_once(' ').unwrap_or((rest, ""));
+            let status = if description.starts_with("is idle") {
+                "idle"
+            } else if description.starts_with("now printing") {
+                "printing"
+            } else if description.starts_with("disabled") {
+                "disabled"
+            } else {
+                "unknown"
+            };
+
+            Some(PrinterInfo {
+                name: name.to_string(),
+                status: status.to_string(),
+                is_default: default_printer.as_deref() == Some(name),
+            })
+        })
+        .collect()
+}
+
+/// Extracts the job id from `lp` output such as `request id is Office-42 (1 file(s))`.
+pub fn parse_lp_job_id(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("request id is ")
+            .and_then(|rest| rest.split_whitespace().next())
+            .map(|id| id.to_string())
+    })
+}
+
+/// Runs a printing call on the blocking thread pool. `lp` and `lpstat` wait on
+/// the CUPS scheduler and must not stall the async runtime.
+pub(crate) async fn run_blocking<T, F>(f: F) -> AppResult<T>
+where
+    F: FnOnce() -> AppResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run_lpstat(args: &[&str]) -> AppResult<String> {
+    let output = Command::new("lpstat")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| {
+            AppError::print_failed(format!("Unable to run lpstat. Is CUPS installed? ({})", e))
+        })?;
+
+    if !output.status.success() {
+        let details = command_failure_details(&output);
+        // lpstat exits non-zero when no printers are configured at all.
+        if details.contains("No destinations added") {
+            return Ok(String::new());
+        }
+        return Err(AppError::print_failed(if details.is_empty() {
+            "lpstat failed".to_string()
+        } else {
+            details
+        }));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn run_lpstat(_args: &[&str]) -> AppResult<String> {
+    Err(AppError::print_failed(
+        "Printer discovery is only available on macOS and Linux.",
+    ))
+}
+
+/// Lists printers known to CUPS, flagging the system default.
+#[instrument]
+pub fn list_printers() -> AppResult<Vec<PrinterInfo>> {
+    let output = run_lpstat(&["-p", "-d"])?;
+    Ok(parse_lpstat_printers(&output))
+}
+
+/// Resolves a printer by name, or the system default when no name is given.
+#[instrument]
+pub fn validate_printer(printer_name: Option<String>) -> AppResult<PrinterInfo> {
+    let printers = list_printers()?;
+    match sanitize_printer_name(printer_name) {
+        Some(name) => printers
+            .into_iter()
+            .find(|printer| printer.name == name)
+            .ok_or(AppError::PrinterNotFound(name)),
+        None => printers
+            .into_iter()
+            .find(|printer| printer.is_default)
+            .ok_or_else(|| {
+                AppError::PrinterNotFound(
+                    "No printer configured and no system default printer is set".to_string(),
+                )
+            }),
+    }
+}
+
+/// Resolves `printer_name`, falling back to the `invoice_printer_name` setting
+/// and then to the system default.
+#[instrument(skip(app))]
+pub fn validate_configured_printer(
+    app: AppHandle,
+    printer_name: Option<String>,
+) -> AppResult<PrinterInfo> {
+    let printer_name = match sanitize_printer_name(printer_name) {
+        Some(name) => Some(name),
+        None => sanitize_printer_name(Some(get_app_settings(app)?.invoice_printer_name)),
+    };
+    validate_printer(printer_name)
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run_lp(printer: Option<&str>, raw: bool, input: LpInput<'_>) -> AppResult<PrintJob> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut command = Command::new("lp");
+    command.env("LC_ALL", "C");
+    if let Some(printer) = printer {
+        command.arg("-d").arg(printer);
+    }
+    if raw {
+        command.arg("-o").arg("raw");
+    }
+
+    let output = match input {
+        LpInput::File(path) => command.arg(path).output().map_err(lp_spawn_failed)?,
+        LpInput::Bytes(bytes) => {
+            let mut child = command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(lp_spawn_failed)?;
+            child
+                .stdin
+                .take()
+                .ok_or_else(|| AppError::print_failed("Failed to open lp input"))?
+                .write_all(bytes)
+                .map_err(|e| AppError::print_failed(format!("Failed to send data to lp: {e}")))?;
+            child
+                .wait_with_output()
+                .map_err(|e| AppError::print_failed(format!("Failed to wait for lp: {e}")))?
+        }
+    };
+
+    if !output.status.success() {
+        let details = command_failure_details(&output);
+        if details.contains("does not exist") {
+            return Err(AppError::PrinterNotFound(
+                printer.unwrap_or("default").to_string(),
+            ));
+        }
+        return Err(AppError::print_failed(if details.is_empty() {
+            "Failed to submit print job".to_string()
+        } else {
+            details
+        }));
+    }
+
+    Ok(PrintJob {
+        job_id: parse_lp_job_id(&String::from_utf8_lossy(&output.stdout)),
+        printer: printer.map(|p| p.to_string()),
+    })
+}
+
+/// `lp` could not be started, usually because CUPS is not installed.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn lp_spawn_failed(e: std::io::Error) -> AppError {
+    AppError::print_failed(format!("Unable to run lp. Is CUPS installed? ({})", e))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn run_lp(_printer: Option<&str>, _raw: bool, _input: LpInput<'_>) -> AppResult<PrintJob> {
+    Err(AppError::print_failed(
+        "CUPS printing is only available on macOS and Linux.",
+    ))
+}
+
+enum LpInput<'a> {
+    File(&'a Path),
+    Bytes(&'a [u8]),
+}
+
+/// Submits a file to CUPS and returns the job id reported by `lp`.
+pub fn submit_print_file(printer: Option<&str>, path: &Path) -> AppResult<PrintJob> {
+    run_lp(printer, false, LpInput::File(path))
+}
+
+/// Submits bytes to CUPS through stdin. With `raw`, CUPS passes them to the
+/// printer untouched, which ESC/POS receipts require.
+pub fn submit_print_bytes(printer: Option<&str>, bytes: &[u8], raw: bool) -> AppResult<PrintJob> {
+    run_lp(printer, raw, LpInput::Bytes(bytes))
+}
+
+fn job_listed(output: &str, job_id: &str) -> bool {
+    output
+        .lines()
+        .any(|line| line.split_whitespace().next() == Some(job_id))
+}
+
+/// Reports whether a CUPS job is queued, printing or completed.
+#[instrument]
+pub fn get_print_job_status(job_id: String) -> AppResult<PrintJobStatus> {
+    let job_id = job_id.trim().to_string();
+    if job_id.is_empty() {
+        return Err(AppError::invalid_input("Job id is required"));
+    }
+
+    let printing_marker = format!("now printing {}.", job_id);
+    let state = if run_lpstat(&["-p"])?.contains(&printing_marker) {
+        "printing"
+    } else if job_listed(&run_lpstat(&["-o"])?, &job_id) {
+        "queued"
+    } else if job_listed(&run_lpstat(&["-W", "completed", "-o"])?, &job_id) {
+        "completed"
+    } else {
+        "unknown"
+    };
+
+    Ok(PrintJobStatus {
+        job_id,
+        state: state.to_string(),
+    })
+}
+
+/// Prints a short plain-text test page on the given or configured printer.
+#[instrument(skip(app))]
+pub fn test_print(app: AppHandle, printer_name: Option<String>) -> AppResult<PrintJob> {
+    let printer = validate_configured_printer(app, printer_name)?;
+
+    let page = format!(
+        "Printer test page\n\nPrinter: {}\nPrinted at: {}\n\nIf you can read this, printing works.\n",
+        printer.name,
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    submit_print_bytes(Some(&printer.name), page.as_bytes(), false)
+}
+
+/// Prints a rendered invoice image without showing a print dialog.
+#[instrument(skip(app, bytes), fields(len = bytes.len()))]
+pub fn print_invoice_image(
+    app: &AppHandle,
+    bytes: Vec<u8>,
+    printer_name: Option<String>,
+) -> AppResult<PrintJob> {
+    if bytes.is_empty() {
+        return Err(AppError::invalid_input("Invoice image is empty"));
+    }
+
+    let temp_dir = app
+        .path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir());
+    fs::create_dir_all(&temp_dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .as_millis();
+    let temp_path = temp_dir.join(format!("invoice_{timestamp}.png"));
+    fs::write(&temp_path, bytes)?;
+
+    let sanitized_printer = sanitize_printer_name(printer_name);
+    let print_result = print_image_file(sanitized_printer.as_deref(), &temp_path);
+
+    let _ = fs::remove_file(&temp_path);
+    print_result
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn print_image_file(printer: Option<&str>, path: &Path) -> AppResult<PrintJob> {
+    submit_print_file(printer, path)
+}
+
+#[cfg(target_os = "windows")]
+fn print_image_file(printer: Option<&str>, path: &Path) -> AppResult<PrintJob> {
+    let printer = printer.ok_or_else(|| {
+        AppError::invalid_input("Please set a printer name in Settings before using direct print.")
+    })?;
+
+    let output = Command::new("mspaint")
+        .arg("/pt")
+        .arg(path)
+        .arg(printer)
+        .output()?;
+
+    if !output.status.success() {
+        let details = command_failure_details(&output);
+        return Err(AppError::print_failed(if details.is_empty() {
+            "Failed to print invoice".to_string()
+        } else {
+            details
+        }));
+    }
+
+    Ok(PrintJob {
+        job_id: None,
+        printer: Some(printer.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lpstat_printers_and_default() {
+        let output = "printer Office is idle.  enabled since Mon 01 Jan 2026\n\
+                      printer Receipt now printing Receipt-7.  enabled since Mon 01 Jan 2026\n\
+                      printer Old disabled since Mon 01 Jan 2026 -\n\
+                      system default destination: Receipt\n";
+        let printers = parse_lpstat_printers(output);
+        let summary: Vec<_> = printers
+            .iter()
+            .map(|p| (p.name.as_str(), p.status.as_str(), p.is_default))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Office", "idle", false),
+                ("Receipt", "printing", true),
+                ("Old", "disabled", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_lp_job_id() {
+        assert_eq!(
+            parse_lp_job_id("request id is Office-42 (1 file(s))\n").as_deref(),
+            Some("Office-42")
+        );
+        assert_eq!(parse_lp_job_id("lp: Error - no default destination"), None);
+    }
+
+    #[test]
+    fn job_listed_matches_whole_job_id() {
+        let output = "Office-42   admin   1024   Mon 01 Jan 2026\n";
+        assert!(job_listed(output, "Office-42"));
+        assert!(!job_listed(output, "Office-4"));
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    #[test]
+    fn lp_spawn_errors_are_print_failures() {
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let err = lp_spawn_failed(missing);
+        assert!(matches!(err, AppError::PrintFailed(_)));
+        assert!(err.to_string().contains("Is CUPS installed?"), "{err}");
+    }
+}
@@ -0,0 +1,671 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::services::attachment::{generate_thumbnail, remove_file_if_present};
+use crate::services::storage::Storage;
+use crate::state::AppState;
+
+const MAX_PHOTO_BYTES: usize = 20 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const S3_PROVIDER_NAME: &str = "thai-htay-product-photos";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhotoSource {
+    File,
+    Clipboard,
+    Url,
+}
+
+impl PhotoSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            PhotoSource::File => "file",
+            PhotoSource::Clipboard => "clipboard",
+            PhotoSource::Url => "url",
+        }
+    }
+
+    fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "file" => Ok(PhotoSource::File),
+            "clipboard" => Ok(PhotoSource::Clipboard),
+            "url" => Ok(PhotoSource::Url),
+            _ => Err(AppError::internal(format!("Unknown photo source: {value}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderItemPhoto {
+    pub id: String,
+    pub order_item_id: String,
+    /// SHA-256 of the image bytes; identical images share one file.
+    pub content_hash: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub source: PhotoSource,
+    /// Where the image was downloaded from, for `url` photos.
+    pub source_url: Option<String>,
+    /// Image in app data; load it in the webview with `convertFileSrc`.
+    pub local_path: String,
+    pub thumbnail_path: Option<String>,
+    pub cloud_url: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct PhotoRow {
+    id: String,
+    order_item_id: String,
+    content_hash: String,
+    file_ext: String,
+    content_type: String,
+    size_bytes: i64,
+    width: Option<i64>,
+    height: Option<i64>,
+    source: String,
+    source_url: Option<String>,
+    cloud_key: Option<String>,
+    cloud_url: Option<String>,
+    created_at: Option<String>,
+}
+
+const PHOTO_SELECT: &str = "SELECT id, order_item_id, content_hash, file_ext, content_type, \
+     size_bytes, width, height, source, source_url, cloud_key, cloud_url, created_at \
+     FROM order_item_photos";
+
+/// Where photo files live. Paths are derived from the hash rather than stored,
+/// so they survive the app data directory moving.
+struct PhotoStore {
+    dir: PathBuf,
+}
+
+impl PhotoStore {
+    fn new(app: &AppHandle) -> AppResult<Self> {
+        Ok(PhotoStore {
+            dir: app.path().app_data_dir()?.join("product-photos"),
+        })
+    }
+
+    fn image_path(&self, hash: &str, ext: &str) -> PathBuf {
+        self.dir.join(&hash[..2]).join(format!("{hash}.{ext}"))
+    }
+
+    fn thumbnail_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("thumbnails").join(format!("{hash}.jpg"))
+    }
+
+    fn to_photo(&self, row: PhotoRow) -> AppResult<OrderItemPhoto> {
+        let thumbnail_path = self.thumbnail_path(&row.content_hash);
+        Ok(OrderItemPhoto {
+            local_path: self
+                .image_path(&row.content_hash, &row.file_ext)
+                .to_string_lossy()
+                .to_string(),
+            thumbnail_path: thumbnail_path
+                .exists()
+                .then(|| thumbnail_path.to_string_lossy().to_string()),
+            id: row.id,
+            order_item_id: row.order_item_id,
+            content_hash: row.content_hash,
+            content_type: row.content_type,
+            size_bytes: row.size_bytes,
+            width: row.width,
+            height: row.height,
+            source: PhotoSource::parse(&row.source)?,
+            source_url: row.source_url,
+            cloud_url: row.cloud_url,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// Extension and content type of a supported image, sniffed from its bytes.
+fn image_kind(bytes: &[u8]) -> AppResult<(&'static str, &'static str)> {
+    match image::guess_format(bytes) {
+        Ok(image::ImageFormat::Png) => Ok(("png", "image/png")),
+        Ok(image::ImageFormat::Jpeg) => Ok(("jpg", "image/jpeg")),
+        Ok(image::ImageFormat::Gif) => Ok(("gif", "image/gif")),
+        Ok(image::ImageFormat::WebP) => Ok(("webp", "image/webp")),
+        _ => Err(AppError::invalid_input(
+            "Only PNG, JPEG, GIF and WebP images are supported",
+        )),
+    }
+}
+
+fn too_large() -> AppError {
+    AppError::invalid_input(format!(
+        "Photos must be at most {} MB",
+        MAX_PHOTO_BYTES / 1024 / 1024
+    ))
+}
+
+async fn find_row(db: &DatabaseConnection, id: &str) -> AppResult<PhotoRow> {
+    PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{PHOTO_SELECT} WHERE id = ?"),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Photo not found"))
+}
+
+/// Any photo row holding the given image, to reuse its size and upload.
+async fn find_by_hash(db: &DatabaseConnection, hash: &str) -> AppResult<Option<PhotoRow>> {
+    Ok(PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{PHOTO_SELECT} WHERE content_hash = ? ORDER BY cloud_key IS NULL, id LIMIT 1"),
+        [hash.into()],
+    ))
+    .one(db)
+    .await?)
+}
+
+async fn ensure_item_exists(db: &DatabaseConnection, order_item_id: &str) -> AppResult<()> {
+    let found = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT 1 AS found FROM order_items WHERE id = ? AND deleted_at IS NULL",
+            [order_item_id.into()],
+        ))
+        .await?;
+    if found.is_none() {
+        return Err(AppError::not_found("Order item not found"));
+    }
+    Ok(())
+}
+
+/// Stores image bytes under their hash and links them to an order item. Adding
+/// the same image to the same item twice returns the existing photo.
+async fn add_photo(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_item_id: String,
+    bytes: Vec<u8>,
+    source: PhotoSource,
+    source_url: Option<String>,
+    upload_to_s3: bool,
+) -> AppResult<OrderItemPhoto> {
+    if bytes.len() > MAX_PHOTO_BYTES {
+        return Err(too_large());
+    }
+    let (file_ext, content_type) = image_kind(&bytes)?;
+
+    let db = state.db.lock().await.clone();
+    ensure_item_exists(&db, &order_item_id).await?;
+    let store = PhotoStore::new(app)?;
+
+    let hash = format!("{:x}", Sha256::digest(&bytes));
+    let existing = PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{PHOTO_SELECT} WHERE order_item_id = ? AND content_hash = ?"),
+        [order_item_id.clone().into(), hash.clone().into()],
+    ))
+    .one(&db)
+    .await?;
+    if let Some(row) = existing {
+        return store.to_photo(row);
+    }
+
+    let size_bytes = bytes.len() as i64;
+    let image_path = store.image_path(&hash, file_ext);
+    if !image_path.exists() {
+        if let Some(parent) = image_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write under a temporary name so a crash never leaves a partial file
+        // at the content-addressed path.
+        let partial = image_path.with_extension("part");
+        fs::write(&partial, &bytes)?;
+        fs::rename(&partial, &image_path)?;
+    }
+
+    let shared = find_by_hash(&db, &hash).await?;
+    let thumbnail_path = store.thumbnail_path(&hash);
+    let dimensions = match shared.as_ref().and_then(|row| row.width.zip(row.height)) {
+        Some(dimensions) if thumbnail_path.exists() => dimensions,
+        _ => match generate_thumbnail(image_path.clone(), thumbnail_path).await {
+            Ok((width, height)) => (i64::from(width), i64::from(height)),
+            Err(err) => {
+                if shared.is_none() {
+                    remove_file_if_present(&image_path.to_string_lossy());
+                }
+                return Err(err);
+            }
+        },
+    };
+
+    let id = Uuid::new_v4().to_string();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO order_item_photos (id, order_item_id, content_hash, file_ext, content_type, \
+         size_bytes, width, height, source, source_url, cloud_key, cloud_url, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))",
+        [
+            id.clone().into(),
+            order_item_id.into(),
+            hash.into(),
+            file_ext.into(),
+            content_type.into(),
+            size_bytes.into(),
+            dimensions.0.into(),
+            dimensions.1.into(),
+            source.as_str().into(),
+            source_url.into(),
+            shared.as_ref().and_then(|row| row.cloud_key.clone()).into(),
+            shared.and_then(|row| row.cloud_url).into(),
+        ],
+    ))
+    .await?;
+
+    let row = find_row(&db, &id).await?;
+    if upload_to_s3 && row.cloud_key.is_none() {
+        if let Err(err) = upload_row(&db, app, &store, &row).await {
+            tracing::warn!("Photo {id} was saved locally but not uploaded: {err}");
+        }
+        return store.to_photo(find_row(&db, &id).await?);
+    }
+    store.to_photo(row)
+}
+
+/// Adds an image file from disk to an order item.
+#[instrument(skip(state, app))]
+pub async fn add_order_item_photo_from_file(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_item_id: String,
+    file_path: String,
+    upload_to_s3: bool,
+) -> AppResult<OrderItemPhoto> {
+    let path = PathBuf::from(file_path.trim());
+    let metadata = fs::metadata(&path)
+        .map_err(|_| AppError::not_found(format!("File not found: {}", file_path.trim())))?;
+    if !metadata.is_file() {
+        return Err(AppError::invalid_input("Only files can be added as photos"));
+    }
+    if metadata.len() > MAX_PHOTO_BYTES as u64 {
+        return Err(too_large());
+    }
+    let bytes = fs::read(&path)?;
+    add_photo(
+        state,
+        app,
+        order_item_id,
+        bytes,
+        PhotoSource::File,
+        None,
+        upload_to_s3,
+    )
+    .await
+}
+
+/// Adds the image currently on the clipboard to an order item, saved as PNG.
+#[instrument(skip(state, app))]
+pub async fn add_order_item_photo_from_clipboard(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_item_id: String,
+    upload_to_s3: bool,
+) -> AppResult<OrderItemPhoto> {
+    let image = app
+        .clipboard()
+        .read_image()
+        .map_err(|e| AppError::invalid_input(format!("No image on the clipboard: {e}")))?;
+    let (width, height, rgba) = (image.width(), image.height(), image.rgba().to_vec());
+    let bytes = tauri::async_runtime::spawn_blocking(move || -> AppResult<Vec<u8>> {
+        let buffer = image::RgbaImage::from_raw(width, height, rgba)
+            .ok_or_else(|| AppError::internal("Clipboard image has an unexpected size"))?;
+        let mut png = Cursor::new(Vec::new());
+        buffer
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| AppError::internal(format!("Failed to encode clipboard image: {e}")))?;
+        Ok(png.into_inner())
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))??;
+    add_photo(
+        state,
+        app,
+        order_item_id,
+        bytes,
+        PhotoSource::Clipboard,
+        None,
+        upload_to_s3,
+    )
+    .await
+}
+
+/// Downloads an image over HTTP(S) and adds it to an order item.
+#[instrument(skip(state, app))]
+pub async fn add_order_item_photo_from_url(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_item_id: String,
+    url: String,
+    upload_to_s3: bool,
+) -> AppResult<OrderItemPhoto> {
+    let url = url::Url::parse(url.trim())
+        .map_err(|_| AppError::invalid_input(format!("Invalid URL: {}", url.trim())))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::invalid_input(
+            "Photo URLs must start with http:// or https://",
+        ));
+    }
+
+    let mut response = reqwest::Client::new()
+        .get(url.clone())
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| AppError::invalid_input(format!("Failed to download photo: {e}")))?;
+    if !response.status().is_success() {
+        return Err(AppError::invalid_input(format!(
+            "Failed to download photo: server returned {}",
+            response.status()
+        )));
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_PHOTO_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::invalid_input(format!("Failed to download photo: {e}")))?
+    {
+        if bytes.len() + chunk.len() > MAX_PHOTO_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    add_photo(
+        state,
+        app,
+        order_item_id,
+        bytes,
+        PhotoSource::Url,
+        Some(url.to_string()),
+        upload_to_s3,
+    )
+    .await
+}
+
+/// Uploads the image once per hash and records the key on every row holding it.
+async fn upload_row(
+    db: &DatabaseConnection,
+    app: &AppHandle,
+    store: &PhotoStore,
+    row: &PhotoRow,
+) -> AppResult<()> {
+    let storage = Storage::required(app, S3_PROVIDER_NAME).await?;
+    let path = store.image_path(&row.content_hash, &row.file_ext);
+    let bytes = fs::read(&path)
+        .map_err(|_| AppError::not_found(format!("Photo file not found: {}", path.display())))?;
+    let key = format!("product-photos/{}.{}", row.content_hash, row.file_ext);
+    storage.put_object(&key, bytes, &row.content_type).await?;
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE order_item_photos SET cloud_key = ?, cloud_url = ?, updated_at = datetime('now') \
+         WHERE content_hash = ?",
+        [
+            key.clone().into(),
+            storage.public_url(&key).into(),
+            row.content_hash.clone().into(),
+        ],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Uploads a photo to the configured storage.
+#[instrument(skip(state, app))]
+pub async fn upload_order_item_photo_to_s3(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+) -> AppResult<OrderItemPhoto> {
+    let db = state.db.lock().await.clone();
+    let store = PhotoStore::new(app)?;
+    let row = find_row(&db, &id).await?;
+    upload_row(&db, app, &store, &row).await?;
+    store.to_photo(find_row(&db, &id).await?)
+}
+
+/// Lists the photos of one order item, oldest first.
+#[instrument(skip(state, app))]
+pub async fn get_order_item_photos(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_item_id: String,
+) -> AppResult<Vec<OrderItemPhoto>> {
+    let db = state.db.lock().await.clone();
+    let store = PhotoStore::new(app)?;
+    PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{PHOTO_SELECT} WHERE order_item_id = ? ORDER BY created_at ASC, id ASC"),
+        [order_item_id.into()],
+    ))
+    .all(&db)
+    .await?
+    .into_iter()
+    .map(|row| store.to_photo(row))
+    .collect()
+}
+
+/// Lists the photos of every current item of an order, for the detail page.
+#[instrument(skip(state, app))]
+pub async fn get_order_photos(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_id: String,
+) -> AppResult<Vec<OrderItemPhoto>> {
+    let db = state.db.lock().await.clone();
+    let store = PhotoStore::new(app)?;
+    PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{PHOTO_SELECT} WHERE order_item_id IN \
+             (SELECT id FROM order_items WHERE order_id = ? AND deleted_at IS NULL) \
+             ORDER BY created_at ASC, id ASC"
+        ),
+        [order_id.into()],
+    ))
+    .all(&db)
+    .await?
+    .into_iter()
+    .map(|row| store.to_photo(row))
+    .collect()
+}
+
+/// Moves the photos of an order item to the row that replaces it. Photos the
+/// new row already has stay behind on the old one.
+pub(crate) async fn move_item_photos<C: ConnectionTrait>(
+    conn: &C,
+    from_item_id: &str,
+    to_item_id: &str,
+) -> AppResult<()> {
+    conn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE OR IGNORE order_item_photos SET order_item_id = ?, updated_at = datetime('now') \
+         WHERE order_item_id = ?",
+        [to_item_id.into(), from_item_id.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Deletes photo rows. A file, its thumbnail and its uploaded copy go only once no
+/// other row holds the same image.
+async fn remove_rows(db: &DatabaseConnection, app: &AppHandle, rows: Vec<PhotoRow>) -> u64 {
+    if rows.is_empty() {
+        return 0;
+    }
+    let store = match PhotoStore::new(app) {
+        Ok(store) => Some(store),
+        Err(err) => {
+            tracing::warn!("Photo files cannot be removed: {err}");
+            None
+        }
+    };
+    let storage = if rows.iter().any(|row| row.cloud_key.is_some()) {
+        Storage::from_settings(app, S3_PROVIDER_NAME)
+            .await
+            .unwrap_or_else(|err| {
+                tracing::warn!("Uploaded photos cannot be removed from storage: {err}");
+                None
+            })
+    } else {
+        None
+    };
+
+    let mut removed = 0;
+    for row in rows {
+        if let Err(err) = db
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                "DELETE FROM order_item_photos WHERE id = ?",
+                [row.id.clone().into()],
+            ))
+            .await
+        {
+            tracing::warn!("Failed to delete photo {}: {err}", row.id);
+            continue;
+        }
+        removed += 1;
+
+        match find_by_hash(db, &row.content_hash).await {
+            Ok(None) => {}
+            Ok(Some(_)) => continue,
+            Err(err) => {
+                tracing::warn!("Keeping the file of photo {}: {err}", row.id);
+                continue;
+            }
+        }
+        if let Some(store) = &store {
+            remove_file_if_present(
+                &store
+                    .image_path(&row.content_hash, &row.file_ext)
+                    .to_string_lossy(),
+            );
+            remove_file_if_present(&store.thumbnail_path(&row.content_hash).to_string_lossy());
+        }
+        if let (Some(storage), Some(key)) = (&storage, &row.cloud_key) {
+            if let Err(err) = storage.delete_object(key).await {
+                tracing::warn!("Failed to delete photo {} from storage: {err}", row.id);
+            }
+        }
+    }
+    removed
+}
+
+/// Removes a photo from its order item.
+#[instrument(skip(state, app))]
+pub async fn delete_order_item_photo(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    id: String,
+) -> AppResult<()> {
+    let db = state.db.lock().await.clone();
+    let row = find_row(&db, &id).await?;
+    remove_rows(&db, app, vec![row]).await;
+    Ok(())
+}
+
+/// Deletes the photos of the given order items, e.g. items dropped from an
+/// order on edit.
+pub(crate) async fn delete_item_photos(
+    db: &DatabaseConnection,
+    app: &AppHandle,
+    order_item_ids: &[String],
+) -> AppResult<u64> {
+    if order_item_ids.is_empty() {
+        return Ok(0);
+    }
+    let placeholders = vec!["?"; order_item_ids.len()].join(", ");
+    let rows = PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{PHOTO_SELECT} WHERE order_item_id IN ({placeholders})"),
+        order_item_ids.iter().map(|id| id.as_str().into()),
+    ))
+    .all(db)
+    .await?;
+    Ok(remove_rows(db, app, rows).await)
+}
+
+/// Deletes the photos of every item of an order; called when the order is deleted.
+pub(crate) async fn delete_order_photos(
+    db: &DatabaseConnection,
+    app: &AppHandle,
+    order_id: &str,
+) -> AppResult<u64> {
+    let rows = PhotoRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{PHOTO_SELECT} WHERE order_item_id IN (SELECT id FROM order_items WHERE order_id = ?)"
+        ),
+        [order_id.into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(remove_rows(db, app, rows).await)
+}
+
+/// Tidies photos after edits and deletes made elsewhere and pulled in through
+/// sync. Photos of an item replaced by an edit move to the current item with
+/// the same product URL; photos of deleted orders are removed. Returns how
+/// many were removed.
+#[instrument(skip(state, app))]
+pub async fn cleanup_orphaned_order_item_photos(
+    state: Arc<AppState>,
+    app: &AppHandle,
+) -> AppResult<u64> {
+    let db = state.db.lock().await.clone();
+    db.execute_unprepared(
+        "UPDATE OR IGNORE order_item_photos SET updated_at = datetime('now'), order_item_id = ( \
+           SELECT current.id FROM order_items old \
+           JOIN order_items current ON current.order_id = old.order_id \
+             AND current.deleted_at IS NULL AND current.product_url = old.product_url \
+           WHERE old.id = order_item_photos.order_item_id \
+           ORDER BY current.created_at, current.id LIMIT 1) \
+         WHERE order_item_id IN (SELECT id FROM order_items WHERE deleted_at IS NOT NULL) \
+           AND EXISTS ( \
+           SELECT 1 FROM order_items old \
+           JOIN order_items current ON current.order_id = old.order_id \
+             AND current.deleted_at IS NULL AND current.product_url = old.product_url \
+           WHERE old.id = order_item_photos.order_item_id)",
+    )
+    .await?;
+
+    let orphans = PhotoRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{PHOTO_SELECT} WHERE NOT EXISTS (SELECT 1 FROM order_items i \
+             JOIN orders o ON o.id = i.order_id \
+             WHERE i.id = order_item_photos.order_item_id AND o.deleted_at IS NULL)"
+        ),
+    ))
+    .all(&db)
+    .await?;
+    Ok(remove_rows(&db, app, orphans).await)
+}
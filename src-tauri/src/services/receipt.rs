@@ -0,0 +1,421 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tracing::instrument;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Customer, OrderDetail, ShopSettings};
+use crate::services::escpos::{Align, EscPosBuilder, PaperWidth};
+use crate::services::printer::{self, PrintJob};
+use crate::services::settings::{get_app_settings, AppSettings};
+use crate::services::{customer, order, shop};
+use crate::state::AppState;
+
+const DEFAULT_RAW_PRINTER_PORT: u16 = 9100;
+const NETWORK_PRINTER_TIMEOUT: Duration = Duration::from_secs(10);
+const RECEIPT_QR_MODULE_SIZE: u8 = 6;
+
+/// Destination for raw ESC/POS bytes, resolved from `AppSettings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptPrinterTarget {
+    /// CUPS queue set up as a raw printer; `None` uses the system default.
+    Cups { queue: Option<String> },
+    /// Serial or USB character device such as `/dev/usb/lp0` or `COM3`.
+    Device { path: String },
+    /// Network printer listening on a raw TCP port, usually 9100.
+    Network { host: String, port: u16 },
+}
+
+impl ReceiptPrinterTarget {
+    /// Builds the target from `receipt_printer_transport` and `receipt_printer_target`.
+    pub fn from_settings(settings: &AppSettings) -> AppResult<Self> {
+        let target = settings.receipt_printer_target.trim();
+        let transport = settings
+            .receipt_printer_transport
+            .trim()
+            .to_ascii_lowercase();
+
+        match transport.as_str() {
+            "" | "cups" => Ok(Self::Cups {
+                queue: Some(target.to_string()).filter(|queue| !queue.is_empty()),
+            }),
+            "device" => {
+                if target.is_empty() {
+                    return Err(AppError::invalid_input(
+                        "Please set the receipt printer device path in Settings.",
+                    ));
+                }
+                Ok(Self::Device {
+                    path: target.to_string(),
+                })
+            }
+            "network" => parse_network_target(target),
+            other => Err(AppError::invalid_input(format!(
+                "Unsupported receipt printer transport: {other}"
+            ))),
+        }
+    }
+}
+
+fn parse_network_target(target: &str) -> AppResult<ReceiptPrinterTarget> {
+    if target.is_empty() {
+        return Err(AppError::invalid_input(
+            "Please set the receipt printer host in Settings.",
+        ));
+    }
+
+    let (host, port) = match target.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+            let port = port
+                .parse::<u16>()
+                .map_err(|_| AppError::invalid_input(format!("Invalid printer port: {port}")))?;
+            (host, port)
+        }
+        _ => (target, DEFAULT_RAW_PRINTER_PORT),
+    };
+
+    Ok(ReceiptPrinterTarget::Network {
+        host: host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        port,
+    })
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn format_amount(amount: f64) -> String {
+    let formatted = format!("{:.2}", amount);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn money(symbol: &str, amount: f64) -> String {
+    format!("{}{}", symbol, format_amount(amount))
+}
+
+/// Strips the scheme and query string so product links fit on one receipt line.
+fn short_product_label(url: &str) -> String {
+    let without_scheme = url
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.");
+    without_scheme
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Renders an order receipt as ESC/POS bytes.
+///
+/// Totals mirror the order detail screen: item total plus shipping, delivery,
+/// cargo and service fees, with the exchange currency total when a rate is set.
+pub fn render_order_receipt(
+    paper: PaperWidth,
+    shop: &ShopSettings,
+    detail: &OrderDetail,
+    customer: Option<&Customer>,
+    settings: &AppSettings,
+) -> Vec<u8> {
+    let order = &detail.order;
+    let symbol = settings.currency_symbol.as_str();
+    let order_code = order.order_id.clone().unwrap_or_else(|| order.id.clone());
+    let mut receipt = EscPosBuilder::new(paper);
+
+    receipt
+        .align(Align::Center)
+        .bold(true)
+        .double_size(true)
+        .line(&shop.shop_name)
+        .double_size(false)
+        .bold(false);
+    if let Some(phone) = non_empty(shop.phone.as_deref()) {
+        receipt.line(phone);
+    }
+    if let Some(address) = non_empty(shop.address.as_deref()) {
+        receipt.line(address);
+    }
+
+    receipt.align(Align::Left).separator();
+    receipt.columns("Order", &order_code);
+    if let Some(date) = non_empty(order.order_date.as_deref().or(order.created_at.as_deref())) {
+        receipt.columns("Date", date.get(..10).unwrap_or(date));
+    }
+    if let Some(name) = customer
+        .map(|c| c.name.as_str())
+        .or(order.customer_name.as_deref())
+    {
+        receipt.columns("Customer", name);
+    }
+    if let Some(phone) = customer.and_then(|c| non_empty(c.phone.as_deref())) {
+        receipt.columns("Phone", phone);
+    }
+    receipt.separator();
+
+    for (index, item) in detail.items.iter().enumerate() {
+        let qty = item.product_qty.unwrap_or(0);
+        let price = item.price.unwrap_or(0.0);
+        let label = item
+            .product_url
+            .as_deref()
+            .map(short_product_label)
+            .unwrap_or_default();
+
+        receipt.columns(&format!("{}. {}", index + 1, label), "");
+        receipt.columns(
+            &format!("   {} x {}", qty, money(symbol, price)),
+            &money(symbol, price * qty as f64),
+        );
+    }
+    receipt.separator();
+
+    let items_total = order.total_price.unwrap_or(0.0);
+    let shipping_fee = order.shipping_fee.unwrap_or(0.0);
+    let delivery_fee = order.delivery_fee.unwrap_or(0.0);
+    let cargo_fee = order.cargo_fee.unwrap_or(0.0);
+    let service_fee = order::service_fee_amount(order);
+    let order_total = order::order_grand_total(order);
+
+    receipt.columns("Subtotal", &money(symbol, items_total));
+    for (label, amount) in [
+        ("Service fee", service_fee),
+        ("Shipping fee", shipping_fee),
+        ("Delivery fee", delivery_fee),
+        ("Cargo fee", cargo_fee),
+    ] {
+        if amount > 0.0 {
+            receipt.columns(label, &money(symbol, amount));
+        }
+    }
+    receipt
+        .bold(true)
+        .columns("TOTAL", &money(symbol, order_total))
+        .bold(false);
+
+    let exchange_rate = order.exchange_rate.unwrap_or(1.0);
+    if exchange_rate > 0.0 && (exchange_rate - 1.0).abs() > f64::EPSILON {
+        receipt.columns(
+            &format!("@ {}", format_amount(exchange_rate)),
+            &money(
+                &settings.exchange_currency_symbol,
+                order_total * exchange_rate,
+            ),
+        );
+    }
+
+    receipt
+        .separator()
+        .align(Align::Center)
+        .qr_code(&order_code, RECEIPT_QR_MODULE_SIZE)
+        .line(&order_code)
+        .line("Thank you!")
+        .feed(3)
+        .cut();
+
+    receipt.into_bytes()
+}
+
+/// Sends raw ESC/POS bytes to the configured receipt printer.
+#[instrument(skip(bytes), fields(len = bytes.len()))]
+pub async fn send_to_printer(target: &ReceiptPrinterTarget, bytes: &[u8]) -> AppResult<PrintJob> {
+    match target {
+        ReceiptPrinterTarget::Cups { queue } => {
+            let queue = queue.clone();
+            let bytes = bytes.to_vec();
+            printer::run_blocking(move || {
+                printer::submit_print_bytes(queue.as_deref(), &bytes, true)
+            })
+            .await
+        }
+        ReceiptPrinterTarget::Device { path } => {
+            let path = path.clone();
+            let bytes = bytes.to_vec();
+            printer::run_blocking(move || {
+                let mut device = OpenOptions::new().write(true).open(&path).map_err(|e| {
+                    AppError::print_failed(format!("Failed to open printer device {}: {}", path, e))
+                })?;
+                device.write_all(&bytes)?;
+                device.flush()?;
+                Ok(PrintJob {
+                    job_id: None,
+                    printer: Some(path),
+                })
+            })
+            .await
+        }
+        ReceiptPrinterTarget::Network { host, port } => {
+            let mut stream = timeout(
+                NETWORK_PRINTER_TIMEOUT,
+                TcpStream::connect((host.as_str(), *port)),
+            )
+            .await
+            .map_err(|_| {
+                AppError::print_failed(format!("Timed out connecting to printer {}:{}", host, port))
+            })??;
+            stream.write_all(bytes).await?;
+            stream.shutdown().await?;
+            Ok(PrintJob {
+                job_id: None,
+                printer: Some(format!("{}:{}", host, port)),
+            })
+        }
+    }
+}
+
+/// Renders an order receipt and sends it to the configured thermal printer.
+#[instrument(skip(state, app))]
+pub async fn print_order_receipt(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    order_id: String,
+) -> AppResult<PrintJob> {
+    let settings = get_app_settings(app.clone())?;
+    let target = ReceiptPrinterTarget::from_settings(&settings)?;
+    let paper = PaperWidth::from_setting(&settings.receipt_paper_width);
+
+    let shop = shop::get_shop_settings(state.clone()).await?;
+    let detail = order::get_order(state.clone(), order_id).await?;
+    let customer = match detail.order.customer_id.clone() {
+        Some(customer_id) => customer::get_customer(state.clone(), customer_id)
+            .await
+            .ok(),
+        None => None,
+    };
+
+    let bytes = render_order_receipt(paper, &shop, &detail, customer.as_ref(), &settings);
+    send_to_printer(&target, &bytes).await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn fixture() -> (ShopSettings, OrderDetail) {
+        let shop = serde_json::from_value(json!({
+            "id": "shop",
+            "shop_name": "Thar Shop",
+            "phone": "09 123 456",
+        }))
+        .unwrap();
+        let detail = serde_json::from_value(json!({
+            "order": {
+                "id": "order-1",
+                "order_id": "ORD-0001",
+                "customer_name": "Aung Aung",
+                "order_date": "2026-03-14 10:00:00",
+                "total_price": 25.5,
+                "shipping_fee": 2.0,
+                "service_fee": 10.0,
+                "service_fee_type": "percent",
+                "exchange_rate": 1.0,
+            },
+            "items": [{
+                "id": "item-1",
+                "order_id": "order-1",
+                "product_url": "https://www.example.com/item/42?ref=abc",
+                "product_qty": 2,
+                "price": 12.75,
+            }],
+        }))
+        .unwrap();
+        (shop, detail)
+    }
+
+    fn expected_receipt(rows: &[&str]) -> Vec<u8> {
+        let mut bytes = b"\x1b@\x1ba\x01\x1bE\x01\x1d!\x11Thar Shop\n\x1d!\x00\x1bE\x00".to_vec();
+        bytes.extend_from_slice(b"09 123 456\n\x1ba\x00");
+        for row in rows {
+            let bold = row.starts_with("TOTAL");
+            if bold {
+                bytes.extend_from_slice(b"\x1bE\x01");
+            }
+            bytes.extend_from_slice(row.as_bytes());
+            bytes.push(b'\n');
+            if bold {
+                bytes.extend_from_slice(b"\x1bE\x00");
+            }
+        }
+        bytes.extend_from_slice(b"\x1ba\x01");
+        bytes.extend_from_slice(b"\x1d(k\x04\x001A2\x00\x1d(k\x03\x001C\x06\x1d(k\x03\x001E1");
+        bytes.extend_from_slice(b"\x1d(k\x0b\x001P0ORD-0001\x1d(k\x03\x001Q0\n");
+        bytes.extend_from_slice(b"ORD-0001\nThank you!\n\x1bd\x03\x1dVB\x03");
+        bytes
+    }
+
+    #[test]
+    fn renders_58mm_receipt() {
+        let (shop, detail) = fixture();
+        let bytes = render_order_receipt(
+            PaperWidth::Mm58,
+            &shop,
+            &detail,
+            None,
+            &AppSettings::default(),
+        );
+        let dashes = "-".repeat(32);
+        assert_eq!(
+            bytes,
+            expected_receipt(&[
+                &dashes,
+                "Order                   ORD-0001",
+                "Date                  2026-03-14",
+                "Customer               Aung Aung",
+                &dashes,
+                "1. example.com/item/42          ",
+                "   2 x $12.75              $25.5",
+                &dashes,
+                "Subtotal                   $25.5",
+                "Service fee                $2.55",
+                "Shipping fee                  $2",
+                "TOTAL                     $30.05",
+                &dashes,
+            ])
+        );
+    }
+
+    #[test]
+    fn renders_80mm_receipt() {
+        let (shop, detail) = fixture();
+        let bytes = render_order_receipt(
+            PaperWidth::Mm80,
+            &shop,
+            &detail,
+            None,
+            &AppSettings::default(),
+        );
+        let dashes = "-".repeat(48);
+        assert_eq!(
+            bytes,
+            expected_receipt(&[
+                &dashes,
+                "Order                                   ORD-0001",
+                "Date                                  2026-03-14",
+                "Customer                               Aung Aung",
+                &dashes,
+                "1. example.com/item/42                          ",
+                "   2 x $12.75                              $25.5",
+                &dashes,
+                "Subtotal                                   $25.5",
+                "Service fee                                $2.55",
+                "Shipping fee                                  $2",
+                "TOTAL                                     $30.05",
+                &dashes,
+            ])
+        );
+    }
+}
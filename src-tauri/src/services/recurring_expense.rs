@@ -0,0 +1,709 @@
+use std::sync::Arc;
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::services::expense::create_expense;
+use crate::services::expense_category::{canonical_category, canonical_payment_method};
+use crate::state::AppState;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Occurrences posted per template in one run; a template that has been paused
+/// for years catches up over several runs instead of all at once.
+const MAX_CATCH_UP: usize = 60;
+
+const DEFAULT_UPCOMING_DAYS: i64 = 30;
+const MAX_UPCOMING_DAYS: i64 = 366;
+
+/// Serializes generation so the startup run and the scheduled run never race
+/// on the same occurrence.
+static GENERATION_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurringFrequency {
+    Monthly,
+    Weekly,
+}
+
+impl RecurringFrequency {
+    fn as_str(self) -> &'static str {
+        match self {
+            RecurringFrequency::Monthly => "monthly",
+            RecurringFrequency::Weekly => "weekly",
+        }
+    }
+}
+
+/// What the expense form sends when saving a template.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecurringExpenseInput {
+    pub title: String,
+    pub amount: f64,
+    pub category: Option<String>,
+    pub payment_method: Option<String>,
+    pub notes: Option<String>,
+    pub frequency: RecurringFrequency,
+    /// 1-31 for monthly; defaults to the day of `start_date`.
+    pub day_of_month: Option<u32>,
+    /// 0 (Sunday) to 6 (Saturday) for weekly; defaults to the weekday of `start_date`.
+    pub day_of_week: Option<u32>,
+    /// `YYYY-MM-DD`; defaults to today. Past dates are posted on the next run.
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecurringExpense {
+    pub id: String,
+    pub title: String,
+    pub amount: f64,
+    pub category: Option<String>,
+    pub payment_method: Option<String>,
+    pub notes: Option<String>,
+    pub frequency: RecurringFrequency,
+    pub day_of_month: Option<u32>,
+    pub day_of_week: Option<u32>,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub is_active: bool,
+    pub last_generated_date: Option<String>,
+    /// Next occurrence not yet posted, if the schedule has one.
+    pub next_due_date: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// One occurrence that has not been posted as an expense yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingRecurringExpense {
+    pub recurring_id: String,
+    pub title: String,
+    pub amount: f64,
+    pub category: Option<String>,
+    pub payment_method: Option<String>,
+    pub due_date: String,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct RecurringExpenseRow {
+    id: String,
+    title: String,
+    amount: f64,
+    category: Option<String>,
+    payment_method: Option<String>,
+    notes: Option<String>,
+    frequency: String,
+    day_of_month: Option<i64>,
+    day_of_week: Option<i64>,
+    start_date: String,
+    end_date: Option<String>,
+    is_active: bool,
+    last_generated_date: Option<String>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct RunRow {
+    expense_id: String,
+}
+
+const RECURRING_SELECT: &str = "SELECT id, title, amount, category, payment_method, notes, \
+     frequency, day_of_month, day_of_week, start_date, end_date, is_active, \
+     last_generated_date, created_at, updated_at FROM recurring_expenses";
+
+#[derive(Debug, Clone, Copy)]
+enum Schedule {
+    Monthly { day: u32 },
+    Weekly { weekday: u32 },
+}
+
+impl Schedule {
+    /// First occurrence on or after `from`.
+    fn next_on_or_after(self, from: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Schedule::Weekly { weekday } => {
+                let offset = (weekday + 7 - from.weekday().num_days_from_sunday()) % 7;
+                from.checked_add_days(Days::new(offset.into()))
+            }
+            Schedule::Monthly { day } => {
+                let this_month = day_in_month(from.year(), from.month(), day)?;
+                if this_month >= from {
+                    return Some(this_month);
+                }
+                let next = from.with_day(1)?.checked_add_months(Months::new(1))?;
+                day_in_month(next.year(), next.month(), day)
+            }
+        }
+    }
+}
+
+/// `day` of the given month, or its last day when the month is shorter.
+fn day_in_month(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (1..=day)
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+fn parse_date(value: &str, field: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).map_err(|_| {
+        AppError::invalid_input(format!("Invalid {field} (expected YYYY-MM-DD): {value}"))
+    })
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+impl RecurringExpenseRow {
+    fn schedule(&self) -> AppResult<Schedule> {
+        let day = |value: Option<i64>| value.and_then(|v| u32::try_from(v).ok());
+        match (
+            self.frequency.as_str(),
+            day(self.day_of_month),
+            day(self.day_of_week),
+        ) {
+            ("monthly", Some(day), _) => Ok(Schedule::Monthly { day }),
+            ("weekly", _, Some(weekday)) => Ok(Schedule::Weekly { weekday }),
+            _ => Err(AppError::internal(format!(
+                "Recurring expense {} has an invalid schedule",
+                self.id
+            ))),
+        }
+    }
+
+    /// Occurrences after the last posted one, up to `until` and the end date.
+    fn pending_occurrences(&self, until: NaiveDate, limit: usize) -> AppResult<Vec<NaiveDate>> {
+        let schedule = self.schedule()?;
+        let start = parse_date(&self.start_date, "start date")?;
+        let resume = match self.last_generated_date.as_deref() {
+            Some(last) => parse_date(last, "last generated date")?
+                .succ_opt()
+                .map_or(start, |next| next.max(start)),
+            None => start,
+        };
+        let until = match self.end_date.as_deref() {
+            Some(end) => until.min(parse_date(end, "end date")?),
+            None => until,
+        };
+
+        let mut dates = Vec::new();
+        let mut cursor = resume;
+        while dates.len() < limit {
+            let Some(due) = schedule.next_on_or_after(cursor).filter(|d| *d <= until) else {
+                break;
+            };
+            dates.push(due);
+            let Some(next) = due.succ_opt() else {
+                break;
+            };
+            cursor = next;
+        }
+        Ok(dates)
+    }
+
+    fn into_recurring_expense(self) -> AppResult<RecurringExpense> {
+        let next_due_date = self
+            .pending_occurrences(NaiveDate::MAX, 1)?
+            .first()
+            .map(|d| d.format(DATE_FORMAT).to_string());
+        let frequency = match self.schedule()? {
+            Schedule::Monthly { .. } => RecurringFrequency::Monthly,
+            Schedule::Weekly { .. } => RecurringFrequency::Weekly,
+        };
+        let day = |value: Option<i64>| value.and_then(|v| u32::try_from(v).ok());
+        Ok(RecurringExpense {
+            id: self.id,
+            title: self.title,
+            amount: self.amount,
+            category: self.category,
+            payment_method: self.payment_method,
+            notes: self.notes,
+            frequency,
+            day_of_month: day(self.day_of_month),
+            day_of_week: day(self.day_of_week),
+            start_date: self.start_date,
+            end_date: self.end_date,
+            is_active: self.is_active,
+            last_generated_date: self.last_generated_date,
+            next_due_date,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// A validated template, ready to be written.
+struct TemplateValues {
+    title: String,
+    amount: f64,
+    category: Option<String>,
+    payment_method: Option<String>,
+    notes: Option<String>,
+    frequency: RecurringFrequency,
+    day_of_month: Option<u32>,
+    day_of_week: Option<u32>,
+    start_date: String,
+    end_date: Option<String>,
+    is_active: bool,
+}
+
+async fn validate_input(
+    db: &DatabaseConnection,
+    input: RecurringExpenseInput,
+) -> AppResult<TemplateValues> {
+    let title = input.title.trim().to_string();
+    if title.is_empty() {
+        return Err(AppError::invalid_input("Expense title is required"));
+    }
+    if !input.amount.is_finite() || input.amount < 0.0 {
+        return Err(AppError::invalid_input(
+            "Expense amount must be a valid non-negative number",
+        ));
+    }
+
+    let start = match input.start_date.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(raw) => parse_date(raw, "start date")?,
+        None => today(),
+    };
+    let end = input
+        .end_date
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|raw| parse_date(raw, "end date"))
+        .transpose()?;
+    if end.is_some_and(|end| end < start) {
+        return Err(AppError::invalid_input(
+            "End date must not be before the start date",
+        ));
+    }
+
+    let (day_of_month, day_of_week) = match input.frequency {
+        RecurringFrequency::Monthly => {
+            let day = input.day_of_month.unwrap_or_else(|| start.day());
+            if !(1..=31).contains(&day) {
+                return Err(AppError::invalid_input(
+                    "Day of month must be between 1 and 31",
+                ));
+            }
+            (Some(day), None)
+        }
+        RecurringFrequency::Weekly => {
+            let weekday = input
+                .day_of_week
+                .unwrap_or_else(|| start.weekday().num_days_from_sunday());
+            if weekday > 6 {
+                return Err(AppError::invalid_input(
+                    "Day of week must be between 0 (Sunday) and 6 (Saturday)",
+                ));
+            }
+            (None, Some(weekday))
+        }
+    };
+
+    Ok(TemplateValues {
+        title,
+        amount: input.amount,
+        category: canonical_category(db, input.category).await?,
+        payment_method: canonical_payment_method(db, input.payment_method).await?,
+        notes: input
+            .notes
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty()),
+        frequency: input.frequency,
+        day_of_month,
+        day_of_week,
+        start_date: start.format(DATE_FORMAT).to_string(),
+        end_date: end.map(|d| d.format(DATE_FORMAT).to_string()),
+        is_active: input.is_active.unwrap_or(true),
+    })
+}
+
+async fn find_row(db: &DatabaseConnection, id: &str) -> AppResult<RecurringExpenseRow> {
+    RecurringExpenseRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{RECURRING_SELECT} WHERE id = ?"),
+        [id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Recurring expense not found"))
+}
+
+async fn load_active_rows(db: &DatabaseConnection) -> AppResult<Vec<RecurringExpenseRow>> {
+    Ok(
+        RecurringExpenseRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!("{RECURRING_SELECT} WHERE is_active = 1"),
+        ))
+        .all(db)
+        .await?,
+    )
+}
+
+/// Lists all recurring expense templates with their next due date.
+#[instrument(skip(state))]
+pub async fn get_recurring_expenses(state: Arc<AppState>) -> AppResult<Vec<RecurringExpense>> {
+    let db = state.db.lock().await.clone();
+    RecurringExpenseRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        format!("{RECURRING_SELECT} ORDER BY is_active DESC, title COLLATE NOCASE ASC"),
+    ))
+    .all(&db)
+    .await?
+    .into_iter()
+    .map(RecurringExpenseRow::into_recurring_expense)
+    .collect()
+}
+
+/// Creates a recurring expense template.
+#[instrument(skip(state))]
+pub async fn create_recurring_expense(
+    state: Arc<AppState>,
+    input: RecurringExpenseInput,
+) -> AppResult<RecurringExpense> {
+    let db = state.db.lock().await.clone();
+    let values = validate_input(&db, input).await?;
+    let id = Uuid::new_v4().to_string();
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO recurring_expenses (id, title, amount, category, payment_method, notes, \
+         frequency, day_of_month, day_of_week, start_date, end_date, is_active, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))",
+        [
+            id.clone().into(),
+            values.title.into(),
+            values.amount.into(),
+            values.category.into(),
+            values.payment_method.into(),
+            values.notes.into(),
+            values.frequency.as_str().into(),
+            values.day_of_month.into(),
+            values.day_of_week.into(),
+            values.start_date.into(),
+            values.end_date.into(),
+            values.is_active.into(),
+        ],
+    ))
+    .await?;
+    find_row(&db, &id).await?.into_recurring_expense()
+}
+
+/// Replaces a template. Occurrences already posted stay as they are; the new
+/// schedule applies from the day after the last posted one.
+#[instrument(skip(state))]
+pub async fn update_recurring_expense(
+    state: Arc<AppState>,
+    id: String,
+    input: RecurringExpenseInput,
+) -> AppResult<RecurringExpense> {
+    let db = state.db.lock().await.clone();
+    find_row(&db, &id).await?;
+    let values = validate_input(&db, input).await?;
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE recurring_expenses SET title = ?, amount = ?, category = ?, payment_method = ?, \
+         notes = ?, frequency = ?, day_of_month = ?, day_of_week = ?, start_date = ?, \
+         end_date = ?, is_active = ?, updated_at = datetime('now') WHERE id = ?",
+        [
+            values.title.into(),
+            values.amount.into(),
+            values.category.into(),
+            values.payment_method.into(),
+            values.notes.into(),
+            values.frequency.as_str().into(),
+            values.day_of_month.into(),
+            values.day_of_week.into(),
+            values.start_date.into(),
+            values.end_date.into(),
+            values.is_active.into(),
+            id.clone().into(),
+        ],
+    ))
+    .await?;
+    find_row(&db, &id).await?.into_recurring_expense()
+}
+
+/// Deletes a template. Expenses it already posted are kept.
+#[instrument(skip(state))]
+pub async fn delete_recurring_expense(state: Arc<AppState>, id: String) -> AppResult<()> {
+    let db = state.db.lock().await.clone();
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "DELETE FROM recurring_expenses WHERE id = ?",
+            [id.into()],
+        ))
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Recurring expense not found"));
+    }
+    Ok(())
+}
+
+/// Lists occurrences due within the next `days` days that have not been
+/// posted yet, including overdue ones the scheduler has not reached.
+#[instrument(skip(state))]
+pub async fn get_upcoming_recurring_expenses(
+    state: Arc<AppState>,
+    days: Option<i64>,
+) -> AppResult<Vec<UpcomingRecurringExpense>> {
+    let days = days
+        .unwrap_or(DEFAULT_UPCOMING_DAYS)
+        .clamp(1, MAX_UPCOMING_DAYS);
+    let until = today()
+        .checked_add_days(Days::new(days as u64))
+        .ok_or_else(|| AppError::internal("Date out of range"))?;
+
+    let db = state.db.lock().await.clone();
+    let mut upcoming = Vec::new();
+    for row in load_active_rows(&db).await? {
+        for due in row.pending_occurrences(until, MAX_UPCOMING_DAYS as usize)? {
+            upcoming.push(UpcomingRecurringExpense {
+                recurring_id: row.id.clone(),
+                title: row.title.clone(),
+                amount: row.amount,
+                category: row.category.clone(),
+                payment_method: row.payment_method.clone(),
+                due_date: due.format(DATE_FORMAT).to_string(),
+            });
+        }
+    }
+    upcoming.sort_by(|a, b| {
+        a.due_date
+            .cmp(&b.due_date)
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+    });
+    Ok(upcoming)
+}
+
+/// Posts one occurrence unless it already was. The occurrence is claimed in
+/// `recurring_expense_runs` first, so a retry reuses the same expense id.
+async fn post_occurrence(
+    state: &Arc<AppState>,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    row: &RecurringExpenseRow,
+    due: &str,
+) -> AppResult<bool> {
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT OR IGNORE INTO recurring_expense_runs (recurring_id, due_date, expense_id) \
+         VALUES (?, ?, ?)",
+        [
+            row.id.clone().into(),
+            due.into(),
+            Uuid::new_v4().to_string().into(),
+        ],
+    ))
+    .await?;
+    let run = RunRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT expense_id FROM recurring_expense_runs WHERE recurring_id = ? AND due_date = ?",
+        [row.id.clone().into(), due.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::internal("Recurring expense run was not recorded"))?;
+
+    // Deleted expenses count as posted, so deleting one does not bring it back.
+    let exists = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT 1 AS found FROM expenses WHERE id = ?",
+            [run.expense_id.clone().into()],
+        ))
+        .await?
+        .is_some();
+    if exists {
+        return Ok(false);
+    }
+
+    create_expense(
+        state.clone(),
+        app,
+        row.title.clone(),
+        row.amount,
+        row.category.clone(),
+        Some(due.to_string()),
+        row.payment_method.clone(),
+        row.notes.clone(),
+        Some(run.expense_id),
+        None,
+    )
+    .await?;
+    Ok(true)
+}
+
+async fn generate_for_template(
+    state: &Arc<AppState>,
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    row: &RecurringExpenseRow,
+    until: NaiveDate,
+) -> AppResult<u64> {
+    let mut posted = 0;
+    for due in row.pending_occurrences(until, MAX_CATCH_UP)? {
+        let due = due.format(DATE_FORMAT).to_string();
+        if post_occurrence(state, app, db, row, &due).await? {
+            posted += 1;
+        }
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE recurring_expenses SET last_generated_date = ? WHERE id = ?",
+            [due.into(), row.id.clone().into()],
+        ))
+        .await?;
+    }
+    Ok(posted)
+}
+
+/// Posts every occurrence of the active templates that is due by today and
+/// returns how many expenses were created. Safe to run any number of times.
+#[instrument(skip(state, app))]
+pub async fn generate_recurring_expenses(state: Arc<AppState>, app: &AppHandle) -> AppResult<u64> {
+    let _guard = GENERATION_LOCK.lock().await;
+    let db = state.db.lock().await.clone();
+    let until = today();
+
+    let mut posted = 0;
+    for row in load_active_rows(&db).await? {
+        match generate_for_template(&state, app, &db, &row, until).await {
+            Ok(count) => posted += count,
+            Err(err) => {
+                tracing::warn!("Failed to post recurring expense {}: {err}", row.id);
+            }
+        }
+    }
+    Ok(posted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, DATE_FORMAT).unwrap()
+    }
+
+    fn dates(values: &[&str]) -> Vec<NaiveDate> {
+        values.iter().map(|value| date(value)).collect()
+    }
+
+    fn row(frequency: &str, day: i64, start_date: &str) -> RecurringExpenseRow {
+        RecurringExpenseRow {
+            id: "r1".into(),
+            title: "Rent".into(),
+            amount: 100.0,
+            category: None,
+            payment_method: None,
+            notes: None,
+            frequency: frequency.into(),
+            day_of_month: (frequency == "monthly").then_some(day),
+            day_of_week: (frequency == "weekly").then_some(day),
+            start_date: start_date.into(),
+            end_date: None,
+            is_active: true,
+            last_generated_date: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn monthly_day_is_clamped_to_short_months() {
+        let schedule = Schedule::Monthly { day: 31 };
+        assert_eq!(
+            schedule.next_on_or_after(date("2026-02-01")),
+            Some(date("2026-02-28"))
+        );
+        assert_eq!(
+            schedule.next_on_or_after(date("2028-02-10")),
+            Some(date("2028-02-29"))
+        );
+        assert_eq!(
+            schedule.next_on_or_after(date("2026-04-30")),
+            Some(date("2026-04-30"))
+        );
+    }
+
+    #[test]
+    fn monthly_rolls_over_to_next_month_and_year() {
+        let schedule = Schedule::Monthly { day: 15 };
+        assert_eq!(
+            schedule.next_on_or_after(date("2026-03-15")),
+            Some(date("2026-03-15"))
+        );
+        assert_eq!(
+            schedule.next_on_or_after(date("2026-12-16")),
+            Some(date("2027-01-15"))
+        );
+    }
+
+    #[test]
+    fn weekly_finds_the_next_weekday() {
+        // 2026-03-14 is a Saturday.
+        let sunday = Schedule::Weekly { weekday: 0 };
+        assert_eq!(
+            sunday.next_on_or_after(date("2026-03-14")),
+            Some(date("2026-03-15"))
+        );
+        let saturday = Schedule::Weekly { weekday: 6 };
+        assert_eq!(
+            saturday.next_on_or_after(date("2026-03-14")),
+            Some(date("2026-03-14"))
+        );
+    }
+
+    #[test]
+    fn pending_occurrences_start_at_the_start_date() {
+        let row = row("monthly", 31, "2026-01-15");
+        assert_eq!(
+            row.pending_occurrences(date("2026-04-30"), 10).unwrap(),
+            dates(&["2026-01-31", "2026-02-28", "2026-03-31", "2026-04-30"])
+        );
+    }
+
+    #[test]
+    fn pending_occurrences_resume_after_the_last_posted_one() {
+        let mut row = row("weekly", 1, "2026-03-01");
+        row.last_generated_date = Some("2026-03-09".into());
+        assert_eq!(
+            row.pending_occurrences(date("2026-03-31"), 10).unwrap(),
+            dates(&["2026-03-16", "2026-03-23", "2026-03-30"])
+        );
+    }
+
+    #[test]
+    fn pending_occurrences_stop_at_end_date_and_limit() {
+        let mut row = row("weekly", 1, "2026-03-01");
+        row.end_date = Some("2026-03-20".into());
+        assert_eq!(
+            row.pending_occurrences(date("2026-12-31"), 10).unwrap(),
+            dates(&["2026-03-02", "2026-03-09", "2026-03-16"])
+        );
+        assert_eq!(
+            row.pending_occurrences(date("2026-12-31"), 2).unwrap(),
+            dates(&["2026-03-02", "2026-03-09"])
+        );
+    }
+
+    #[test]
+    fn invalid_schedule_is_an_error() {
+        let mut row = row("monthly", 1, "2026-03-01");
+        row.day_of_month = None;
+        assert!(row.pending_occurrences(date("2026-12-31"), 10).is_err());
+    }
+}
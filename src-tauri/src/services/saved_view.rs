@@ -0,0 +1,446 @@
+use std::sync::Arc;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CustomerListFilter, ExpenseListFilter, OrderListFilter, PaginatedCustomers, PaginatedExpenses,
+    PaginatedOrders,
+};
+use crate::services::customer::{build_customer_list_query, get_customers_paginated};
+use crate::services::expense::{build_expense_list_query, get_expenses_paginated};
+use crate::services::export::{customer_column_keys, expense_column_keys, order_column_keys};
+use crate::services::order::{build_order_list_query, get_orders_paginated};
+use crate::state::AppState;
+
+const MAX_NAME_LEN: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedViewEntity {
+    Orders,
+    Customers,
+    Expenses,
+}
+
+impl SavedViewEntity {
+    fn as_str(self) -> &'static str {
+        match self {
+            SavedViewEntity::Orders => "orders",
+            SavedViewEntity::Customers => "customers",
+            SavedViewEntity::Expenses => "expenses",
+        }
+    }
+
+    fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "orders" => Ok(SavedViewEntity::Orders),
+            "customers" => Ok(SavedViewEntity::Customers),
+            "expenses" => Ok(SavedViewEntity::Expenses),
+            _ => Err(AppError::internal(format!(
+                "Unknown saved view entity: {value}"
+            ))),
+        }
+    }
+
+    /// Checks the filter against the list it belongs to and returns it in
+    /// canonical form, so a saved view can't fail later on a bad key.
+    fn validate_filter(self, filter: JsonValue) -> AppResult<JsonValue> {
+        fn parse<T: serde::de::DeserializeOwned>(filter: JsonValue) -> AppResult<T> {
+            serde_json::from_value(filter)
+                .map_err(|e| AppError::invalid_input(format!("Invalid saved view filter: {e}")))
+        }
+
+        let canonical = match self {
+            SavedViewEntity::Orders => {
+                let filter: OrderListFilter = parse(filter)?;
+                build_order_list_query(&filter)?;
+                serde_json::to_value(filter)?
+            }
+            SavedViewEntity::Customers => {
+                let filter: CustomerListFilter = parse(filter)?;
+                build_customer_list_query(&filter)?;
+                serde_json::to_value(filter)?
+            }
+            SavedViewEntity::Expenses => {
+                let filter: ExpenseListFilter = parse(filter)?;
+                build_expense_list_query(&filter)?;
+                serde_json::to_value(filter)?
+            }
+        };
+        Ok(canonical)
+    }
+
+    /// Validates a column set against the export columns; empty means all.
+    fn validate_columns(self, columns: Option<Vec<String>>) -> AppResult<Option<Vec<String>>> {
+        let Some(columns) = columns.filter(|c| !c.is_empty()) else {
+            return Ok(None);
+        };
+        let known = match self {
+            SavedViewEntity::Orders => order_column_keys(),
+            SavedViewEntity::Customers => customer_column_keys(),
+            SavedViewEntity::Expenses => expense_column_keys(),
+        };
+        let columns: Vec<String> = columns.into_iter().map(|c| c.trim().to_string()).collect();
+        if let Some(unknown) = columns.iter().find(|c| !known.contains(&c.as_str())) {
+            return Err(AppError::invalid_input(format!(
+                "Unknown column: {unknown}"
+            )));
+        }
+        Ok(Some(columns))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedView {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub entity: SavedViewEntity,
+    /// The list filter of `entity`, including search and sort.
+    pub filter: JsonValue,
+    /// Column keys in display order; `None` shows all columns.
+    pub columns: Option<Vec<String>>,
+    pub is_shared: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// One page of a saved view's list; the shape follows `view.entity`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SavedViewResults {
+    Orders(PaginatedOrders),
+    Customers(PaginatedCustomers),
+    Expenses(PaginatedExpenses),
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedViewRun {
+    pub view: SavedView,
+    pub results: SavedViewResults,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SavedViewRow {
+    id: String,
+    owner_id: String,
+    name: String,
+    entity: String,
+    filter: String,
+    columns: Option<String>,
+    is_shared: Option<bool>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl TryFrom<SavedViewRow> for SavedView {
+    type Error = AppError;
+
+    fn try_from(row: SavedViewRow) -> AppResult<Self> {
+        Ok(SavedView {
+            id: row.id,
+            owner_id: row.owner_id,
+            name: row.name,
+            entity: SavedViewEntity::parse(&row.entity)?,
+            filter: serde_json::from_str(&row.filter)?,
+            columns: row
+                .columns
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()?,
+            is_shared: row.is_shared.unwrap_or(false),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+const SAVED_VIEW_SELECT: &str = "SELECT id, owner_id, name, entity, filter, columns, is_shared, \
+     created_at, updated_at FROM saved_views";
+
+fn normalize_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Saved view name is required"));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::invalid_input(format!(
+            "Saved view name must be at most {MAX_NAME_LEN} characters"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn require_user(user_id: &str) -> AppResult<()> {
+    if user_id.trim().is_empty() {
+        return Err(AppError::invalid_input("A signed-in user is required"));
+    }
+    Ok(())
+}
+
+async fn ensure_unique_name(
+    db: &DatabaseConnection,
+    owner_id: &str,
+    entity: SavedViewEntity,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> AppResult<()> {
+    let existing = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT id FROM saved_views WHERE owner_id = ? AND entity = ? \
+             AND name = ? COLLATE NOCASE AND id <> ?",
+            [
+                owner_id.into(),
+                entity.as_str().into(),
+                name.into(),
+                exclude_id.unwrap_or_default().into(),
+            ],
+        ))
+        .await?;
+    if existing.is_some() {
+        return Err(AppError::invalid_input(format!(
+            "A saved view named \"{name}\" already exists"
+        )));
+    }
+    Ok(())
+}
+
+/// Loads a view visible to `user_id`: their own, or one shared by someone else.
+async fn load_visible_view(
+    db: &DatabaseConnection,
+    user_id: &str,
+    id: &str,
+) -> AppResult<SavedView> {
+    SavedViewRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!("{SAVED_VIEW_SELECT} WHERE id = ? AND (owner_id = ? OR is_shared = 1)"),
+        [id.into(), user_id.into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| AppError::not_found("Saved view not found"))?
+    .try_into()
+}
+
+/// Loads a view owned by `user_id`; shared views can only be changed by their owner.
+async fn load_owned_view(db: &DatabaseConnection, user_id: &str, id: &str) -> AppResult<SavedView> {
+    let view = load_visible_view(db, user_id, id).await?;
+    if view.owner_id != user_id {
+        return Err(AppError::invalid_input(
+            "Only the owner can change a shared view",
+        ));
+    }
+    Ok(view)
+}
+
+/// Saves a named filter, sort and column set for one of the lists.
+#[instrument(skip(state, filter))]
+pub async fn create_saved_view(
+    state: Arc<AppState>,
+    user_id: String,
+    name: String,
+    entity: SavedViewEntity,
+    filter: JsonValue,
+    columns: Option<Vec<String>>,
+    is_shared: Option<bool>,
+) -> AppResult<SavedView> {
+    require_user(&user_id)?;
+    let name = normalize_name(&name)?;
+    let filter = entity.validate_filter(filter)?;
+    let columns = entity.validate_columns(columns)?;
+
+    let db = state.db.lock().await.clone();
+    ensure_unique_name(&db, &user_id, entity, &name, None).await?;
+
+    let id = Uuid::new_v4().to_string();
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "INSERT INTO saved_views (id, owner_id, name, entity, filter, columns, is_shared, \
+         created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))",
+        [
+            id.clone().into(),
+            user_id.clone().into(),
+            name.into(),
+            entity.as_str().into(),
+            filter.to_string().into(),
+            columns
+                .map(|c| serde_json::to_string(&c))
+                .transpose()?
+                .into(),
+            is_shared.unwrap_or(false).into(),
+        ],
+    ))
+    .await?;
+
+    load_visible_view(&db, &user_id, &id).await
+}
+
+/// Lists the user's own views and those shared by others, optionally for one list.
+#[instrument(skip(state))]
+pub async fn get_saved_views(
+    state: Arc<AppState>,
+    user_id: String,
+    entity: Option<SavedViewEntity>,
+) -> AppResult<Vec<SavedView>> {
+    require_user(&user_id)?;
+    let db = state.db.lock().await.clone();
+    let statement = match entity {
+        Some(entity) => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "{SAVED_VIEW_SELECT} WHERE (owner_id = ? OR is_shared = 1) AND entity = ? \
+                 ORDER BY owner_id = ? DESC, name COLLATE NOCASE ASC"
+            ),
+            [
+                user_id.clone().into(),
+                entity.as_str().into(),
+                user_id.into(),
+            ],
+        ),
+        None => Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "{SAVED_VIEW_SELECT} WHERE owner_id = ? OR is_shared = 1 \
+                 ORDER BY entity ASC, owner_id = ? DESC, name COLLATE NOCASE ASC"
+            ),
+            [user_id.clone().into(), user_id.into()],
+        ),
+    };
+
+    SavedViewRow::find_by_statement(statement)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(SavedView::try_from)
+        .collect()
+}
+
+/// Updates the given fields of a view owned by the user.
+#[instrument(skip(state, filter))]
+pub async fn update_saved_view(
+    state: Arc<AppState>,
+    user_id: String,
+    id: String,
+    name: Option<String>,
+    filter: Option<JsonValue>,
+    columns: Option<Vec<String>>,
+    is_shared: Option<bool>,
+) -> AppResult<SavedView> {
+    require_user(&user_id)?;
+    let db = state.db.lock().await.clone();
+    let view = load_owned_view(&db, &user_id, &id).await?;
+
+    let name = match name {
+        Some(name) => {
+            let name = normalize_name(&name)?;
+            ensure_unique_name(&db, &user_id, view.entity, &name, Some(&id)).await?;
+            name
+        }
+        None => view.name,
+    };
+    let filter = match filter {
+        Some(filter) => view.entity.validate_filter(filter)?,
+        None => view.filter,
+    };
+    let columns = match columns {
+        Some(columns) => view.entity.validate_columns(Some(columns))?,
+        None => view.columns,
+    };
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE saved_views SET name = ?, filter = ?, columns = ?, is_shared = ?, \
+         updated_at = datetime('now') WHERE id = ?",
+        [
+            name.into(),
+            filter.to_string().into(),
+            columns
+                .map(|c| serde_json::to_string(&c))
+                .transpose()?
+                .into(),
+            is_shared.unwrap_or(view.is_shared).into(),
+            id.clone().into(),
+        ],
+    ))
+    .await?;
+
+    load_visible_view(&db, &user_id, &id).await
+}
+
+/// Deletes a view owned by the user.
+#[instrument(skip(state))]
+pub async fn delete_saved_view(state: Arc<AppState>, user_id: String, id: String) -> AppResult<()> {
+    require_user(&user_id)?;
+    let db = state.db.lock().await.clone();
+    load_owned_view(&db, &user_id, &id).await?;
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "DELETE FROM saved_views WHERE id = ?",
+        [id.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Runs a saved view through its list's paginated query.
+#[instrument(skip(state))]
+pub async fn run_saved_view(
+    state: Arc<AppState>,
+    user_id: String,
+    id: String,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> AppResult<SavedViewRun> {
+    require_user(&user_id)?;
+    let view = {
+        let db = state.db.lock().await.clone();
+        load_visible_view(&db, &user_id, &id).await?
+    };
+
+    let results = match view.entity {
+        SavedViewEntity::Orders => {
+            let f: OrderListFilter = serde_json::from_value(view.filter.clone())?;
+            SavedViewResults::Orders(
+                get_orders_paginated(
+                    state,
+                    page,
+                    page_size,
+                    f.search_key,
+                    f.search_term,
+                    f.status_filter,
+                    f.sort_by,
+                    f.sort_order,
+                    f.conditions,
+                )
+                .await?,
+            )
+        }
+        SavedViewEntity::Customers => {
+            let f: CustomerListFilter = serde_json::from_value(view.filter.clone())?;
+            SavedViewResults::Customers(
+                get_customers_paginated(
+                    state,
+                    page,
+                    page_size,
+                    f.search_key,
+                    f.search_term,
+                    f.sort_by,
+                    f.sort_order,
+                )
+                .await?,
+            )
+        }
+        SavedViewEntity::Expenses => {
+            let f: ExpenseListFilter = serde_json::from_value(view.filter.clone())?;
+            SavedViewResults::Expenses(get_expenses_paginated(state, page, page_size, f).await?)
+        }
+    };
+
+    Ok(SavedViewRun { view, results })
+}
@@ -0,0 +1,588 @@
+use std::sync::Arc;
+
+use chrono::Local;
+use sea_orm::{ConnectionTrait, DatabaseBackend, FromQueryResult, Statement};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::Order;
+use crate::services::order::{normalize_order_status, normalize_order_status_filter};
+use crate::services::settings::get_app_settings;
+use crate::state::AppState;
+use crate::sync::enqueue_sync;
+
+/// Order fields shown to the operator after a scan.
+#[derive(Debug, Clone, Serialize, FromQueryResult)]
+pub struct ScannedOrder {
+    pub id: String,
+    pub order_id: Option<String>,
+    pub customer_name: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Outcome of a single scan. `outcome` is one of `updated`, `unchanged`,
+/// `skipped` (the order is cancelled or already past the target status),
+/// `duplicate` or `unknown`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanResult {
+    pub code: String,
+    pub outcome: String,
+    pub previous_status: Option<String>,
+    pub order: Option<ScannedOrder>,
+}
+
+/// In-memory state of a batch scan, kept in `AppState` until it is closed.
+#[derive(Debug, Clone)]
+pub struct ScanSession {
+    started_at: String,
+    target_status: Option<String>,
+    date_column: Option<&'static str>,
+    expected: Vec<ScannedOrder>,
+    scanned: Vec<ScannedOrder>,
+    unexpected: Vec<ScannedOrder>,
+    unknown_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScanSessionReport {
+    pub session_id: String,
+    pub started_at: String,
+    pub target_status: Option<String>,
+    pub expected_count: usize,
+    pub scanned: Vec<ScannedOrder>,
+    pub missing: Vec<ScannedOrder>,
+    /// Orders that resolved but were not part of the expected set.
+    pub unexpected: Vec<ScannedOrder>,
+    pub unknown_codes: Vec<String>,
+    pub last_result: Option<ScanResult>,
+}
+
+impl ScanSession {
+    fn report(&self, session_id: &str, last_result: Option<ScanResult>) -> ScanSessionReport {
+        let missing = self
+            .expected
+            .iter()
+            .filter(|order| !self.scanned.iter().any(|s| s.id == order.id))
+            .cloned()
+            .collect();
+
+        ScanSessionReport {
+            session_id: session_id.to_string(),
+            started_at: self.started_at.clone(),
+            target_status: self.target_status.clone(),
+            expected_count: self.expected.len(),
+            scanned: self.scanned.clone(),
+            missing,
+            unexpected: self.unexpected.clone(),
+            unknown_codes: self.unknown_codes.clone(),
+            last_result,
+        }
+    }
+}
+
+/// Order lifecycle a scan moves forward through. Scans never move an order
+/// back, and never move it out of `cancelled`.
+const STATUS_FLOW: [&str; 4] = ["pending", "confirmed", "shipping", "completed"];
+
+/// Statuses an order may have for a scan to move it to `target_status`. The
+/// target itself is included so a scan can still stamp a missing date.
+fn scan_from_statuses(target_status: &str) -> &'static [&'static str] {
+    match target_status {
+        "cancelled" => &["pending", "confirmed", "shipping", "cancelled"],
+        _ => match STATUS_FLOW
+            .iter()
+            .position(|status| *status == target_status)
+        {
+            Some(index) => &STATUS_FLOW[..=index],
+            None => &[],
+        },
+    }
+}
+
+/// An order without a status is treated as `pending`.
+fn current_status(status: Option<&str>) -> &str {
+    status
+        .filter(|status| !status.is_empty())
+        .unwrap_or("pending")
+}
+
+/// Moves an order to `target_status` and stamps `date_column` with `today`
+/// when it is empty. The WHERE clause repeats the forward-only check so a
+/// concurrent edit cannot be overwritten. Returns whether a row changed.
+async fn transition_order<C: ConnectionTrait>(
+    db: &C,
+    order_id: &str,
+    target_status: &str,
+    date_column: Option<&str>,
+    today: &str,
+) -> AppResult<bool> {
+    let from_statuses = scan_from_statuses(target_status);
+    if from_statuses.is_empty() {
+        return Ok(false);
+    }
+    let placeholders = vec!["?"; from_statuses.len()].join(", ");
+    let status_guard = format!("COALESCE(NULLIF(status, ''), 'pending') IN ({placeholders})");
+
+    let (sql, mut values): (String, Vec<sea_orm::Value>) = match date_column {
+        Some(column) => (
+            format!(
+                "UPDATE orders SET status = ?, {column} = COALESCE(NULLIF({column}, ''), ?), \
+                 updated_at = datetime('now') \
+                 WHERE id = ? AND (COALESCE(status, '') != ? OR COALESCE({column}, '') = '') \
+                 AND {status_guard}"
+            ),
+            vec![
+                target_status.into(),
+                today.into(),
+                order_id.into(),
+                target_status.into(),
+            ],
+        ),
+        None => (
+            format!(
+                "UPDATE orders SET status = ?, updated_at = datetime('now') \
+                 WHERE id = ? AND COALESCE(status, '') != ? AND {status_guard}"
+            ),
+            vec![target_status.into(), order_id.into(), target_status.into()],
+        ),
+    };
+    values.extend(from_statuses.iter().map(|status| (*status).into()));
+
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            &sql,
+            values,
+        ))
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+const SCANNED_ORDER_SELECT: &str = "SELECT o.id, o.order_id, c.name as customer_name, o.status \
+     FROM orders o LEFT JOIN customers c ON o.customer_id = c.id";
+
+/// Date column stamped on scan, limited to the order date fields.
+fn scan_date_column(value: &str) -> AppResult<Option<&'static str>> {
+    match value.trim() {
+        "" => Ok(None),
+        "arrived_date" => Ok(Some("arrived_date")),
+        "shipment_date" => Ok(Some("shipment_date")),
+        "user_withdraw_date" => Ok(Some("user_withdraw_date")),
+        other => Err(AppError::invalid_input(format!(
+            "Unsupported scan date field: {other}"
+        ))),
+    }
+}
+
+/// Resolves a scanned barcode or QR payload to a live order by `order_id`
+/// (case-insensitive) or by internal `id`.
+pub async fn resolve_scanned_code<C: ConnectionTrait>(
+    db: &C,
+    code: &str,
+) -> AppResult<Option<ScannedOrder>> {
+    let order = ScannedOrder::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "{} WHERE o.deleted_at IS NULL AND (UPPER(o.order_id) = UPPER(?) OR o.id = ?) \
+             ORDER BY CASE WHEN UPPER(o.order_id) = UPPER(?) THEN 0 ELSE 1 END LIMIT 1",
+            SCANNED_ORDER_SELECT
+        ),
+        [code.into(), code.into(), code.into()],
+    ))
+    .one(db)
+    .await?;
+
+    Ok(order)
+}
+
+/// Scanners often append CR/LF or pad the payload; keep only the code.
+fn clean_code(code: &str) -> AppResult<String> {
+    let code: String = code.chars().filter(|c| !c.is_control()).collect();
+    let code = code.trim().to_string();
+    if code.is_empty() {
+        return Err(AppError::invalid_input("Scanned code is empty"));
+    }
+    Ok(code)
+}
+
+/// Target status for a scan: the explicit one, else the `scan_target_status`
+/// setting. An empty value means lookup only.
+fn resolve_target_status(
+    app: &AppHandle,
+    target_status: Option<String>,
+) -> AppResult<(Option<String>, Option<&'static str>)> {
+    let settings = get_app_settings(app.clone())?;
+    let target_status = target_status.unwrap_or(settings.scan_target_status);
+    Ok((
+        normalize_order_status(Some(target_status))?,
+        scan_date_column(&settings.scan_date_field)?,
+    ))
+}
+
+async fn apply_scan(
+    state: &Arc<AppState>,
+    app: &AppHandle,
+    code: String,
+    order: Option<ScannedOrder>,
+    target_status: Option<&str>,
+    date_column: Option<&str>,
+) -> AppResult<ScanResult> {
+    let Some(order) = order else {
+        return Ok(ScanResult {
+            code,
+            outcome: "unknown".to_string(),
+            previous_status: None,
+            order: None,
+        });
+    };
+
+    let previous_status = order.status.clone();
+    let Some(target_status) = target_status else {
+        return Ok(ScanResult {
+            code,
+            outcome: "unchanged".to_string(),
+            previous_status,
+            order: Some(order),
+        });
+    };
+    if !scan_from_statuses(target_status).contains(&current_status(previous_status.as_deref())) {
+        return Ok(ScanResult {
+            code,
+            outcome: "skipped".to_string(),
+            previous_status,
+            order: Some(order),
+        });
+    }
+
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let db = state.db.lock().await.clone();
+    if !transition_order(&db, &order.id, target_status, date_column, &today).await? {
+        return Ok(ScanResult {
+            code,
+            outcome: "unchanged".to_string(),
+            previous_status,
+            order: Some(order),
+        });
+    }
+
+    if let Some(record) = Order::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "SELECT * FROM orders WHERE id = ?",
+        [order.id.clone().into()],
+    ))
+    .one(&db)
+    .await?
+    {
+        let pool = state.pool.lock().await;
+        enqueue_sync(
+            &pool,
+            app,
+            "orders",
+            "UPDATE",
+            &order.id,
+            serde_json::json!(record),
+        )
+        .await;
+    }
+
+    Ok(ScanResult {
+        code,
+        outcome: "updated".to_string(),
+        previous_status,
+        order: Some(ScannedOrder {
+            status: Some(target_status.to_string()),
+            ..order
+        }),
+    })
+}
+
+/// Resolves a scanned code to an order and applies the configured status
+/// transition, stamping the configured date field.
+#[instrument(skip(state, app))]
+pub async fn scan_order_code(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    code: String,
+    target_status: Option<String>,
+) -> AppResult<ScanResult> {
+    let code = clean_code(&code)?;
+    let (target_status, date_column) = resolve_target_status(app, target_status)?;
+    let db = state.db.lock().await.clone();
+    let order = resolve_scanned_code(&db, &code).await?;
+    apply_scan(
+        &state,
+        app,
+        code,
+        order,
+        target_status.as_deref(),
+        date_column,
+    )
+    .await
+}
+
+/// Starts a batch scan against the given orders, or every order with
+/// `expected_status` when no ids are given.
+#[instrument(skip(state, app))]
+pub async fn start_scan_session(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    expected_order_ids: Option<Vec<String>>,
+    expected_status: Option<String>,
+    target_status: Option<String>,
+) -> AppResult<ScanSessionReport> {
+    let (target_status, date_column) = resolve_target_status(app, target_status)?;
+    let expected_ids: Vec<String> = expected_order_ids
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    let expected_status = normalize_order_status_filter(expected_status)?;
+
+    let db = state.db.lock().await.clone();
+    let expected = if !expected_ids.is_empty() {
+        let placeholders = vec!["?"; expected_ids.len()].join(", ");
+        ScannedOrder::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "{} WHERE o.deleted_at IS NULL AND o.id IN ({}) ORDER BY o.order_id ASC",
+                SCANNED_ORDER_SELECT, placeholders
+            ),
+            expected_ids.into_iter().map(Into::into).collect::<Vec<_>>(),
+        ))
+        .all(&db)
+        .await?
+    } else if let Some(status) = expected_status {
+        ScannedOrder::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!(
+                "{} WHERE o.deleted_at IS NULL AND o.status = ? ORDER BY o.order_id ASC",
+                SCANNED_ORDER_SELECT
+            ),
+            [status.into()],
+        ))
+        .all(&db)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let session_id = Uuid::new_v4().to_string();
+    let session = ScanSession {
+        started_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        target_status,
+        date_column,
+        expected,
+        scanned: Vec::new(),
+        unexpected: Vec::new(),
+        unknown_codes: Vec::new(),
+    };
+    let report = session.report(&session_id, None);
+    state.scan_sessions.lock().await.insert(session_id, session);
+
+    Ok(report)
+}
+
+/// Records a scan in a session, applying the session's status transition.
+#[instrument(skip(state, app))]
+pub async fn scan_in_session(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    session_id: String,
+    code: String,
+) -> AppResult<ScanSessionReport> {
+    let code = clean_code(&code)?;
+    let db = state.db.lock().await.clone();
+    let order = resolve_scanned_code(&db, &code).await?;
+
+    let (target_status, date_column, duplicate) = {
+        let sessions = state.scan_sessions.lock().await;
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| AppError::not_found("Scan session not found"))?;
+        let duplicate = order.as_ref().is_some_and(|order| {
+            session
+                .scanned
+                .iter()
+                .chain(session.unexpected.iter())
+                .any(|s| s.id == order.id)
+        });
+        (
+            session.target_status.clone(),
+            session.date_column,
+            duplicate,
+        )
+    };
+
+    let result = if duplicate {
+        ScanResult {
+            code,
+            outcome: "duplicate".to_string(),
+            previous_status: order.as_ref().and_then(|o| o.status.clone()),
+            order,
+        }
+    } else {
+        apply_scan(
+            &state,
+            app,
+            code,
+            order,
+            target_status.as_deref(),
+            date_column,
+        )
+        .await?
+    };
+
+    let mut sessions = state.scan_sessions.lock().await;
+    let session = sessions
+        .get_mut(&session_id)
+        .ok_or_else(|| AppError::not_found("Scan session not found"))?;
+    match (&result.order, result.outcome.as_str()) {
+        (_, "duplicate") => {}
+        (Some(order), _) => {
+            if session.expected.iter().any(|e| e.id == order.id) {
+                session.scanned.push(order.clone());
+            } else {
+                session.unexpected.push(order.clone());
+            }
+        }
+        (None, _) => {
+            if !session.unknown_codes.contains(&result.code) {
+                session.unknown_codes.push(result.code.clone());
+            }
+        }
+    }
+
+    Ok(session.report(&session_id, Some(result)))
+}
+
+/// Returns the current progress of a scan session.
+pub async fn get_scan_session(
+    state: Arc<AppState>,
+    session_id: String,
+) -> AppResult<ScanSessionReport> {
+    state
+        .scan_sessions
+        .lock()
+        .await
+        .get(&session_id)
+        .map(|session| session.report(&session_id, None))
+        .ok_or_else(|| AppError::not_found("Scan session not found"))
+}
+
+/// Ends a scan session and returns its final report.
+pub async fn close_scan_session(
+    state: Arc<AppState>,
+    session_id: String,
+) -> AppResult<ScanSessionReport> {
+    state
+        .scan_sessions
+        .lock()
+        .await
+        .remove(&session_id)
+        .map(|session| session.report(&session_id, None))
+        .ok_or_else(|| AppError::not_found("Scan session not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::DatabaseConnection;
+
+    use super::*;
+
+    #[derive(Debug, FromQueryResult)]
+    struct Row {
+        status: Option<String>,
+        arrived_date: Option<String>,
+    }
+
+    async fn seeded_db() -> DatabaseConnection {
+        let state = AppState::for_tests().await;
+        let db = state.db.lock().await.clone();
+        db.execute_unprepared(
+            "INSERT INTO orders (id, order_id, status, arrived_date) VALUES \
+               ('pending', 'ORD-1', 'pending', NULL), \
+               ('blank', 'ORD-2', NULL, NULL), \
+               ('shipping', 'ORD-3', 'shipping', NULL), \
+               ('stamped', 'ORD-4', 'shipping', '2026-01-01'), \
+               ('completed', 'ORD-5', 'completed', NULL), \
+               ('cancelled', 'ORD-6', 'cancelled', NULL);",
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn row(db: &DatabaseConnection, id: &str) -> Row {
+        Row::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT status, arrived_date FROM orders WHERE id = ?",
+            [id.into()],
+        ))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn scans_only_move_orders_forward() {
+        assert_eq!(scan_from_statuses("pending"), ["pending"]);
+        assert_eq!(
+            scan_from_statuses("shipping"),
+            ["pending", "confirmed", "shipping"]
+        );
+        assert!(!scan_from_statuses("completed").contains(&"cancelled"));
+        assert!(!scan_from_statuses("cancelled").contains(&"completed"));
+    }
+
+    #[tokio::test]
+    async fn transition_moves_earlier_orders_and_stamps_the_date() {
+        let db = seeded_db().await;
+        for id in ["pending", "blank", "shipping"] {
+            let updated = transition_order(&db, id, "shipping", Some("arrived_date"), "2026-03-14")
+                .await
+                .unwrap();
+            assert!(updated, "{id} should move to shipping");
+            let row = row(&db, id).await;
+            assert_eq!(row.status.as_deref(), Some("shipping"));
+            assert_eq!(row.arrived_date.as_deref(), Some("2026-03-14"));
+        }
+    }
+
+    #[tokio::test]
+    async fn transition_skips_later_cancelled_and_stamped_orders() {
+        let db = seeded_db().await;
+        for (id, status) in [
+            ("completed", "completed"),
+            ("cancelled", "cancelled"),
+            ("stamped", "shipping"),
+        ] {
+            let updated = transition_order(&db, id, "shipping", Some("arrived_date"), "2026-03-14")
+                .await
+                .unwrap();
+            assert!(!updated, "{id} should not change");
+            assert_eq!(row(&db, id).await.status.as_deref(), Some(status));
+        }
+        assert_eq!(
+            row(&db, "stamped").await.arrived_date.as_deref(),
+            Some("2026-01-01")
+        );
+    }
+
+    #[tokio::test]
+    async fn transition_never_moves_an_order_back() {
+        let db = seeded_db().await;
+        let updated = transition_order(&db, "shipping", "confirmed", None, "2026-03-14")
+            .await
+            .unwrap();
+        assert!(!updated);
+        assert_eq!(
+            row(&db, "shipping").await.status.as_deref(),
+            Some("shipping")
+        );
+    }
+}
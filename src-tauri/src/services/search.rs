@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, FromQueryResult, Statement, TransactionTrait, Value,
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::error::AppResult;
+use crate::services::phone::phone_search_digits;
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+
+/// The trigram tokenizer cannot match anything shorter than this.
+const MIN_MATCH_CHARS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEntityType {
+    Customer,
+    Order,
+    Expense,
+}
+
+impl SearchEntityType {
+    fn as_str(self) -> &'static str {
+        match self {
+            SearchEntityType::Customer => "customer",
+            SearchEntityType::Order => "order",
+            SearchEntityType::Expense => "expense",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "customer" => Some(SearchEntityType::Customer),
+            "order" => Some(SearchEntityType::Order),
+            "expense" => Some(SearchEntityType::Expense),
+            _ => None,
+        }
+    }
+}
+
+/// One ranked search result. Lower `rank` is a better match.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub entity_type: SearchEntityType,
+    pub entity_id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Excerpt of the indexed text around the match, if any.
+    pub snippet: Option<String>,
+    pub rank: f64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SearchRow {
+    entity_type: String,
+    entity_id: String,
+    title: Option<String>,
+    subtitle: Option<String>,
+    snippet: Option<String>,
+    rank: f64,
+}
+
+/// Rewrites a typed phone number ("09 791-234 567" as a single term) into the
+/// digits stored in `phone_e164`; other terms are returned unchanged.
+fn normalize_term(term: &str) -> String {
+    let phone_like = term
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '(' | ')'));
+    let digit_count = term.chars().filter(char::is_ascii_digit).count();
+    if phone_like && digit_count >= 7 {
+        phone_search_digits(term).unwrap_or_else(|| term.to_string())
+    } else {
+        term.to_string()
+    }
+}
+
+/// Builds the FTS5 MATCH expression for the searchable terms and `LIKE`
+/// fallbacks for terms too short for the trigram index. All terms must match.
+fn build_conditions(query: &str) -> (Option<String>, Vec<String>) {
+    let mut phrases = Vec::new();
+    let mut short_terms = Vec::new();
+    for term in query.split_whitespace().map(normalize_term) {
+        if term.chars().count() >= MIN_MATCH_CHARS {
+            phrases.push(format!("\"{}\"", term.replace('"', "\"\"")));
+        } else {
+            short_terms.push(term);
+        }
+    }
+    let match_expr = (!phrases.is_empty()).then(|| phrases.join(" AND "));
+    (match_expr, short_terms)
+}
+
+/// Searches customers, orders (including their item URLs) and expenses at once.
+#[instrument(skip(state))]
+pub async fn global_search(
+    state: Arc<AppState>,
+    query: String,
+    entity_types: Option<Vec<SearchEntityType>>,
+    limit: Option<u64>,
+) -> AppResult<Vec<SearchHit>> {
+    let (match_expr, short_terms) = build_conditions(&query);
+    if match_expr.is_none() && short_terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conditions = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+    if let Some(expr) = &match_expr {
+        conditions.push("search_index MATCH ?".to_string());
+        params.push(expr.clone().into());
+    }
+    for term in short_terms {
+        conditions.push("(title LIKE ? OR body LIKE ?)".to_string());
+        let pattern = format!("%{term}%");
+        params.push(pattern.clone().into());
+        params.push(pattern.into());
+    }
+    if let Some(types) = entity_types.filter(|types| !types.is_empty()) {
+        let placeholders = vec!["?"; types.len()].join(", ");
+        conditions.push(format!("entity_type IN ({placeholders})"));
+        params.extend(types.into_iter().map(|t| t.as_str().into()));
+    }
+
+    // bm25 weights follow the column order; a title hit counts ten times a body hit.
+    let (rank_sql, snippet_sql, order_sql) = if match_expr.is_some() {
+        (
+            "bm25(search_index, 0.0, 0.0, 10.0, 0.0, 1.0)",
+            "snippet(search_index, 4, '', '', '…', 32)",
+            "rank ASC",
+        )
+    } else {
+        ("0.0", "NULL", "entity_type ASC, title ASC")
+    };
+    let sql = format!(
+        "SELECT entity_type, entity_id, title, NULLIF(subtitle, '') AS subtitle, \
+         {snippet_sql} AS snippet, {rank_sql} AS rank \
+         FROM search_index WHERE {} ORDER BY {order_sql} LIMIT ?",
+        conditions.join(" AND ")
+    );
+    params.push((limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as i64).into());
+
+    let db = state.db.lock().await.clone();
+    let rows = SearchRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        sql,
+        params,
+    ))
+    .all(&db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(SearchHit {
+                entity_type: SearchEntityType::parse(&row.entity_type)?,
+                entity_id: row.entity_id,
+                title: row.title.unwrap_or_default(),
+                subtitle: row.subtitle,
+                snippet: row.snippet.filter(|s| !s.is_empty()),
+                rank: row.rank,
+            })
+        })
+        .collect())
+}
+
+/// Rebuilds the search index from scratch, e.g. after restoring a database.
+#[instrument(skip(state))]
+pub async fn rebuild_search_index(state: Arc<AppState>) -> AppResult<u64> {
+    let db = state.db.lock().await.clone();
+    let txn = db.begin().await?;
+    txn.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "DELETE FROM search_index",
+    ))
+    .await?;
+    let inserted = txn
+        .execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO search_index (entity_type, entity_id, title, subtitle, body) \
+             SELECT entity_type, entity_id, title, subtitle, body FROM search_documents",
+        ))
+        .await?
+        .rows_affected();
+    txn.commit().await?;
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_terms_fall_back_to_like() {
+        let (match_expr, short_terms) = build_conditions("aung 09 \"x");
+        assert_eq!(match_expr.as_deref(), Some("\"aung\""));
+        assert_eq!(short_terms, ["09", "\"x"]);
+    }
+
+    #[test]
+    fn typed_phone_numbers_search_their_digits() {
+        assert_eq!(normalize_term("09-791-234-567"), "9791234567");
+        assert_eq!(normalize_term("ORD-0001"), "ORD-0001");
+    }
+
+    #[tokio::test]
+    async fn rebuilt_index_finds_customers_and_their_orders() {
+        let state = AppState::for_tests().await;
+        {
+            let db = state.db.lock().await.clone();
+            db.execute_unprepared(
+                "INSERT INTO customers (id, customer_id, name, city) \
+                   VALUES ('c1', 'SSC-1', 'Aung Aung', 'Yangon'); \
+                 INSERT INTO orders (id, order_id, customer_id) VALUES ('o1', 'SSO-1', 'c1'); \
+                 DELETE FROM search_index;",
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(rebuild_search_index(state.clone()).await.unwrap(), 2);
+        let hits = global_search(state.clone(), "aung".into(), None, None)
+            .await
+            .unwrap();
+        let mut found: Vec<_> = hits
+            .iter()
+            .map(|hit| (hit.entity_type, hit.entity_id.as_str()))
+            .collect();
+        found.sort_by_key(|(_, id)| id.to_string());
+        assert_eq!(
+            found,
+            [
+                (SearchEntityType::Customer, "c1"),
+                (SearchEntityType::Order, "o1")
+            ]
+        );
+
+        let orders_only = global_search(
+            state,
+            "yangon".into(),
+            Some(vec![SearchEntityType::Order]),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(orders_only.is_empty());
+    }
+}
@@ -1,13 +1,16 @@
 use std::fs;
 
-use aws_config::BehaviorVersion;
-use aws_credential_types::provider::SharedCredentialsProvider;
-use aws_sdk_s3::config::{Credentials, Region};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 use tracing::instrument;
 
 use crate::error::{AppError, AppResult};
+use crate::scheduler::validate_backup_schedule;
+use crate::services::storage::{
+    ObjectStorage, S3Config, S3Storage, Storage, PROVIDER_LOCAL, PROVIDER_S3,
+    PROVIDER_S3_COMPATIBLE,
+};
+use crate::services::vault::{is_sealed, redact, reveal_secret, seal_secret, REDACTED};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppSettings {
@@ -28,12 +31,39 @@ pub struct AppSettings {
     pub invoice_printer_name: String,
     #[serde(default = "default_silent_invoice_print")]
     pub silent_invoice_print: bool,
+    #[serde(default = "default_receipt_printer_transport")]
+    pub receipt_printer_transport: String,
+    #[serde(default)]
+    pub receipt_printer_target: String,
+    #[serde(default = "default_receipt_paper_width")]
+    pub receipt_paper_width: String,
+    #[serde(default = "default_scan_target_status")]
+    pub scan_target_status: String,
+    #[serde(default = "default_scan_date_field")]
+    pub scan_date_field: String,
+    #[serde(default = "default_label_sheet_size")]
+    pub label_sheet_size: String,
+    #[serde(default)]
+    pub label_font_path: String,
+    #[serde(default = "default_phone_country")]
+    pub default_phone_country: String,
     #[serde(default = "default_auto_backup")]
     pub auto_backup: bool,
+    /// `never`, `daily`, `weekly`, `monthly` or `custom`.
     #[serde(default = "default_backup_frequency")]
     pub backup_frequency: String,
+    /// One or more local times as `HH:MM`, separated by commas.
     #[serde(default = "default_backup_time")]
     pub backup_time: String,
+    /// Day of a weekly backup, 0 (Sunday) to 6.
+    #[serde(default)]
+    pub backup_weekday: u32,
+    /// Day of a monthly backup, 1 to 28.
+    #[serde(default = "default_backup_month_day")]
+    pub backup_month_day: u32,
+    /// Cron expression for the `custom` frequency, with or without seconds.
+    #[serde(default)]
+    pub backup_cron: String,
     #[serde(default = "default_font_size")]
     pub font_size: String,
     #[serde(default)]
@@ -44,8 +74,27 @@ pub struct AppSettings {
     pub aws_region: String,
     #[serde(default)]
     pub aws_bucket_name: String,
+    /// CDN URL uploaded files are served from, for any storage provider.
     #[serde(default)]
     pub imagekit_base_url: String,
+    /// `s3`, `s3_compatible` or `local`.
+    #[serde(default = "default_storage_provider")]
+    pub storage_provider: String,
+    /// Endpoint of an S3-compatible service, e.g. `http://localhost:9000`.
+    #[serde(default)]
+    pub s3_endpoint_url: String,
+    #[serde(default = "default_s3_force_path_style")]
+    pub s3_force_path_style: bool,
+    /// Folder used as storage by the `local` provider.
+    #[serde(default)]
+    pub local_storage_dir: String,
+    /// `none`, `master_password` or `passphrase`. Changed only through
+    /// `set_backup_encryption`.
+    #[serde(default = "default_backup_encryption")]
+    pub backup_encryption: String,
+    /// Passphrase backup archives are encrypted with, sealed by the vault.
+    #[serde(default)]
+    pub backup_passphrase: String,
 }
 
 fn default_accent_color() -> String {
@@ -72,6 +121,30 @@ fn default_silent_invoice_print() -> bool {
     true
 }
 
+fn default_receipt_printer_transport() -> String {
+    "cups".to_string()
+}
+
+fn default_receipt_paper_width() -> String {
+    "80mm".to_string()
+}
+
+fn default_scan_target_status() -> String {
+    "shipping".to_string()
+}
+
+fn default_scan_date_field() -> String {
+    "arrived_date".to_string()
+}
+
+fn default_label_sheet_size() -> String {
+    "a4_14".to_string()
+}
+
+fn default_phone_country() -> String {
+    "MM".to_string()
+}
+
 fn default_auto_backup() -> bool {
     true
 }
@@ -84,10 +157,26 @@ fn default_backup_time() -> String {
     "23:00".to_string()
 }
 
+fn default_backup_month_day() -> u32 {
+    1
+}
+
 fn default_font_size() -> String {
     "normal".to_string()
 }
 
+fn default_storage_provider() -> String {
+    PROVIDER_S3.to_string()
+}
+
+fn default_s3_force_path_style() -> bool {
+    true
+}
+
+fn default_backup_encryption() -> String {
+    "none".to_string()
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
@@ -101,15 +190,32 @@ impl Default for AppSettings {
             exchange_currency_symbol: "Ks".to_string(),
             invoice_printer_name: String::new(),
             silent_invoice_print: true,
+            receipt_printer_transport: "cups".to_string(),
+            receipt_printer_target: String::new(),
+            receipt_paper_width: "80mm".to_string(),
+            scan_target_status: "shipping".to_string(),
+            scan_date_field: "arrived_date".to_string(),
+            label_sheet_size: "a4_14".to_string(),
+            label_font_path: String::new(),
+            default_phone_country: "MM".to_string(),
             auto_backup: true,
             backup_frequency: "never".to_string(),
             backup_time: "23:00".to_string(),
+            backup_weekday: 0,
+            backup_month_day: 1,
+            backup_cron: String::new(),
             font_size: "normal".to_string(),
             aws_access_key_id: String::new(),
             aws_secret_access_key: String::new(),
             aws_region: String::new(),
             aws_bucket_name: String::new(),
             imagekit_base_url: String::new(),
+            storage_provider: PROVIDER_S3.to_string(),
+            s3_endpoint_url: String::new(),
+            s3_force_path_style: true,
+            local_storage_dir: String::new(),
+            backup_encryption: "none".to_string(),
+            backup_passphrase: String::new(),
         }
     }
 }
@@ -120,6 +226,11 @@ pub struct AwsS3ConnectionInput {
     pub secret_access_key: String,
     pub region: String,
     pub bucket_name: String,
+    /// Set for S3-compatible services; empty for AWS.
+    #[serde(default)]
+    pub endpoint_url: String,
+    #[serde(default)]
+    pub force_path_style: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -136,53 +247,24 @@ pub(crate) fn normalize_s3_bucket_name(bucket_name: &str) -> String {
         .to_string()
 }
 
-fn validate_s3_connection_input(input: &AwsS3ConnectionInput) -> AppResult<AwsS3ConnectionInput> {
-    let normalized = AwsS3ConnectionInput {
-        access_key_id: input.access_key_id.trim().to_string(),
-        secret_access_key: input.secret_access_key.trim().to_string(),
-        region: input.region.trim().to_string(),
-        bucket_name: normalize_s3_bucket_name(&input.bucket_name),
+async fn check_aws_s3_connection(app: &AppHandle, input: &AwsS3ConnectionInput) -> AppResult<()> {
+    let secret_access_key = if input.secret_access_key == REDACTED {
+        reveal_secret(&get_app_settings(app.clone())?.aws_secret_access_key)?
+    } else {
+        input.secret_access_key.clone()
     };
-
-    if normalized.access_key_id.is_empty()
-        || normalized.secret_access_key.is_empty()
-        || normalized.region.is_empty()
-        || normalized.bucket_name.is_empty()
-    {
-        return Err(AppError::invalid_input(
-            "AWS Access Key, Secret Key, Region, and Bucket are required.",
-        ));
-    }
-
-    Ok(normalized)
-}
-
-async fn check_aws_s3_connection(input: &AwsS3ConnectionInput) -> AppResult<()> {
-    let normalized = validate_s3_connection_input(input)?;
-
-    let credentials = Credentials::new(
-        normalized.access_key_id,
-        normalized.secret_access_key,
-        None,
-        None,
-        "thai-htay-settings",
-    );
-
-    let config = aws_config::defaults(BehaviorVersion::latest())
-        .region(Region::new(normalized.region))
-        .credentials_provider(SharedCredentialsProvider::new(credentials))
-        .load()
-        .await;
-
-    let client = aws_sdk_s3::Client::new(&config);
-    client
-        .head_bucket()
-        .bucket(normalized.bucket_name)
-        .send()
+    let config = S3Config {
+        access_key_id: input.access_key_id.clone(),
+        secret_access_key,
+        region: input.region.clone(),
+        bucket: input.bucket_name.clone(),
+        endpoint_url: Some(input.endpoint_url.clone()),
+        force_path_style: input.force_path_style,
+    };
+    S3Storage::connect(config, "thai-htay-settings")
+        .await?
+        .check_connection()
         .await
-        .map_err(|err| AppError::internal(format!("Unable to connect to S3 bucket: {err}")))?;
-
-    Ok(())
 }
 
 /// Reads and returns app settings from disk.
@@ -203,23 +285,95 @@ pub fn get_app_settings(app: AppHandle) -> AppResult<AppSettings> {
     Ok(settings)
 }
 
-/// Persists app settings to disk.
-#[instrument(skip(app, settings))]
-pub fn update_app_settings(app: AppHandle, settings: AppSettings) -> AppResult<()> {
+pub(crate) fn write_app_settings(app: &AppHandle, settings: &AppSettings) -> AppResult<()> {
     let app_data_dir = app.path().app_data_dir()?;
     let settings_path = app_data_dir.join("settings.json");
 
-    let settings_json = serde_json::to_string_pretty(&settings)?;
+    let settings_json = serde_json::to_string_pretty(settings)?;
     fs::write(settings_path, settings_json)?;
     Ok(())
 }
 
+/// Returns app settings for the frontend, with secrets redacted.
+#[instrument(skip(app))]
+pub fn get_redacted_app_settings(app: AppHandle) -> AppResult<AppSettings> {
+    let mut settings = get_app_settings(app)?;
+    settings.aws_secret_access_key = redact(&settings.aws_secret_access_key);
+    settings.backup_passphrase = redact(&settings.backup_passphrase);
+    Ok(settings)
+}
+
+/// Persists app settings to disk. Secrets are encrypted; a redacted secret
+/// sent back by the frontend keeps the stored one. Backup encryption is kept
+/// as stored, and an invalid backup schedule is rejected.
+#[instrument(skip(app, settings))]
+pub fn update_app_settings(app: AppHandle, mut settings: AppSettings) -> AppResult<()> {
+    validate_backup_schedule(&app, &settings)?;
+    if ![PROVIDER_S3, PROVIDER_S3_COMPATIBLE, PROVIDER_LOCAL]
+        .contains(&settings.storage_provider.as_str())
+    {
+        return Err(AppError::invalid_input(format!(
+            "Unknown storage provider: {}",
+            settings.storage_provider
+        )));
+    }
+    let stored = get_app_settings(app.clone())?;
+    settings.aws_secret_access_key = if settings.aws_secret_access_key == REDACTED {
+        stored.aws_secret_access_key
+    } else {
+        seal_secret(&settings.aws_secret_access_key)?
+    };
+    settings.backup_encryption = stored.backup_encryption;
+    settings.backup_passphrase = stored.backup_passphrase;
+    write_app_settings(&app, &settings)
+}
+
+/// Encrypts secrets still stored in plaintext. Returns whether any were.
+pub(crate) fn seal_plaintext_settings_secrets(app: &AppHandle) -> AppResult<bool> {
+    let mut settings = get_app_settings(app.clone())?;
+    let mut sealed = false;
+    for secret in [
+        &mut settings.aws_secret_access_key,
+        &mut settings.backup_passphrase,
+    ] {
+        if !secret.is_empty() && !is_sealed(secret) {
+            *secret = seal_secret(secret)?;
+            sealed = true;
+        }
+    }
+    if sealed {
+        write_app_settings(app, &settings)?;
+    }
+    Ok(sealed)
+}
+
+/// Drops secrets the vault can no longer decrypt.
+pub(crate) fn clear_sealed_settings_secrets(app: &AppHandle) -> AppResult<()> {
+    let mut settings = get_app_settings(app.clone())?;
+    let mut cleared = false;
+    for secret in [
+        &mut settings.aws_secret_access_key,
+        &mut settings.backup_passphrase,
+    ] {
+        if is_sealed(secret) {
+            secret.clear();
+            cleared = true;
+        }
+    }
+    if cleared {
+        write_app_settings(app, &settings)?;
+    }
+    Ok(())
+}
+
 /// Tests provided AWS S3 configuration and returns connection status payload.
-#[instrument(skip(config))]
+/// A redacted secret key is replaced by the stored one.
+#[instrument(skip(app, config))]
 pub async fn test_aws_s3_connection(
+    app: AppHandle,
     config: AwsS3ConnectionInput,
 ) -> AppResult<AwsS3ConnectionStatus> {
-    match check_aws_s3_connection(&config).await {
+    match check_aws_s3_connection(&app, &config).await {
         Ok(_) => Ok(AwsS3ConnectionStatus {
             connected: true,
             message: "Connected to AWS S3 successfully.".to_string(),
@@ -231,32 +385,35 @@ pub async fn test_aws_s3_connection(
     }
 }
 
-/// Reads configured AWS S3 values from settings and reports connection status.
+/// Checks the storage selected in settings (S3, S3-compatible or a local
+/// folder) and reports connection status.
 #[instrument(skip(app))]
 pub async fn get_aws_s3_connection_status(app: AppHandle) -> AppResult<AwsS3ConnectionStatus> {
-    let settings = get_app_settings(app)?;
-    if settings.aws_access_key_id.trim().is_empty()
-        || settings.aws_secret_access_key.trim().is_empty()
-        || settings.aws_region.trim().is_empty()
-        || settings.aws_bucket_name.trim().is_empty()
-    {
-        return Ok(AwsS3ConnectionStatus {
-            connected: false,
-            message: "AWS S3 is not configured.".to_string(),
-        });
-    }
-
-    let config = AwsS3ConnectionInput {
-        access_key_id: settings.aws_access_key_id,
-        secret_access_key: settings.aws_secret_access_key,
-        region: settings.aws_region,
-        bucket_name: settings.aws_bucket_name,
+    let provider = get_app_settings(app.clone())?.storage_provider;
+    let storage = match Storage::from_settings(&app, "thai-htay-settings").await {
+        Ok(Some(storage)) => storage,
+        Ok(None) => {
+            return Ok(AwsS3ConnectionStatus {
+                connected: false,
+                message: "File storage is not configured.".to_string(),
+            })
+        }
+        Err(err) => {
+            return Ok(AwsS3ConnectionStatus {
+                connected: false,
+                message: err.to_string(),
+            })
+        }
     };
 
-    match check_aws_s3_connection(&config).await {
+    match storage.check_connection().await {
         Ok(_) => Ok(AwsS3ConnectionStatus {
             connected: true,
-            message: "Connected to AWS S3 successfully.".to_string(),
+            message: match provider.as_str() {
+                PROVIDER_LOCAL => "Storage folder is ready.".to_string(),
+                PROVIDER_S3_COMPATIBLE => "Connected to the S3-compatible storage.".to_string(),
+                _ => "Connected to AWS S3 successfully.".to_string(),
+            },
         }),
         Err(err) => Ok(AwsS3ConnectionStatus {
             connected: false,
@@ -2,10 +2,6 @@ use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 
-use aws_config::BehaviorVersion;
-use aws_credential_types::provider::SharedCredentialsProvider;
-use aws_sdk_s3::config::{Credentials, Region};
-use aws_sdk_s3::primitives::ByteStream;
 use chrono::Utc;
 use sea_orm::{
     ActiveModelTrait, ConnectionTrait, DatabaseBackend, EntityTrait, FromQueryResult, QueryOrder,
@@ -19,7 +15,7 @@ use crate::db::copy_logo_to_app_data;
 use crate::entities::shop_settings;
 use crate::error::{AppError, AppResult};
 use crate::models::ShopSettings;
-use crate::services::settings::{get_app_settings, normalize_s3_bucket_name};
+use crate::services::storage::{content_type_for, Storage};
 use crate::state::AppState;
 use crate::sync::enqueue_sync;
 
@@ -170,49 +166,14 @@ pub async fn update_shop_settings(
     Ok(())
 }
 
-fn normalize_base_url(url: &str) -> String {
-    url.trim().trim_end_matches('/').to_string()
-}
-
-fn image_content_type(file_path: &str) -> &'static str {
-    match Path::new(file_path)
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_ascii_lowercase())
-        .as_deref()
-    {
-        Some("jpg") | Some("jpeg") => "image/jpeg",
-        Some("gif") => "image/gif",
-        Some("webp") => "image/webp",
-        Some("svg") => "image/svg+xml",
-        Some("png") => "image/png",
-        _ => "application/octet-stream",
-    }
-}
-
-/// Uploads shop logo to S3, updates local setting, and enqueues sync payload.
+/// Uploads shop logo to the configured storage, updates local setting, and enqueues sync payload.
 #[instrument(skip(state, app))]
 pub async fn upload_shop_logo_to_s3(
     state: Arc<AppState>,
     app: &AppHandle,
     logo_path: Option<String>,
 ) -> AppResult<String> {
-    let app_settings = get_app_settings(app.clone())?;
-    let aws_access_key_id = app_settings.aws_access_key_id.trim().to_string();
-    let aws_secret_access_key = app_settings.aws_secret_access_key.trim().to_string();
-    let aws_region = app_settings.aws_region.trim().to_string();
-    let aws_bucket_name = normalize_s3_bucket_name(&app_settings.aws_bucket_name);
-    let imagekit_base_url = normalize_base_url(&app_settings.imagekit_base_url);
-
-    if aws_access_key_id.is_empty()
-        || aws_secret_access_key.is_empty()
-        || aws_region.is_empty()
-        || aws_bucket_name.is_empty()
-    {
-        return Err(AppError::invalid_input(
-            "AWS S3 is not configured. Please set access key, secret key, region, and bucket in Settings.",
-        ));
-    }
+    let storage = Storage::required(app, "thai-htay-shop-logo").await?;
 
     let new_internal_logo_path = logo_path
         .map(|p| p.trim().to_string())
@@ -254,39 +215,10 @@ pub async fn upload_shop_logo_to_s3(
         extension
     );
 
-    let credentials = Credentials::new(
-        aws_access_key_id,
-        aws_secret_access_key,
-        None,
-        None,
-        "thai-htay-shop-logo",
-    );
-    let config = aws_config::defaults(BehaviorVersion::latest())
-        .region(Region::new(aws_region.clone()))
-        .credentials_provider(SharedCredentialsProvider::new(credentials))
-        .load()
-        .await;
-    let s3_client = aws_sdk_s3::Client::new(&config);
-
-    s3_client
-        .put_object()
-        .bucket(aws_bucket_name.clone())
-        .key(&object_key)
-        .body(ByteStream::from(file_bytes))
-        .content_type(image_content_type(&logo_to_upload))
-        .send()
-        .await
-        .map_err(|e| AppError::internal(format!("Failed to upload logo to S3: {}", e)))?;
-
-    let s3_cloud_url = format!(
-        "https://{}.s3.{}.amazonaws.com/{}",
-        aws_bucket_name, aws_region, object_key
-    );
-    let cloud_url = if imagekit_base_url.is_empty() {
-        s3_cloud_url
-    } else {
-        format!("{}/{}", imagekit_base_url, object_key)
-    };
+    storage
+        .put_object(&object_key, file_bytes, content_type_for(&logo_to_upload))
+        .await?;
+    let cloud_url = storage.public_url(&object_key);
 
     if let Some(local_path) = new_internal_logo_path {
         db.execute(Statement::from_sql_and_values(
@@ -8,6 +8,7 @@ use tracing::{info, instrument};
 
 use crate::entities::sync_config;
 use crate::error::{AppError, AppResult};
+use crate::services::vault::reveal_secret;
 use crate::state::AppState;
 use crate::sync::SyncConfig;
 
@@ -87,7 +88,7 @@ async fn load_sync_config(state: &AppState) -> AppResult<SyncConfig> {
         id: Some(row.id),
         supabase_url: row.supabase_url,
         supabase_anon_key: row.supabase_anon_key,
-        supabase_service_key: row.supabase_service_key,
+        supabase_service_key: reveal_secret(&row.supabase_service_key)?,
         sync_enabled,
         sync_interval,
     })
@@ -0,0 +1,585 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use aws_config::BehaviorVersion;
+use aws_credential_types::provider::SharedCredentialsProvider;
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::primitives::ByteStream;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult};
+use crate::services::settings::{get_app_settings, normalize_s3_bucket_name, AppSettings};
+use crate::services::vault::reveal_secret;
+
+pub(crate) const PROVIDER_S3: &str = "s3";
+pub(crate) const PROVIDER_S3_COMPATIBLE: &str = "s3_compatible";
+pub(crate) const PROVIDER_LOCAL: &str = "local";
+
+/// An object in a storage backend, as returned by `list_objects`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredObject {
+    pub key: String,
+    pub size_bytes: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// A place files are copied to: a bucket or a folder. Keys are `/`-separated
+/// paths relative to the bucket or folder root.
+#[async_trait]
+pub(crate) trait ObjectStorage: Send + Sync {
+    async fn put_object(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> AppResult<()>;
+    async fn get_object(&self, key: &str) -> AppResult<Vec<u8>>;
+    async fn delete_object(&self, key: &str) -> AppResult<()>;
+    /// Objects whose key starts with `prefix`, sorted by key.
+    async fn list_objects(&self, prefix: &str) -> AppResult<Vec<StoredObject>>;
+    /// Fails when the bucket or folder cannot be reached.
+    async fn check_connection(&self) -> AppResult<()>;
+    /// Where the object can be fetched from without credentials.
+    fn object_url(&self, key: &str) -> String;
+}
+
+/// Connection details of an S3 bucket. `endpoint_url` is set for
+/// S3-compatible services (MinIO, Cloudflare R2, Wasabi); most of them need
+/// path-style addressing.
+#[derive(Debug, Clone)]
+pub(crate) struct S3Config {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub region: String,
+    pub bucket: String,
+    pub endpoint_url: Option<String>,
+    pub force_path_style: bool,
+}
+
+impl S3Config {
+    /// Trims the values and fails when a required one is empty.
+    pub(crate) fn validated(self) -> AppResult<Self> {
+        let config = S3Config {
+            access_key_id: self.access_key_id.trim().to_string(),
+            secret_access_key: self.secret_access_key.trim().to_string(),
+            region: self.region.trim().to_string(),
+            bucket: normalize_s3_bucket_name(&self.bucket),
+            endpoint_url: self
+                .endpoint_url
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            force_path_style: self.force_path_style,
+        };
+        if config.access_key_id.is_empty()
+            || config.secret_access_key.is_empty()
+            || config.region.is_empty()
+            || config.bucket.is_empty()
+        {
+            return Err(AppError::invalid_input(
+                "AWS Access Key, Secret Key, Region, and Bucket are required.",
+            ));
+        }
+        if let Some(endpoint_url) = &config.endpoint_url {
+            let parsed = url::Url::parse(endpoint_url).map_err(|_| {
+                AppError::invalid_input(format!("Invalid S3 endpoint URL: {endpoint_url}"))
+            })?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(AppError::invalid_input(
+                    "The S3 endpoint URL must start with http:// or https://",
+                ));
+            }
+        }
+        Ok(config)
+    }
+}
+
+pub(crate) struct S3Storage {
+    client: aws_sdk_s3::Client,
+    config: S3Config,
+}
+
+impl S3Storage {
+    pub(crate) async fn connect(config: S3Config, provider_name: &'static str) -> AppResult<Self> {
+        let config = config.validated()?;
+        let credentials = Credentials::new(
+            config.access_key_id.clone(),
+            config.secret_access_key.clone(),
+            None,
+            None,
+            provider_name,
+        );
+        let shared = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(config.region.clone()))
+            .credentials_provider(SharedCredentialsProvider::new(credentials))
+            .load()
+            .await;
+        let mut builder =
+            aws_sdk_s3::config::Builder::from(&shared).force_path_style(config.force_path_style);
+        if let Some(endpoint_url) = &config.endpoint_url {
+            builder = builder.endpoint_url(endpoint_url);
+        }
+        Ok(S3Storage {
+            client: aws_sdk_s3::Client::from_conf(builder.build()),
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for S3Storage {
+    async fn put_object(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> AppResult<()> {
+        self.client
+            .put_object()
+            .bucket(&self.config.bucket)
+            .key(key)
+            .body(ByteStream::from(bytes))
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to upload to S3: {}", e)))?;
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> AppResult<Vec<u8>> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.config.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to download from S3: {}", e)))?;
+        let body = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to download from S3: {}", e)))?;
+        Ok(body.into_bytes().to_vec())
+    }
+
+    async fn delete_object(&self, key: &str) -> AppResult<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.config.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to delete from S3: {}", e)))?;
+        Ok(())
+    }
+
+    async fn list_objects(&self, prefix: &str) -> AppResult<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.config.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .map_err(|e| AppError::internal(format!("Failed to list S3 objects: {}", e)))?;
+            objects.extend(output.contents().iter().filter_map(|object| {
+                Some(StoredObject {
+                    key: object.key()?.to_string(),
+                    size_bytes: object.size().unwrap_or_default(),
+                    last_modified: object
+                        .last_modified()
+                        .and_then(|time| time.to_millis().ok())
+                        .and_then(DateTime::from_timestamp_millis),
+                })
+            }));
+            match output.next_continuation_token() {
+                Some(token) => continuation_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    async fn check_connection(&self) -> AppResult<()> {
+        self.client
+            .head_bucket()
+            .bucket(&self.config.bucket)
+            .send()
+            .await
+            .map_err(|err| AppError::internal(format!("Unable to connect to S3 bucket: {err}")))?;
+        Ok(())
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        match (&self.config.endpoint_url, self.config.force_path_style) {
+            (Some(endpoint_url), true) => {
+                format!("{}/{}/{}", endpoint_url, self.config.bucket, key)
+            }
+            (Some(endpoint_url), false) => {
+                let (scheme, host) = endpoint_url
+                    .split_once("://")
+                    .unwrap_or(("https", endpoint_url));
+                format!("{}://{}.{}/{}", scheme, self.config.bucket, host, key)
+            }
+            (None, true) => format!(
+                "https://s3.{}.amazonaws.com/{}/{}",
+                self.config.region, self.config.bucket, key
+            ),
+            (None, false) => format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.config.bucket, self.config.region, key
+            ),
+        }
+    }
+}
+
+/// A folder on this machine or a mounted drive, used like a bucket.
+pub(crate) struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub(crate) fn new(root: impl Into<PathBuf>) -> AppResult<Self> {
+        let root = root.into();
+        if root.as_os_str().is_empty() {
+            return Err(AppError::invalid_input("A storage folder is required."));
+        }
+        if !root.is_absolute() {
+            return Err(AppError::invalid_input(
+                "The storage folder must be an absolute path.",
+            ));
+        }
+        Ok(LocalStorage { root })
+    }
+
+    /// Resolves a key inside the root, refusing keys that would escape it.
+    fn path_for(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(AppError::invalid_input(format!(
+                "Invalid storage key: {key}"
+            )));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    fn collect(&self, dir: &Path, objects: &mut Vec<StoredObject>) -> AppResult<()> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let path = entry.path();
+            if metadata.is_dir() {
+                self.collect(&path, objects)?;
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            let key = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if key.ends_with(".part") {
+                continue;
+            }
+            objects.push(StoredObject {
+                key,
+                size_bytes: metadata.len() as i64,
+                last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalStorage {
+    async fn put_object(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> AppResult<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write under a temporary name so readers never see a partial file.
+        let mut partial = path.clone().into_os_string();
+        partial.push(".part");
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, &path)?;
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> AppResult<Vec<u8>> {
+        match fs::read(self.path_for(key)?) {
+            Ok(bytes) => Ok(bytes),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(AppError::not_found(
+                format!("File not found in storage: {key}"),
+            )),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete_object(&self, key: &str) -> AppResult<()> {
+        match fs::remove_file(self.path_for(key)?) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn list_objects(&self, prefix: &str) -> AppResult<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        self.collect(&self.root, &mut objects)?;
+        objects.retain(|object| object.key.starts_with(prefix));
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    async fn check_connection(&self) -> AppResult<()> {
+        fs::create_dir_all(&self.root).map_err(|err| {
+            AppError::invalid_input(format!(
+                "Unable to use storage folder {}: {err}",
+                self.root.display()
+            ))
+        })?;
+        let probe = self.root.join(".write-test");
+        fs::write(&probe, b"ok")
+            .and_then(|_| fs::remove_file(&probe))
+            .map_err(|err| {
+                AppError::invalid_input(format!(
+                    "Storage folder {} is not writable: {err}",
+                    self.root.display()
+                ))
+            })
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        self.root.join(key).to_string_lossy().to_string()
+    }
+}
+
+/// The storage configured in Settings, with the URL files are served from.
+pub(crate) struct Storage {
+    backend: Box<dyn ObjectStorage>,
+    /// CDN in front of the bucket (e.g. ImageKit); overrides `object_url`.
+    public_base_url: String,
+}
+
+impl Storage {
+    /// Connects to the storage selected in Settings, or returns `None` when it
+    /// is not fully configured.
+    pub(crate) async fn from_settings(
+        app: &AppHandle,
+        provider_name: &'static str,
+    ) -> AppResult<Option<Self>> {
+        let settings = get_app_settings(app.clone())?;
+        let backend: Box<dyn ObjectStorage> = match settings.storage_provider.as_str() {
+            PROVIDER_LOCAL => {
+                let dir = settings.local_storage_dir.trim();
+                if dir.is_empty() {
+                    return Ok(None);
+                }
+                Box::new(LocalStorage::new(dir)?)
+            }
+            provider => {
+                let config = s3_config_from_settings(&settings, provider)?;
+                if config.clone().validated().is_err() {
+                    return Ok(None);
+                }
+                Box::new(S3Storage::connect(config, provider_name).await?)
+            }
+        };
+        Ok(Some(Storage {
+            backend,
+            public_base_url: settings
+                .imagekit_base_url
+                .trim()
+                .trim_end_matches('/')
+                .to_string(),
+        }))
+    }
+
+    /// Like `from_settings`, but a missing configuration is an error.
+    pub(crate) async fn required(app: &AppHandle, provider_name: &'static str) -> AppResult<Self> {
+        Self::from_settings(app, provider_name)
+            .await?
+            .ok_or_else(|| {
+                AppError::invalid_input(
+                    "File storage is not configured. Please set up S3 or a storage folder in Settings.",
+                )
+            })
+    }
+
+    pub(crate) async fn put_object(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> AppResult<()> {
+        self.backend.put_object(key, bytes, content_type).await
+    }
+
+    pub(crate) async fn delete_object(&self, key: &str) -> AppResult<()> {
+        self.backend.delete_object(key).await
+    }
+
+    pub(crate) async fn list_objects(&self, prefix: &str) -> AppResult<Vec<StoredObject>> {
+        self.backend.list_objects(prefix).await
+    }
+
+    pub(crate) async fn check_connection(&self) -> AppResult<()> {
+        self.backend.check_connection().await
+    }
+
+    /// Public URL of an object: through the CDN when configured, else the
+    /// backend's own URL.
+    pub(crate) fn public_url(&self, key: &str) -> String {
+        if self.public_base_url.is_empty() {
+            self.backend.object_url(key)
+        } else {
+            format!("{}/{}", self.public_base_url, key)
+        }
+    }
+}
+
+/// S3 connection values from Settings. The endpoint is only used for the
+/// S3-compatible provider, so a stale value cannot redirect AWS uploads.
+pub(crate) fn s3_config_from_settings(
+    settings: &AppSettings,
+    provider: &str,
+) -> AppResult<S3Config> {
+    let compatible = provider == PROVIDER_S3_COMPATIBLE;
+    Ok(S3Config {
+        access_key_id: settings.aws_access_key_id.clone(),
+        secret_access_key: reveal_secret(&settings.aws_secret_access_key)?,
+        region: settings.aws_region.clone(),
+        bucket: settings.aws_bucket_name.clone(),
+        endpoint_url: compatible.then(|| settings.s3_endpoint_url.clone()),
+        force_path_style: compatible && settings.s3_force_path_style,
+    })
+}
+
+/// Content type sent with an upload, from the file extension.
+pub(crate) fn content_type_for(file_path: &str) -> &'static str {
+    match Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("heic") => "image/heic",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(dir: &tempfile::TempDir) -> LocalStorage {
+        LocalStorage::new(dir.path()).unwrap()
+    }
+
+    fn s3(endpoint_url: Option<&str>, force_path_style: bool) -> S3Storage {
+        let client_config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("ap-southeast-1"))
+            .build();
+        S3Storage {
+            client: aws_sdk_s3::Client::from_conf(client_config),
+            config: S3Config {
+                access_key_id: "key".into(),
+                secret_access_key: "secret".into(),
+                region: "ap-southeast-1".into(),
+                bucket: "shop-files".into(),
+                endpoint_url: endpoint_url.map(str::to_string),
+                force_path_style,
+            },
+        }
+    }
+
+    #[test]
+    fn local_root_must_be_absolute() {
+        assert!(LocalStorage::new("").is_err());
+        assert!(LocalStorage::new("relative/folder").is_err());
+    }
+
+    #[test]
+    fn path_for_rejects_keys_outside_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = local(&dir);
+        for key in [
+            "",
+            "../secret.txt",
+            "backups/../../secret.txt",
+            "/etc/passwd",
+            "./a",
+        ] {
+            assert!(storage.path_for(key).is_err(), "{key:?} should be rejected");
+        }
+        assert_eq!(
+            storage.path_for("backups/a.zip").unwrap(),
+            dir.path().join("backups").join("a.zip")
+        );
+    }
+
+    #[tokio::test]
+    async fn list_objects_filters_by_prefix_and_skips_partial_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = local(&dir);
+        storage
+            .put_object("backups/b.zip", b"bb".to_vec(), "application/zip")
+            .await
+            .unwrap();
+        storage
+            .put_object("backups/a.zip", b"a".to_vec(), "application/zip")
+            .await
+            .unwrap();
+        storage
+            .put_object("photos/p.jpg", b"p".to_vec(), "image/jpeg")
+            .await
+            .unwrap();
+        fs::write(dir.path().join("backups").join("c.zip.part"), b"half").unwrap();
+
+        let objects = storage.list_objects("backups/").await.unwrap();
+        let listed: Vec<_> = objects
+            .iter()
+            .map(|object| (object.key.as_str(), object.size_bytes))
+            .collect();
+        assert_eq!(listed, [("backups/a.zip", 1), ("backups/b.zip", 2)]);
+        assert_eq!(storage.list_objects("").await.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn object_url_for_aws_and_compatible_endpoints() {
+        assert_eq!(
+            s3(None, false).object_url("photos/p.jpg"),
+            "https://shop-files.s3.ap-southeast-1.amazonaws.com/photos/p.jpg"
+        );
+        assert_eq!(
+            s3(None, true).object_url("photos/p.jpg"),
+            "https://s3.ap-southeast-1.amazonaws.com/shop-files/photos/p.jpg"
+        );
+        assert_eq!(
+            s3(Some("http://localhost:9000"), true).object_url("photos/p.jpg"),
+            "http://localhost:9000/shop-files/photos/p.jpg"
+        );
+        assert_eq!(
+            s3(Some("https://r2.example.com"), false).object_url("photos/p.jpg"),
+            "https://shop-files.r2.example.com/photos/p.jpg"
+        );
+    }
+}
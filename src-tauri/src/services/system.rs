@@ -1,17 +1,21 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use sea_orm::SqlxSqliteConnector;
-use sea_orm::{ConnectionTrait, DatabaseBackend, FromQueryResult, Statement};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
 use sea_orm_migration::MigratorTrait;
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use tauri::{AppHandle, Manager};
 use tracing::instrument;
 
 use crate::error::{AppError, AppResult};
 use crate::migration::Migrator;
 use crate::models::{DbStatus, TableSequenceResetStatus, TableStatus};
+use crate::services::backup::{
+    copy_pre_restore_file, decrypt_backup_file, extract_backup_archive, save_pre_restore_copy,
+    snapshot_database, validate_restore_candidate,
+};
 use crate::state::AppState;
 
 #[derive(Debug, FromQueryResult)]
@@ -68,33 +72,82 @@ pub async fn reset_app_data(state: Arc<AppState>, app: &AppHandle) -> AppResult<
     Ok(())
 }
 
-/// Backs up sqlite DB file to destination path.
-#[instrument(skip(app))]
-pub async fn backup_database(app: &AppHandle, dest_path: String) -> AppResult<u64> {
-    let app_data_dir = app.path().app_data_dir()?;
-    let db_path = app_data_dir.join("shop.db");
-
-    if !db_path.exists() {
-        return Err(AppError::not_found("Database file not found"));
-    }
+/// Backs up a verified snapshot of the sqlite DB to destination path.
+#[instrument(skip(state, app))]
+pub async fn backup_database(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    dest_path: String,
+) -> AppResult<u64> {
+    let snapshot = snapshot_database(&state, app).await?;
 
     let dest = PathBuf::from(&dest_path);
-    let bytes_copied = fs::copy(&db_path, &dest)
+    let bytes_copied = fs::copy(snapshot.path(), &dest)
         .map_err(|e| AppError::internal(format!("Failed to copy database: {}", e)))?;
     Ok(bytes_copied)
 }
 
-/// Restores sqlite DB file, reconnects pool, and runs pending migrations.
-#[instrument(skip(state, app))]
+/// Replaces the database file, dropping the old WAL and shared-memory files
+/// so SQLite does not replay them onto the new one. The pool must be closed.
+fn replace_database_file(source: &Path, db_path: &Path) -> AppResult<()> {
+    let partial = db_path.with_extension("db.part");
+    fs::copy(source, &partial)?;
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = PathBuf::from(sidecar);
+        if sidecar.exists() {
+            fs::remove_file(sidecar)?;
+        }
+    }
+    fs::rename(&partial, db_path)?;
+    Ok(())
+}
+
+/// Connects to the database file and runs pending migrations. The pool is
+/// closed again when migrating fails, so the file can be replaced.
+async fn open_database(db_path: &Path) -> AppResult<(SqlitePool, DatabaseConnection)> {
+    let db_url = format!("sqlite:{}?mode=rwc", db_path.to_string_lossy());
+    let pool = SqlitePoolOptions::new()
+        .max_connections(5)
+        .connect(&db_url)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to reconnect to database: {}", e)))?;
+    let db = SqlxSqliteConnector::from_sqlx_sqlite_pool(pool.clone());
+    if let Err(err) = Migrator::up(&db, None).await {
+        pool.close().await;
+        return Err(AppError::internal(format!(
+            "Failed to migrate the restored database: {err}"
+        )));
+    }
+    Ok((pool, db))
+}
+
+/// Restores sqlite DB file or backup archive, reconnects pool, and runs
+/// pending migrations. Encrypted archives are decrypted with `passphrase`, or
+/// the backup passphrase from settings. The file is validated before anything
+/// is touched, and the current database is snapshotted to `pre-restore/` and
+/// put back if reconnecting or migrating fails.
+#[instrument(skip(state, app, passphrase))]
 pub async fn restore_database(
     state: Arc<AppState>,
     app: &AppHandle,
     restore_path: String,
+    passphrase: Option<String>,
 ) -> AppResult<()> {
     let restore_source = PathBuf::from(&restore_path);
     if !restore_source.exists() {
         return Err(AppError::not_found("Restore file not found"));
     }
+    let decrypted = decrypt_backup_file(app, &restore_source, passphrase.as_deref())?;
+    let restore_source = decrypted
+        .as_ref()
+        .map_or(restore_source.as_path(), |file| file.path());
+    let extracted = extract_backup_archive(app, restore_source)?;
+    let restore_source = extracted
+        .as_ref()
+        .map_or(restore_source, |file| file.path());
+    validate_restore_candidate(restore_source).await?;
 
     let app_data_dir = app.path().app_data_dir()?;
     let db_path = app_data_dir.join("shop.db");
@@ -103,26 +156,44 @@ pub async fn restore_database(
     let mut db_guard = state.db.lock().await;
     let mut pool_guard = state.pool.lock().await;
 
+    let snapshot = save_pre_restore_copy(app, &db_guard).await;
     pool_guard.close().await;
+    // A database too damaged to snapshot is still kept as a plain copy.
+    let rollback_point = match snapshot {
+        Ok(path) => Some(path),
+        Err(err) => {
+            tracing::warn!("{err}");
+            copy_pre_restore_file(app, &db_path)
+        }
+    };
 
-    fs::copy(&restore_source, &db_path)
-        .map_err(|e| AppError::internal(format!("Failed to restore database: {}", e)))?;
-
-    let db_url = format!("sqlite:{}?mode=rwc", db_path.to_string_lossy());
-    let new_pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&db_url)
-        .await
-        .map_err(|e| AppError::internal(format!("Failed to reconnect to database: {}", e)))?;
-
-    let new_db = SqlxSqliteConnector::from_sqlx_sqlite_pool(new_pool.clone());
-    Migrator::up(&new_db, None)
-        .await
-        .map_err(|e| AppError::internal(e.to_string()))?;
-
-    *pool_guard = new_pool;
-    *db_guard = new_db;
-    Ok(())
+    let restored = match replace_database_file(restore_source, &db_path) {
+        Ok(()) => open_database(&db_path).await,
+        Err(err) => Err(AppError::internal(format!(
+            "Failed to restore database: {}",
+            err
+        ))),
+    };
+    let (pool, db, outcome) = match restored {
+        Ok((pool, db)) => (pool, db, Ok(())),
+        Err(err) => {
+            tracing::warn!("Restore failed, rolling back: {err}");
+            let rolled_back = rollback_point
+                .as_deref()
+                .is_some_and(|path| replace_database_file(path, &db_path).is_ok());
+            let (pool, db) = open_database(&db_path).await?;
+            let outcome = match err {
+                AppError::Internal(message) if rolled_back => Err(AppError::internal(format!(
+                    "{message}. The previous database was put back."
+                ))),
+                err => Err(err),
+            };
+            (pool, db, outcome)
+        }
+    };
+    *pool_guard = pool;
+    *db_guard = db;
+    outcome
 }
 
 /// Returns DB table row counts and DB file size.
@@ -0,0 +1,492 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand_core::RngCore;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tracing::instrument;
+
+use crate::error::{AppError, AppResult};
+use crate::services::{drive, settings};
+use crate::state::AppState;
+
+/// Prefix of a value encrypted by the vault, as stored in settings.json, the
+/// Drive token file or SQLite.
+const SEALED_PREFIX: &str = "vault:v1:";
+/// What the frontend sees in place of a stored secret. Sending it back on save
+/// keeps the stored value.
+pub(crate) const REDACTED: &str = "********";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// The data key that encrypts secrets, while the vault is unlocked.
+static DATA_KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+
+/// What the data key is wrapped with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultMode {
+    /// Derived from this computer's id; unlocks by itself on startup.
+    MachineKey,
+    /// Derived from the owner's master password; unlocked once per session.
+    MasterPassword,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultStatus {
+    pub mode: VaultMode,
+    pub unlocked: bool,
+}
+
+/// vault.json: the random data key, encrypted with a key derived from the
+/// machine id or the master password.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct VaultFile {
+    version: u32,
+    mode: VaultMode,
+    salt: String,
+    wrapped_key: String,
+}
+
+fn vault_path(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(app.path().app_data_dir()?.join("vault.json"))
+}
+
+fn load_vault_file(app: &AppHandle) -> AppResult<Option<VaultFile>> {
+    let path = vault_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+fn save_vault_file(app: &AppHandle, file: &VaultFile) -> AppResult<()> {
+    let path = vault_path(app)?;
+    let partial = path.with_extension("json.part");
+    fs::write(&partial, serde_json::to_string_pretty(file)?)?;
+    fs::rename(&partial, &path)?;
+    Ok(())
+}
+
+fn current_key() -> Option<[u8; 32]> {
+    *DATA_KEY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn set_current_key(key: Option<[u8; 32]>) {
+    *DATA_KEY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = key;
+}
+
+fn data_key() -> AppResult<[u8; 32]> {
+    current_key().ok_or(AppError::VaultLocked)
+}
+
+fn derive_key(secret: &[u8], salt: &[u8]) -> AppResult<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|e| AppError::internal(format!("Failed to derive vault key: {e}")))?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; 32], plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut sealed = nonce.to_vec();
+    sealed.extend(
+        cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| AppError::internal("Failed to encrypt secret"))?,
+    );
+    Ok(sealed)
+}
+
+/// Decrypts `nonce || ciphertext`; `None` when the key is wrong or the data
+/// was tampered with.
+fn open(key: &[u8; 32], sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()
+}
+
+fn wrap(mode: VaultMode, data_key: &[u8; 32], secret: &[u8]) -> AppResult<VaultFile> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let wrapping_key = derive_key(secret, &salt)?;
+    Ok(VaultFile {
+        version: 1,
+        mode,
+        salt: STANDARD.encode(salt),
+        wrapped_key: STANDARD.encode(seal(&wrapping_key, data_key)?),
+    })
+}
+
+fn unwrap(file: &VaultFile, secret: &[u8]) -> Option<[u8; 32]> {
+    let salt = STANDARD.decode(&file.salt).ok()?;
+    let wrapped = STANDARD.decode(&file.wrapped_key).ok()?;
+    let wrapping_key = derive_key(secret, &salt).ok()?;
+    open(&wrapping_key, &wrapped)?.try_into().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn platform_machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("IOPlatformUUID"))
+        .and_then(|line| line.split('"').nth(3))
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_machine_id() -> Option<String> {
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn platform_machine_id() -> Option<String> {
+    None
+}
+
+fn machine_secret() -> AppResult<Vec<u8>> {
+    let id = platform_machine_id()
+        .ok_or_else(|| AppError::internal("Unable to read this computer's id"))?;
+    Ok(format!("thai-htay-vault:{id}").into_bytes())
+}
+
+/// Loads the vault on startup, creating a machine-key vault on first run. A
+/// master-password vault stays locked until `unlock_vault`.
+pub(crate) fn initialize_vault(app: &AppHandle) -> AppResult<VaultStatus> {
+    match load_vault_file(app)? {
+        None => {
+            let key: [u8; 32] = Aes256Gcm::generate_key(OsRng).into();
+            save_vault_file(app, &wrap(VaultMode::MachineKey, &key, &machine_secret()?)?)?;
+            set_current_key(Some(key));
+        }
+        Some(file) if file.mode == VaultMode::MachineKey => {
+            let key = unwrap(&file, &machine_secret()?).ok_or_else(|| {
+                AppError::internal(
+                    "Stored secrets were encrypted on another computer. Reset the vault and enter them again.",
+                )
+            })?;
+            set_current_key(Some(key));
+        }
+        Some(_) => {}
+    }
+    get_vault_status(app)
+}
+
+/// Whether a stored value was encrypted by the vault.
+pub(crate) fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+/// Encrypts a secret for storage. Empty and already encrypted values are kept.
+pub(crate) fn seal_secret(value: &str) -> AppResult<String> {
+    if value.is_empty() || is_sealed(value) {
+        return Ok(value.to_string());
+    }
+    let sealed = seal(&data_key()?, value.as_bytes())?;
+    Ok(format!("{SEALED_PREFIX}{}", STANDARD.encode(sealed)))
+}
+
+/// Decrypts a stored secret. Values written before the vault existed are
+/// plaintext and returned as they are.
+pub(crate) fn reveal_secret(value: &str) -> AppResult<String> {
+    let Some(encoded) = value.strip_prefix(SEALED_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let key = data_key()?;
+    STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|sealed| open(&key, &sealed))
+        .and_then(|plaintext| String::from_utf8(plaintext).ok())
+        .ok_or_else(|| AppError::internal("A stored secret could not be decrypted"))
+}
+
+/// Encrypts data with a key derived from a passphrase, as
+/// `salt || nonce || ciphertext`. Independent of the vault's data key, so the
+/// result can be opened on any computer.
+pub(crate) fn seal_with_passphrase(passphrase: &str, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase.as_bytes(), &salt)?;
+    let mut sealed = salt.to_vec();
+    sealed.extend(seal(&key, plaintext)?);
+    Ok(sealed)
+}
+
+/// Decrypts `seal_with_passphrase` output; `None` when the passphrase is
+/// wrong or the data was tampered with.
+pub(crate) fn open_with_passphrase(passphrase: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < SALT_LEN {
+        return None;
+    }
+    let (salt, rest) = sealed.split_at(SALT_LEN);
+    let key = derive_key(passphrase.as_bytes(), salt).ok()?;
+    open(&key, rest)
+}
+
+/// What the frontend is shown for a stored secret.
+pub(crate) fn redact(value: &str) -> String {
+    if value.is_empty() {
+        String::new()
+    } else {
+        REDACTED.to_string()
+    }
+}
+
+pub(crate) async fn verify_master_password(
+    db: &DatabaseConnection,
+    master_password: &str,
+) -> AppResult<()> {
+    let row = db
+        .query_one(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT master_password_hash FROM users WHERE role = 'owner' \
+             AND master_password_hash IS NOT NULL LIMIT 1",
+        ))
+        .await?
+        .ok_or_else(|| AppError::invalid_input("No master password set. Please set one first."))?;
+    let hash: String = row.try_get("", "master_password_hash")?;
+    let parsed = PasswordHash::new(&hash)
+        .map_err(|e| AppError::internal(format!("Invalid master password hash: {e}")))?;
+    Argon2::default()
+        .verify_password(master_password.as_bytes(), &parsed)
+        .map_err(|_| AppError::invalid_input("Invalid master password"))
+}
+
+/// Encrypts secrets still stored in plaintext: the S3 secret key, the Drive
+/// tokens and Supabase service keys. Returns how many were encrypted.
+#[instrument(skip(state, app))]
+pub async fn seal_plaintext_secrets(state: Arc<AppState>, app: &AppHandle) -> AppResult<u64> {
+    data_key()?;
+    let mut sealed = 0;
+    if settings::seal_plaintext_settings_secrets(app)? {
+        sealed += 1;
+    }
+    if drive::seal_plaintext_tokens(app)? {
+        sealed += 1;
+    }
+
+    let db = state.db.lock().await.clone();
+    let rows = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT id, supabase_service_key FROM sync_config \
+             WHERE supabase_service_key <> '' AND supabase_service_key NOT LIKE 'vault:%'",
+        ))
+        .await?;
+    for row in rows {
+        let id: i64 = row.try_get("", "id")?;
+        let service_key: String = row.try_get("", "supabase_service_key")?;
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE sync_config SET supabase_service_key = ? WHERE id = ?",
+            [seal_secret(&service_key)?.into(), id.into()],
+        ))
+        .await?;
+        sealed += 1;
+    }
+    Ok(sealed)
+}
+
+/// Reports how secrets are protected and whether they can be read right now.
+#[instrument(skip(app))]
+pub fn get_vault_status(app: &AppHandle) -> AppResult<VaultStatus> {
+    let mode = load_vault_file(app)?
+        .map(|file| file.mode)
+        .unwrap_or(VaultMode::MachineKey);
+    Ok(VaultStatus {
+        mode,
+        unlocked: current_key().is_some(),
+    })
+}
+
+/// Unlocks a master-password vault for this session, then encrypts any
+/// secrets left in plaintext.
+#[instrument(skip(state, app, master_password))]
+pub async fn unlock_vault(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    master_password: String,
+) -> AppResult<VaultStatus> {
+    let file = load_vault_file(app)?.ok_or_else(|| AppError::not_found("No vault found"))?;
+    if file.mode == VaultMode::MasterPassword {
+        let key = unwrap(&file, master_password.as_bytes())
+            .ok_or_else(|| AppError::invalid_input("Invalid master password"))?;
+        set_current_key(Some(key));
+    }
+    if let Err(err) = seal_plaintext_secrets(state, app).await {
+        tracing::warn!("Failed to encrypt plaintext secrets: {err}");
+    }
+    get_vault_status(app)
+}
+
+/// Forgets the data key until the next unlock. Only a master-password vault
+/// can be locked; a machine-key vault unlocks itself on startup anyway.
+#[instrument(skip(app))]
+pub fn lock_vault(app: &AppHandle) -> AppResult<VaultStatus> {
+    if get_vault_status(app)?.mode != VaultMode::MasterPassword {
+        return Err(AppError::invalid_input(
+            "Only a vault protected by the master password can be locked",
+        ));
+    }
+    set_current_key(None);
+    get_vault_status(app)
+}
+
+/// Switches between the machine key and the master password. The vault must
+/// be unlocked and the master password is required either way.
+#[instrument(skip(state, app, master_password))]
+pub async fn set_vault_mode(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    mode: VaultMode,
+    master_password: String,
+) -> AppResult<VaultStatus> {
+    let db = state.db.lock().await.clone();
+    verify_master_password(&db, &master_password).await?;
+    let key = data_key()?;
+    let secret = match mode {
+        VaultMode::MachineKey => machine_secret()?,
+        VaultMode::MasterPassword => master_password.into_bytes(),
+    };
+    save_vault_file(app, &wrap(mode, &key, &secret)?)?;
+    get_vault_status(app)
+}
+
+/// Re-wraps a master-password vault when the master password changes. Fails
+/// while the vault is locked, so the change does not strand the secrets.
+/// Returns the replaced vault.json for `restore_vault_file`, or `None` when
+/// nothing changed.
+pub(crate) fn rewrap_for_master_password(
+    app: &AppHandle,
+    new_master: &str,
+) -> AppResult<Option<VaultFile>> {
+    let Some(previous) = load_vault_file(app)? else {
+        return Ok(None);
+    };
+    if previous.mode != VaultMode::MasterPassword {
+        return Ok(None);
+    }
+    let key = current_key().ok_or_else(|| {
+        AppError::invalid_input("Unlock the vault before changing the master password")
+    })?;
+    save_vault_file(
+        app,
+        &wrap(VaultMode::MasterPassword, &key, new_master.as_bytes())?,
+    )?;
+    Ok(Some(previous))
+}
+
+/// Puts back the vault.json replaced by `rewrap_for_master_password` when the
+/// master password change around it fails.
+pub(crate) fn restore_vault_file(app: &AppHandle, previous: &VaultFile) -> AppResult<()> {
+    save_vault_file(app, previous)
+}
+
+/// Starts over with a new machine-key vault when the old one cannot be
+/// opened, e.g. after moving to another computer. Encrypted secrets are
+/// unrecoverable and cleared, so they have to be entered again.
+#[instrument(skip(state, app, master_password))]
+pub async fn reset_vault(
+    state: Arc<AppState>,
+    app: &AppHandle,
+    master_password: String,
+) -> AppResult<VaultStatus> {
+    let db = state.db.lock().await.clone();
+    verify_master_password(&db, &master_password).await?;
+
+    let key: [u8; 32] = Aes256Gcm::generate_key(OsRng).into();
+    save_vault_file(app, &wrap(VaultMode::MachineKey, &key, &machine_secret()?)?)?;
+    set_current_key(Some(key));
+
+    settings::clear_sealed_settings_secrets(app)?;
+    drive::clear_sealed_tokens(app)?;
+    db.execute_unprepared(
+        "UPDATE sync_config SET supabase_service_key = '' WHERE supabase_service_key LIKE 'vault:%'",
+    )
+    .await?;
+    db.execute_unprepared(
+        "UPDATE backup_targets SET s3_secret_access_key = '' \
+         WHERE s3_secret_access_key LIKE 'vault:%'",
+    )
+    .await?;
+    get_vault_status(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrapped_key_opens_only_with_the_new_master_password() {
+        let key: [u8; 32] = Aes256Gcm::generate_key(OsRng).into();
+        let old = wrap(VaultMode::MasterPassword, &key, b"old master").unwrap();
+        let new = wrap(VaultMode::MasterPassword, &key, b"new master").unwrap();
+
+        assert_eq!(unwrap(&old, b"old master"), Some(key));
+        assert_eq!(unwrap(&new, b"new master"), Some(key));
+        assert_eq!(unwrap(&new, b"old master"), None);
+    }
+
+    #[test]
+    fn passphrase_sealed_data_opens_only_with_the_same_passphrase() {
+        let sealed = seal_with_passphrase("correct horse", b"backup archive").unwrap();
+
+        assert_eq!(
+            open_with_passphrase("correct horse", &sealed).as_deref(),
+            Some(&b"backup archive"[..])
+        );
+        assert_eq!(open_with_passphrase("wrong horse", &sealed), None);
+        assert_eq!(
+            open_with_passphrase("correct horse", &sealed[..SALT_LEN]),
+            None
+        );
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(open_with_passphrase("correct horse", &tampered), None);
+    }
+}
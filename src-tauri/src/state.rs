@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use reqwest::Client;
@@ -5,6 +6,7 @@ use sea_orm::DatabaseConnection;
 use sqlx::{Pool, Sqlite};
 use tokio::sync::Mutex;
 
+use crate::services::scan::ScanSession;
 use crate::sync::client::SupabaseClient;
 
 /// Thin wrapper kept so the sync module can access the raw sqlx pool via
@@ -32,6 +34,8 @@ pub struct AppState {
     /// Raw sqlx pool shared with `AppDb` for the sync module.
     pub pool: Arc<Mutex<Pool<Sqlite>>>,
     pub supabase_client: SupabaseClient,
+    /// Open batch scan sessions keyed by session id.
+    pub scan_sessions: Mutex<HashMap<String, ScanSession>>,
 }
 
 impl AppState {
@@ -44,6 +48,32 @@ impl AppState {
             db: Mutex::new(db),
             pool,
             supabase_client: SupabaseClient::new(http_client),
+            scan_sessions: Mutex::new(HashMap::new()),
         }
     }
 }
+
+#[cfg(test)]
+impl AppState {
+    /// App state over a migrated in-memory database. The pool keeps a single
+    /// connection open for the whole test, since each connection to
+    /// `sqlite::memory:` gets its own empty database.
+    pub(crate) async fn for_tests() -> Arc<Self> {
+        use sea_orm::SqlxSqliteConnector;
+        use sea_orm_migration::MigratorTrait;
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .expect("open in-memory database");
+        let db = SqlxSqliteConnector::from_sqlx_sqlite_pool(pool.clone());
+        crate::migration::Migrator::up(&db, None)
+            .await
+            .expect("run migrations");
+        Arc::new(Self::new(db, Arc::new(Mutex::new(pool)), Client::new()))
+    }
+}
@@ -5,6 +5,7 @@ use sqlx::{Pool, Sqlite};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::services::vault::{redact, reveal_secret, seal_secret, REDACTED};
 use crate::state::AppDb;
 
 // ─── Structs ─────────────────────────────────────────────────────
@@ -496,8 +497,22 @@ pub async fn enqueue_sync(
     });
 }
 
-/// Load sync config from SQLite
+/// Load sync config from SQLite, with the service key decrypted. `None` while
+/// the vault is locked.
 async fn load_sync_config(pool: &Pool<Sqlite>) -> Option<SyncConfig> {
+    let mut config = load_stored_sync_config(pool).await?;
+    match reveal_secret(&config.supabase_service_key) {
+        Ok(service_key) => config.supabase_service_key = service_key,
+        Err(e) => {
+            tracing::warn!("Failed to decrypt sync service key: {e}");
+            return None;
+        }
+    }
+    Some(config)
+}
+
+/// Load sync config from SQLite as stored, with the service key still encrypted
+async fn load_stored_sync_config(pool: &Pool<Sqlite>) -> Option<SyncConfig> {
     let row: Option<(i64, String, String, String, i64, i64)> = sqlx::query_as(
         "SELECT id, supabase_url, supabase_anon_key, supabase_service_key, sync_enabled, COALESCE(sync_interval, 30) FROM sync_config WHERE is_active = 1 ORDER BY id DESC LIMIT 1"
     )
@@ -713,6 +728,16 @@ pub async fn save_sync_config(
     .map_err(|e| e.to_string())?
     .unwrap_or(30);
 
+    // A redacted key sent back by the frontend keeps the stored one
+    let service_key = if service_key == REDACTED {
+        load_stored_sync_config(&pool)
+            .await
+            .map(|config| config.supabase_service_key)
+            .unwrap_or_default()
+    } else {
+        seal_secret(&service_key).map_err(|e| e.to_string())?
+    };
+
     // Deactivate existing configs
     sqlx::query("UPDATE sync_config SET is_active = 0")
         .execute(&*pool)
@@ -738,7 +763,10 @@ pub async fn save_sync_config(
 pub async fn get_sync_config(app: AppHandle) -> Result<Option<SyncConfig>, String> {
     let db = app.state::<AppDb>();
     let pool = db.0.lock().await;
-    let config = load_sync_config(&pool).await;
+    let config = load_stored_sync_config(&pool).await.map(|mut config| {
+        config.supabase_service_key = redact(&config.supabase_service_key);
+        config
+    });
     Ok(config)
 }
 
@@ -991,14 +1019,38 @@ pub async fn set_master_password(
         .map_err(|e| format!("Failed to hash master password: {}", e))?
         .to_string();
 
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
     sqlx::query("UPDATE users SET master_password_hash = ? WHERE id = ?")
         .bind(master_hash)
         .bind(user_id)
-        .execute(&*pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(())
+    // A vault and backups protected by the master password follow the change
+    // only once the new hash is written; a failure puts the old files back
+    // and rolls the hash back with the dropped transaction.
+    let previous_vault = crate::services::vault::rewrap_for_master_password(&app, &new_master)
+        .map_err(|e| e.to_string())?;
+    let followed = crate::services::backup::follow_master_password_change(&app, &new_master)
+        .map_err(|e| e.to_string());
+    let result = match &followed {
+        Ok(_) => tx.commit().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.clone()),
+    };
+    if result.is_err() {
+        if let Some(previous) = &previous_vault {
+            if let Err(e) = crate::services::vault::restore_vault_file(&app, previous) {
+                tracing::error!("Failed to restore vault.json: {e}");
+            }
+        }
+        if let Ok(Some(previous)) = &followed {
+            if let Err(e) = crate::services::settings::write_app_settings(&app, previous) {
+                tracing::error!("Failed to restore backup settings: {e}");
+            }
+        }
+    }
+    result
 }
 
 #[tauri::command]
@@ -1076,7 +1128,7 @@ pub async fn migrate_to_new_database(
     )
     .bind(&new_supabase_url)
     .bind(&new_anon_key)
-    .bind(&new_service_key)
+    .bind(seal_secret(&new_service_key).map_err(|e| e.to_string())?)
     .bind(current_interval)
     .execute(&*pool)
     .await
@@ -1701,6 +1753,8 @@ pub async fn apply_remote_changes(
     }
 
     let mut applied_count = 0;
+    let touches_expenses = changes.iter().any(|change| change.table_name == "expenses");
+    let touches_orders = changes.iter().any(|change| change.table_name == "orders");
 
     for change in changes {
         let table = change.table_name.as_str();
@@ -1883,6 +1937,20 @@ pub async fn apply_remote_changes(
         }
     }
 
+    if touches_expenses || touches_orders {
+        let db = sea_orm::SqlxSqliteConnector::from_sqlx_sqlite_pool(pool.clone());
+        if touches_expenses {
+            if let Err(err) = crate::services::expense_category::register_expense_names(&db).await {
+                tracing::warn!("Failed to register synced expense categories: {err}");
+            }
+        }
+        if touches_orders {
+            if let Err(err) = crate::services::order::normalize_order_dates(&db).await {
+                tracing::warn!("Failed to normalize synced order dates: {err}");
+            }
+        }
+    }
+
     Ok(format!(
         "Successfully applied {} remote changes locally.",
         applied_count
@@ -16,6 +16,11 @@ export const reloadScheduler = async (): Promise<void> => {
   return invoke("reload_scheduler");
 };
 
+/** When the next scheduled backup runs; `null` when none are scheduled. */
+export const getNextBackupRun = async (): Promise<string | null> => {
+  return invoke<string | null>("get_next_backup_run");
+};
+
 export const checkIsOnboarded = async (): Promise<boolean> => {
   return invoke<boolean>("check_is_onboarded");
 };
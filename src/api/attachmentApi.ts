@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Attachment, AttachmentEntityType } from "../types/attachment";
+
+/** Copies a file into app data and attaches it to the record. */
+export const addAttachment = async (
+  entityType: AttachmentEntityType,
+  entityId: string,
+  filePath: string,
+  uploadToS3 = false,
+): Promise<Attachment> => {
+  return await invoke("add_attachment", {
+    entityType,
+    entityId,
+    filePath,
+    uploadToS3,
+  });
+};
+
+export const getAttachments = async (
+  entityType: AttachmentEntityType,
+  entityId: string,
+): Promise<Attachment[]> => {
+  return await invoke("get_attachments", { entityType, entityId });
+};
+
+export const uploadAttachmentToS3 = async (id: string): Promise<Attachment> => {
+  return await invoke("upload_attachment_to_s3", { id });
+};
+
+export const deleteAttachment = async (id: string): Promise<void> => {
+  return await invoke("delete_attachment", { id });
+};
@@ -1,5 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
-import { Customer, CustomerMutationInput } from "../types/customer";
+import {
+  Customer,
+  CustomerMatch,
+  CustomerMergeRecord,
+  CustomerMergeResult,
+  CustomerMutationInput,
+  DuplicateCandidate,
+} from "../types/customer";
+import { CursorPage, CursorPageParams } from "../types/pagination";
+import { CustomerListFilter } from "./exportApi";
 
 export const CUSTOMER_PAGE_SIZE_LIMITS = {
   min: 5,
@@ -57,8 +66,24 @@ export const getCustomersPaginated = async (
   });
 };
 
+export const getCustomersPage = async (
+  params: CursorPageParams<CustomerListFilter> = {},
+): Promise<CursorPage<Customer>> => {
+  return await invoke("get_customers_page", {
+    filter: params.filter ?? null,
+    cursor: params.cursor ?? null,
+    limit: params.limit ?? null,
+    includeTotal: params.includeTotal ?? false,
+  });
+};
+
+/**
+ * Rejects with code `duplicate_customer` when the phone or social profile
+ * matches an existing customer, unless `allowDuplicate` is set.
+ */
 export const createCustomer = async (
   customer: CustomerMutationInput,
+  options?: { allowDuplicate?: boolean },
 ): Promise<string> => {
   return await invoke("create_customer", {
     name: customer.name,
@@ -72,6 +97,7 @@ export const createCustomer = async (
     createdAt: customer.created_at,
     updatedAt: customer.updated_at,
     deletedAt: customer.deleted_at,
+    allowDuplicate: options?.allowDuplicate ?? false,
   });
 };
 
@@ -97,6 +123,45 @@ export const deleteCustomer = async (id: string): Promise<void> => {
   return await invoke("delete_customer", { id });
 };
 
+export const checkCustomerDuplicates = async (params: {
+  name: string;
+  phone?: string | null;
+  socialMediaUrl?: string | null;
+  excludeId?: string | null;
+  minNameSimilarity?: number | null;
+}): Promise<CustomerMatch[]> => {
+  return await invoke("check_customer_duplicates", {
+    name: params.name,
+    phone: params.phone ?? null,
+    socialMediaUrl: params.socialMediaUrl ?? null,
+    excludeId: params.excludeId ?? null,
+    minNameSimilarity: params.minNameSimilarity ?? null,
+  });
+};
+
+export const findDuplicateCustomers = async (
+  minNameSimilarity?: number,
+): Promise<DuplicateCandidate[]> => {
+  return await invoke("find_duplicate_customers", {
+    minNameSimilarity: minNameSimilarity ?? null,
+  });
+};
+
+export const mergeCustomers = async (
+  survivorId: string,
+  mergedId: string,
+): Promise<CustomerMergeResult> => {
+  return await invoke("merge_customers", { survivorId, mergedId });
+};
+
+export const getCustomerMerges = async (
+  customerId?: string,
+): Promise<CustomerMergeRecord[]> => {
+  return await invoke("get_customer_merges", {
+    customerId: customerId ?? null,
+  });
+};
+
 export const getCustomerById = async (id: string): Promise<Customer> => {
   return await invoke("get_customer", { id });
 };
@@ -1,9 +1,18 @@
 import { invoke } from "@tauri-apps/api/core";
 
+import type {
+  BackupEncryption,
+  BackupEncryptionInput,
+  BackupRun,
+  BackupTarget,
+  BackupTargetInput,
+  RemoteBackup,
+} from "../types/backup";
 import type {
   DbStatus,
   DriveConnectionStatus,
   ResetTableSequenceResult,
+  StoredObject,
 } from "../types/settings";
 
 export const getDbStatus = async (): Promise<DbStatus> => {
@@ -32,6 +41,82 @@ export const triggerDriveBackup = async (): Promise<void> => {
   return invoke("trigger_drive_backup");
 };
 
+/** Uploads a database backup to the configured storage; returns its key. */
+export const triggerStorageBackup = async (): Promise<string> => {
+  return invoke<string>("trigger_storage_backup");
+};
+
+export const listStorageBackups = async (): Promise<StoredObject[]> => {
+  return invoke<StoredObject[]>("list_storage_backups");
+};
+
+export const getBackupTargets = async (): Promise<BackupTarget[]> => {
+  return invoke<BackupTarget[]>("get_backup_targets");
+};
+
+export const createBackupTarget = async (
+  input: BackupTargetInput,
+): Promise<BackupTarget> => {
+  return invoke<BackupTarget>("create_backup_target", { input });
+};
+
+export const updateBackupTarget = async (
+  id: string,
+  input: BackupTargetInput,
+): Promise<BackupTarget> => {
+  return invoke<BackupTarget>("update_backup_target", { id, input });
+};
+
+export const deleteBackupTarget = async (id: string): Promise<void> => {
+  return invoke("delete_backup_target", { id });
+};
+
+export const testBackupTarget = async (id: string): Promise<void> => {
+  return invoke("test_backup_target", { id });
+};
+
+/** Backs up to one target, or to every enabled target when none is given. */
+export const runBackupNow = async (targetId?: string): Promise<BackupRun[]> => {
+  return invoke<BackupRun[]>("run_backup_now", { targetId });
+};
+
+export const getBackupRuns = async (
+  targetId?: string,
+  limit?: number,
+): Promise<BackupRun[]> => {
+  return invoke<BackupRun[]>("get_backup_runs", { targetId, limit });
+};
+
+export const listTargetBackups = async (
+  targetId: string,
+): Promise<RemoteBackup[]> => {
+  return invoke<RemoteBackup[]>("list_target_backups", { targetId });
+};
+
+/** Restores a backup from a target; the current data is copied aside first. */
+/** `passphrase` overrides the stored one for encrypted backups. */
+export const restoreFromBackupTarget = async (
+  targetId: string,
+  backupId: string,
+  passphrase?: string,
+): Promise<void> => {
+  return invoke("restore_from_backup_target", {
+    targetId,
+    backupId,
+    passphrase,
+  });
+};
+
+export const getBackupEncryption = async (): Promise<BackupEncryption> => {
+  return invoke<BackupEncryption>("get_backup_encryption");
+};
+
+export const setBackupEncryption = async (
+  input: BackupEncryptionInput,
+): Promise<BackupEncryption> => {
+  return invoke<BackupEncryption>("set_backup_encryption", { input });
+};
+
 export const resetAppData = async (): Promise<void> => {
   return invoke("reset_app_data");
 };
@@ -40,6 +125,9 @@ export const backupDatabase = async (destPath: string): Promise<void> => {
   return invoke("backup_database", { destPath });
 };
 
-export const restoreDatabase = async (restorePath: string): Promise<void> => {
-  return invoke("restore_database", { restorePath });
+export const restoreDatabase = async (
+  restorePath: string,
+  passphrase?: string,
+): Promise<void> => {
+  return invoke("restore_database", { restorePath, passphrase });
 };
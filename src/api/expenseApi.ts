@@ -1,5 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { Expense, PaginatedExpenses } from "../types/expense";
+import { CursorPage, CursorPageParams } from "../types/pagination";
+import { ExpenseListFilter } from "./exportApi";
 
 export const EXPENSE_PAGE_SIZE_LIMITS = {
   min: 5,
@@ -68,6 +70,17 @@ export const getExpensesPaginated = async (
   });
 };
 
+export const getExpensesPage = async (
+  params: CursorPageParams<ExpenseListFilter> = {},
+): Promise<CursorPage<Expense>> => {
+  return await invoke("get_expenses_page", {
+    filter: params.filter ?? null,
+    cursor: params.cursor ?? null,
+    limit: params.limit ?? null,
+    includeTotal: params.includeTotal ?? false,
+  });
+};
+
 export const getExpenseById = async (id: string): Promise<Expense> => {
   return await invoke("get_expense", { id });
 };
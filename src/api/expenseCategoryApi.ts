@@ -0,0 +1,84 @@
+import { invoke } from "@tauri-apps/api/core";
+import {
+  BudgetReportRow,
+  ExpenseCategory,
+  PaymentMethod,
+} from "../types/expense";
+
+export interface ExpenseCategoryInput {
+  name: string;
+  parentId?: string | null;
+  monthlyBudget?: number | null;
+}
+
+export const getExpenseCategories = async (): Promise<ExpenseCategory[]> => {
+  return await invoke("get_expense_categories");
+};
+
+export const createExpenseCategory = async (
+  input: ExpenseCategoryInput,
+): Promise<ExpenseCategory> => {
+  return await invoke("create_expense_category", {
+    name: input.name,
+    parentId: input.parentId ?? null,
+    monthlyBudget: input.monthlyBudget ?? null,
+  });
+};
+
+export const updateExpenseCategory = async (
+  id: string,
+  input: ExpenseCategoryInput,
+): Promise<ExpenseCategory> => {
+  return await invoke("update_expense_category", {
+    id,
+    name: input.name,
+    parentId: input.parentId ?? null,
+    monthlyBudget: input.monthlyBudget ?? null,
+  });
+};
+
+/** Deletes a category; its expenses move to `replacementId` (a merge). */
+export const deleteExpenseCategory = async (
+  id: string,
+  replacementId?: string | null,
+): Promise<number> => {
+  return await invoke("delete_expense_category", {
+    id,
+    replacementId: replacementId ?? null,
+  });
+};
+
+export const getPaymentMethods = async (): Promise<PaymentMethod[]> => {
+  return await invoke("get_payment_methods");
+};
+
+export const createPaymentMethod = async (
+  name: string,
+): Promise<PaymentMethod> => {
+  return await invoke("create_payment_method", { name });
+};
+
+export const updatePaymentMethod = async (
+  id: string,
+  name: string,
+): Promise<PaymentMethod> => {
+  return await invoke("update_payment_method", { id, name });
+};
+
+export const deletePaymentMethod = async (
+  id: string,
+  replacementId?: string | null,
+): Promise<number> => {
+  return await invoke("delete_payment_method", {
+    id,
+    replacementId: replacementId ?? null,
+  });
+};
+
+/** Months are `YYYY-MM`, inclusive, at most 36 months apart. */
+export const getBudgetReport = async (
+  fromMonth: string,
+  toMonth: string,
+): Promise<BudgetReportRow[]> => {
+  return await invoke("get_budget_report", { fromMonth, toMonth });
+};
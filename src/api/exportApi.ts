@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+import { OrderConditions } from "../types/order";
+
+export type ExportFormat = "csv" | "xlsx";
+
+export interface ExportSummary {
+  path: string;
+  format: ExportFormat;
+  row_count: number;
+}
+
+export interface OrderListFilter {
+  search_key?: string | null;
+  search_term?: string | null;
+  status_filter?: string | null;
+  sort_by?: string | null;
+  sort_order?: "asc" | "desc" | null;
+  conditions?: OrderConditions | null;
+}
+
+export interface CustomerListFilter {
+  search_key?: string | null;
+  search_term?: string | null;
+  sort_by?: string | null;
+  sort_order?: "asc" | "desc" | null;
+}
+
+export interface ExpenseListFilter {
+  search_key?: string | null;
+  search_term?: string | null;
+  category_filter?: string | null;
+  date_from?: string | null;
+  date_to?: string | null;
+  sort_by?: string | null;
+  sort_order?: "asc" | "desc" | null;
+}
+
+interface ExportParams<TFilter> {
+  destPath: string;
+  format?: ExportFormat | null;
+  columns?: string[] | null;
+  filter?: TFilter | null;
+}
+
+const toArgs = <TFilter>(params: ExportParams<TFilter>) => ({
+  destPath: params.destPath,
+  format: params.format ?? null,
+  columns: params.columns ?? null,
+  filter: params.filter ?? null,
+});
+
+export const exportOrders = async (
+  params: ExportParams<OrderListFilter>,
+): Promise<ExportSummary> => {
+  return invoke("export_orders", toArgs(params));
+};
+
+export const exportCustomers = async (
+  params: ExportParams<CustomerListFilter>,
+): Promise<ExportSummary> => {
+  return invoke("export_customers", toArgs(params));
+};
+
+export const exportExpenses = async (
+  params: ExportParams<ExpenseListFilter>,
+): Promise<ExportSummary> => {
+  return invoke("export_expenses", toArgs(params));
+};
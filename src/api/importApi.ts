@@ -0,0 +1,99 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ImportEntity = "customers" | "orders" | "order_items";
+
+export type ImportFormat = "csv" | "xlsx" | "xlsm" | "xls" | "ods";
+
+/** Target field key -> source column header. */
+export type ImportMapping = Record<string, string>;
+
+export interface ImportFieldInfo {
+  key: string;
+  label: string;
+  required: boolean;
+}
+
+export interface ImportPreview {
+  headers: string[];
+  sample_rows: string[][];
+  total_rows: number;
+  fields: ImportFieldInfo[];
+  suggested_mapping: ImportMapping;
+}
+
+export type ImportIssueCode =
+  | "missing_name"
+  | "missing_value"
+  | "invalid_phone"
+  | "unknown_customer"
+  | "ambiguous_customer"
+  | "unknown_order"
+  | "invalid_date"
+  | "invalid_number"
+  | "invalid_status"
+  | "invalid_value"
+  | "conflicting_order";
+
+export interface ImportIssue {
+  field: string;
+  code: ImportIssueCode;
+  message: string;
+}
+
+export interface ImportRowReport {
+  row: number;
+  record_code: string | null;
+  issues: ImportIssue[];
+}
+
+export interface ImportReport {
+  entity: ImportEntity;
+  dry_run: boolean;
+  committed: boolean;
+  total_rows: number;
+  valid_rows: number;
+  invalid_rows: number;
+  created_count: number;
+  rows: ImportRowReport[];
+}
+
+interface ImportParams {
+  path: string;
+  format?: ImportFormat | null;
+  mapping?: ImportMapping | null;
+  /** Defaults to true; pass false to commit. */
+  dryRun?: boolean;
+}
+
+const toArgs = (params: ImportParams) => ({
+  path: params.path,
+  format: params.format ?? null,
+  mapping: params.mapping ?? null,
+  dryRun: params.dryRun ?? true,
+});
+
+export const previewImport = async (
+  path: string,
+  entity: ImportEntity,
+  format?: ImportFormat | null,
+): Promise<ImportPreview> => {
+  return invoke("preview_import", { path, entity, format: format ?? null });
+};
+
+export const importCustomers = async (
+  params: ImportParams,
+): Promise<ImportReport> => {
+  return invoke("import_customers", toArgs(params));
+};
+
+export const importOrders = async (
+  params: ImportParams,
+): Promise<ImportReport> => {
+  return invoke("import_orders", toArgs(params));
+};
+
+export const importOrderItems = async (
+  params: ImportParams,
+): Promise<ImportReport> => {
+  return invoke("import_order_items", toArgs(params));
+};
@@ -0,0 +1,9 @@
+import { invoke } from "@tauri-apps/api/core";
+import { JobRun, ScheduledJob } from "../types/jobRun";
+
+export const getJobRuns = async (
+  job?: ScheduledJob,
+  limit?: number,
+): Promise<JobRun[]> => {
+  return await invoke("get_job_runs", { job, limit });
+};
@@ -22,8 +22,11 @@ export const saveShopSetup = async (
   });
 };
 
-export const restoreDatabase = async (restorePath: string): Promise<void> => {
-  return invoke("restore_database", { restorePath });
+export const restoreDatabase = async (
+  restorePath: string,
+  passphrase?: string,
+): Promise<void> => {
+  return invoke("restore_database", { restorePath, passphrase });
 };
 
 export const updateAppLanguage = async (language: string): Promise<void> => {
@@ -7,7 +7,10 @@ import {
   PaginatedOrders,
   OrderExportRow,
   OrderStatus,
+  OrderConditions,
 } from "../types/order";
+import { CursorPage, CursorPageParams } from "../types/pagination";
+import { OrderListFilter } from "./exportApi";
 
 export const ORDER_PAGE_SIZE_LIMITS = {
   min: 5,
@@ -43,6 +46,7 @@ export interface OrderSearchParams {
   statusFilter?: OrderStatus | "all";
   sortBy?: "customer_name" | "order_id" | "created_at";
   sortOrder?: "asc" | "desc";
+  conditions?: OrderConditions | null;
 }
 
 export const getOrdersPaginated = async (
@@ -59,6 +63,18 @@ export const getOrdersPaginated = async (
         : undefined,
     sortBy: params.sortBy,
     sortOrder: params.sortOrder,
+    conditions: params.conditions ?? null,
+  });
+};
+
+export const getOrdersPage = async (
+  params: CursorPageParams<OrderListFilter> = {},
+): Promise<CursorPage<OrderWithCustomer>> => {
+  return await invoke("get_orders_page", {
+    filter: params.filter ?? null,
+    cursor: params.cursor ?? null,
+    limit: params.limit ?? null,
+    includeTotal: params.includeTotal ?? false,
   });
 };
 
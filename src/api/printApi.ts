@@ -1,12 +1,70 @@
 import { invoke } from "@tauri-apps/api/core";
+import { LabelSheetResult } from "../types/labelPrint";
+import { LabelSheetSize } from "../types/settings";
+
+export interface PrinterInfo {
+  name: string;
+  status: "idle" | "printing" | "disabled" | "unknown";
+  is_default: boolean;
+}
+
+export interface PrintJob {
+  job_id: string | null;
+  printer: string | null;
+}
+
+export interface PrintJobStatus {
+  job_id: string;
+  state: "queued" | "printing" | "completed" | "unknown";
+}
 
 export const printInvoiceDirect = async (
   bytes: number[],
   printerName: string | null,
-): Promise<void> => {
+): Promise<PrintJob> => {
   return invoke("print_invoice_direct", { bytes, printerName });
 };
 
+export const printOrderReceipt = async (orderId: string): Promise<PrintJob> => {
+  return invoke("print_order_receipt", { orderId });
+};
+
+export const listPrinters = async (): Promise<PrinterInfo[]> => {
+  return invoke("list_printers");
+};
+
+export const validatePrinter = async (
+  printerName?: string | null,
+): Promise<PrinterInfo> => {
+  return invoke("validate_printer", { printerName: printerName ?? null });
+};
+
+export const getPrintJobStatus = async (
+  jobId: string,
+): Promise<PrintJobStatus> => {
+  return invoke("get_print_job_status", { jobId });
+};
+
+export const testPrint = async (
+  printerName?: string | null,
+): Promise<PrintJob> => {
+  return invoke("test_print", { printerName: printerName ?? null });
+};
+
+export const generateParcelLabels = async (params: {
+  orderIds?: string[] | null;
+  status?: string | null;
+  sheetSize?: LabelSheetSize | null;
+  destPath: string;
+}): Promise<LabelSheetResult> => {
+  return invoke("generate_parcel_labels", {
+    orderIds: params.orderIds ?? null,
+    status: params.status ?? null,
+    sheetSize: params.sheetSize ?? null,
+    destPath: params.destPath,
+  });
+};
+
 export const printWindow = async (): Promise<void> => {
   return invoke("print_window");
 };
@@ -0,0 +1,60 @@
+import { invoke } from "@tauri-apps/api/core";
+import { OrderItemPhoto } from "../types/productPhoto";
+
+export const addOrderItemPhotoFromFile = async (
+  orderItemId: string,
+  filePath: string,
+  uploadToS3 = false,
+): Promise<OrderItemPhoto> => {
+  return await invoke("add_order_item_photo_from_file", {
+    orderItemId,
+    filePath,
+    uploadToS3,
+  });
+};
+
+/** Saves the image currently on the clipboard as a PNG. */
+export const addOrderItemPhotoFromClipboard = async (
+  orderItemId: string,
+  uploadToS3 = false,
+): Promise<OrderItemPhoto> => {
+  return await invoke("add_order_item_photo_from_clipboard", {
+    orderItemId,
+    uploadToS3,
+  });
+};
+
+export const addOrderItemPhotoFromUrl = async (
+  orderItemId: string,
+  url: string,
+  uploadToS3 = false,
+): Promise<OrderItemPhoto> => {
+  return await invoke("add_order_item_photo_from_url", {
+    orderItemId,
+    url,
+    uploadToS3,
+  });
+};
+
+export const getOrderItemPhotos = async (
+  orderItemId: string,
+): Promise<OrderItemPhoto[]> => {
+  return await invoke("get_order_item_photos", { orderItemId });
+};
+
+/** Photos of every item of an order, for the detail page. */
+export const getOrderPhotos = async (
+  orderId: string,
+): Promise<OrderItemPhoto[]> => {
+  return await invoke("get_order_photos", { orderId });
+};
+
+export const uploadOrderItemPhotoToS3 = async (
+  id: string,
+): Promise<OrderItemPhoto> => {
+  return await invoke("upload_order_item_photo_to_s3", { id });
+};
+
+export const deleteOrderItemPhoto = async (id: string): Promise<void> => {
+  return await invoke("delete_order_item_photo", { id });
+};
@@ -0,0 +1,39 @@
+import { invoke } from "@tauri-apps/api/core";
+import {
+  RecurringExpense,
+  RecurringExpenseInput,
+  UpcomingRecurringExpense,
+} from "../types/expense";
+
+export const getRecurringExpenses = async (): Promise<RecurringExpense[]> => {
+  return await invoke("get_recurring_expenses");
+};
+
+export const createRecurringExpense = async (
+  input: RecurringExpenseInput,
+): Promise<RecurringExpense> => {
+  return await invoke("create_recurring_expense", { input });
+};
+
+export const updateRecurringExpense = async (
+  id: string,
+  input: RecurringExpenseInput,
+): Promise<RecurringExpense> => {
+  return await invoke("update_recurring_expense", { id, input });
+};
+
+export const deleteRecurringExpense = async (id: string): Promise<void> => {
+  return await invoke("delete_recurring_expense", { id });
+};
+
+/** Occurrences not yet posted within the next `days` days (default 30). */
+export const getUpcomingRecurringExpenses = async (
+  days?: number,
+): Promise<UpcomingRecurringExpense[]> => {
+  return await invoke("get_upcoming_recurring_expenses", { days: days ?? null });
+};
+
+/** Posts due occurrences now; returns how many expenses were created. */
+export const generateRecurringExpenses = async (): Promise<number> => {
+  return await invoke("generate_recurring_expenses");
+};
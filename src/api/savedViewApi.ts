@@ -0,0 +1,96 @@
+import { invoke } from "@tauri-apps/api/core";
+import { PaginatedCustomers } from "./customerApi";
+import { PaginatedExpenses } from "../types/expense";
+import { PaginatedOrders } from "../types/order";
+import {
+  CustomerListFilter,
+  ExpenseListFilter,
+  OrderListFilter,
+} from "./exportApi";
+
+export type SavedViewEntity = "orders" | "customers" | "expenses";
+
+export interface SavedView {
+  id: string;
+  owner_id: string;
+  name: string;
+  entity: SavedViewEntity;
+  /** The list filter of `entity`, including search and sort. */
+  filter: OrderListFilter | CustomerListFilter | ExpenseListFilter;
+  /** Column keys in display order; null shows all columns. */
+  columns: string[] | null;
+  is_shared: boolean;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface SavedViewRun {
+  view: SavedView;
+  /** Shape follows `view.entity`. */
+  results: PaginatedOrders | PaginatedCustomers | PaginatedExpenses;
+}
+
+export interface SavedViewInput {
+  name: string;
+  entity: SavedViewEntity;
+  filter: OrderListFilter | CustomerListFilter | ExpenseListFilter;
+  columns?: string[] | null;
+  isShared?: boolean;
+}
+
+export const createSavedView = async (
+  userId: string,
+  input: SavedViewInput,
+): Promise<SavedView> => {
+  return invoke("create_saved_view", {
+    userId,
+    name: input.name,
+    entity: input.entity,
+    filter: input.filter,
+    columns: input.columns ?? null,
+    isShared: input.isShared ?? false,
+  });
+};
+
+export const getSavedViews = async (
+  userId: string,
+  entity?: SavedViewEntity,
+): Promise<SavedView[]> => {
+  return invoke("get_saved_views", { userId, entity: entity ?? null });
+};
+
+export const updateSavedView = async (
+  userId: string,
+  id: string,
+  changes: Partial<Omit<SavedViewInput, "entity">>,
+): Promise<SavedView> => {
+  return invoke("update_saved_view", {
+    userId,
+    id,
+    name: changes.name ?? null,
+    filter: changes.filter ?? null,
+    columns: changes.columns ?? null,
+    isShared: changes.isShared ?? null,
+  });
+};
+
+export const deleteSavedView = async (
+  userId: string,
+  id: string,
+): Promise<void> => {
+  return invoke("delete_saved_view", { userId, id });
+};
+
+export const runSavedView = async (
+  userId: string,
+  id: string,
+  page?: number,
+  pageSize?: number,
+): Promise<SavedViewRun> => {
+  return invoke("run_saved_view", {
+    userId,
+    id,
+    page: page ?? null,
+    pageSize: pageSize ?? null,
+  });
+};
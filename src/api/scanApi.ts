@@ -0,0 +1,41 @@
+import { invoke } from "@tauri-apps/api/core";
+import { OrderStatus } from "../types/order";
+import { ScanResult, ScanSessionReport } from "../types/scan";
+
+export const scanOrderCode = async (
+  code: string,
+  targetStatus?: OrderStatus | "" | null,
+): Promise<ScanResult> => {
+  return invoke("scan_order_code", { code, targetStatus: targetStatus ?? null });
+};
+
+export const startScanSession = async (params: {
+  expectedOrderIds?: string[] | null;
+  expectedStatus?: OrderStatus | null;
+  targetStatus?: OrderStatus | "" | null;
+}): Promise<ScanSessionReport> => {
+  return invoke("start_scan_session", {
+    expectedOrderIds: params.expectedOrderIds ?? null,
+    expectedStatus: params.expectedStatus ?? null,
+    targetStatus: params.targetStatus ?? null,
+  });
+};
+
+export const scanInSession = async (
+  sessionId: string,
+  code: string,
+): Promise<ScanSessionReport> => {
+  return invoke("scan_in_session", { sessionId, code });
+};
+
+export const getScanSession = async (
+  sessionId: string,
+): Promise<ScanSessionReport> => {
+  return invoke("get_scan_session", { sessionId });
+};
+
+export const closeScanSession = async (
+  sessionId: string,
+): Promise<ScanSessionReport> => {
+  return invoke("close_scan_session", { sessionId });
+};
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type SearchEntityType = "customer" | "order" | "expense";
+
+export interface SearchHit {
+  entity_type: SearchEntityType;
+  entity_id: string;
+  title: string;
+  subtitle: string | null;
+  snippet: string | null;
+  /** Lower is a better match. */
+  rank: number;
+}
+
+export const globalSearch = async (
+  query: string,
+  options: { entityTypes?: SearchEntityType[]; limit?: number } = {},
+): Promise<SearchHit[]> => {
+  return invoke("global_search", {
+    query,
+    entityTypes: options.entityTypes ?? null,
+    limit: options.limit ?? null,
+  });
+};
+
+export const rebuildSearchIndex = async (): Promise<number> => {
+  return invoke("rebuild_search_index");
+};
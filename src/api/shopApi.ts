@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 
-import type { AwsS3ConnectionStatus } from "../types/settings";
+import type { AwsS3Config, AwsS3ConnectionStatus } from "../types/settings";
 
 export interface ShopSettingsRecord {
   shop_name: string;
@@ -49,12 +49,7 @@ export const getAwsS3ConnectionStatus = async (): Promise<AwsS3ConnectionStatus>
 };
 
 export const testAwsS3Connection = async (
-  config: {
-    access_key_id: string;
-    secret_access_key: string;
-    region: string;
-    bucket_name: string;
-  },
+  config: AwsS3Config,
 ): Promise<AwsS3ConnectionStatus> => {
   return invoke<AwsS3ConnectionStatus>("test_aws_s3_connection", { config });
 };
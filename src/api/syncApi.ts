@@ -6,6 +6,7 @@ export interface SyncConfig {
   id?: number;
   supabase_url: string;
   supabase_anon_key: string;
+  /** Shown as `********` once saved; send it back unchanged to keep it. */
   supabase_service_key: string;
   sync_enabled: boolean;
   sync_interval: number;
@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+import { VaultMode, VaultStatus } from "../types/vault";
+
+export const getVaultStatus = async (): Promise<VaultStatus> => {
+  return await invoke("get_vault_status");
+};
+
+export const unlockVault = async (
+  masterPassword: string,
+): Promise<VaultStatus> => {
+  return await invoke("unlock_vault", { masterPassword });
+};
+
+export const lockVault = async (): Promise<VaultStatus> => {
+  return await invoke("lock_vault");
+};
+
+export const setVaultMode = async (
+  mode: VaultMode,
+  masterPassword: string,
+): Promise<VaultStatus> => {
+  return await invoke("set_vault_mode", { mode, masterPassword });
+};
+
+/** Starts over with a new vault; previously stored credentials are cleared. */
+export const resetVault = async (
+  masterPassword: string,
+): Promise<VaultStatus> => {
+  return await invoke("reset_vault", { masterPassword });
+};
@@ -45,15 +45,32 @@ const DEFAULT_SETTINGS = {
   exchange_currency_symbol: "Ks",
   invoice_printer_name: "",
   silent_invoice_print: true,
+  receipt_printer_transport: "cups",
+  receipt_printer_target: "",
+  receipt_paper_width: "80mm",
+  scan_target_status: "shipping",
+  scan_date_field: "arrived_date",
+  label_sheet_size: "a4_14",
+  label_font_path: "",
+  default_phone_country: "MM",
   auto_backup: true,
   backup_frequency: "never",
   backup_time: "23:00",
+  backup_weekday: 0,
+  backup_month_day: 1,
+  backup_cron: "",
   font_size: "normal",
   aws_access_key_id: "",
   aws_secret_access_key: "",
   aws_region: "",
   aws_bucket_name: "",
   imagekit_base_url: "",
+  storage_provider: "s3",
+  s3_endpoint_url: "",
+  s3_force_path_style: true,
+  local_storage_dir: "",
+  backup_encryption: "none",
+  backup_passphrase: "",
 } satisfies AppSettings;
 
 export function AppSettingsProvider({
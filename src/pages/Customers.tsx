@@ -598,7 +598,22 @@ export default function Customers() {
           updated_at: undefined,
         });
       } else {
-        await createCustomer(normalizedFormData);
+        try {
+          await createCustomer(normalizedFormData);
+        } catch (error) {
+          const appError = error as { code?: string; message?: string };
+          if (
+            appError?.code !== "duplicate_customer" ||
+            !window.confirm(
+              t("customers.form.duplicate_confirm", {
+                message: appError.message,
+              }),
+            )
+          ) {
+            throw error;
+          }
+          await createCustomer(normalizedFormData, { allowDuplicate: true });
+        }
       }
       setFormData(normalizedFormData);
       playSound("success");
@@ -62,6 +62,7 @@ const buildOrderUpdatePayload = (
     status: order.status || "pending",
     order_from: order.order_from,
     items: items.map((item) => ({
+      id: item.id,
       product_url: item.product_url,
       product_qty: item.product_qty,
       price: item.price,
@@ -723,6 +723,7 @@ export default function Orders() {
         const nextItems =
           detail.items.length > 0
             ? detail.items.map((item) => ({
+                id: item.id,
                 product_url: item.product_url || "",
                 product_qty: item.product_qty ?? 1,
                 price: item.price ?? 0,
@@ -804,6 +805,7 @@ export default function Orders() {
         status: formData.status || "pending",
         order_from: formData.order_from || undefined,
         items: formData.items.map((item) => ({
+          id: item.id,
           product_url: item.product_url.trim() || undefined,
           product_qty:
             item.product_qty > 0 ? Number(item.product_qty) : undefined,
@@ -0,0 +1,16 @@
+export type AttachmentEntityType = "expense" | "order" | "customer";
+
+export interface Attachment {
+  id: string;
+  entity_type: AttachmentEntityType;
+  entity_id: string;
+  /** Original file name, as picked by the user. */
+  file_name: string;
+  content_type: string;
+  size_bytes: number;
+  local_path: string;
+  /** JPEG preview for images; null for other files. */
+  thumbnail_path: string | null;
+  cloud_url: string | null;
+  created_at: string | null;
+}
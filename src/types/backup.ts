@@ -0,0 +1,84 @@
+export type BackupTargetKind = "google_drive" | "s3" | "s3_compatible" | "local";
+
+/** What backup archives are encrypted with. */
+export type BackupEncryption = "none" | "master_password" | "passphrase";
+
+export interface BackupEncryptionInput {
+  mode: BackupEncryption;
+  /** Required for `passphrase`, at least 8 characters. */
+  passphrase?: string | null;
+  /** Required for `master_password`. */
+  master_password?: string | null;
+}
+
+/**
+ * Grandfather-father-son retention: the newest backup of each of the last
+ * `keep_daily` days, `keep_weekly` weeks and `keep_monthly` months is kept.
+ */
+export interface RetentionPolicy {
+  keep_daily: number;
+  keep_weekly: number;
+  keep_monthly: number;
+}
+
+/** Only the fields of the chosen kind are used. */
+export interface BackupTargetInput {
+  name: string;
+  kind: BackupTargetKind;
+  is_enabled?: boolean;
+  /** Absolute path of a local folder or mounted network share. */
+  local_dir?: string | null;
+  s3_bucket?: string | null;
+  s3_region?: string | null;
+  /** Required for `s3_compatible`. */
+  s3_endpoint_url?: string | null;
+  s3_force_path_style?: boolean;
+  s3_access_key_id?: string | null;
+  /** Send `********` back unchanged to keep the stored key. */
+  s3_secret_access_key?: string | null;
+  /** `null` keeps every backup. */
+  retention?: RetentionPolicy | null;
+}
+
+export interface BackupRun {
+  id: string;
+  target_id: string;
+  target_name: string | null;
+  trigger: "scheduled" | "manual";
+  status: "success" | "failed";
+  file_name: string | null;
+  size_bytes: number | null;
+  /** Old backups deleted by the retention policy after this one. */
+  pruned_count: number | null;
+  error: string | null;
+  started_at: string;
+  finished_at: string;
+}
+
+export interface BackupTarget {
+  id: string;
+  name: string;
+  kind: BackupTargetKind;
+  is_enabled: boolean;
+  local_dir: string | null;
+  s3_bucket: string | null;
+  s3_region: string | null;
+  s3_endpoint_url: string | null;
+  s3_force_path_style: boolean;
+  s3_access_key_id: string | null;
+  /** Redacted as `********`. */
+  s3_secret_access_key: string | null;
+  retention: RetentionPolicy | null;
+  last_run: BackupRun | null;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+/** A backup archive stored on a target. */
+export interface RemoteBackup {
+  /** Object key, or the file id on Google Drive. */
+  id: string;
+  file_name: string;
+  size_bytes: number | null;
+  created_at: string | null;
+}
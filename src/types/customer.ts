@@ -45,3 +45,35 @@ export const createEmptyCustomerFormData = (): CustomerFormData => ({
   social_media_url: "",
   platform: "",
 });
+
+export type DuplicateReason = "phone" | "social_media_url" | "name";
+
+export interface CustomerMatch {
+  customer: Customer;
+  reasons: DuplicateReason[];
+  name_similarity: number;
+}
+
+export interface DuplicateCandidate {
+  /** Older record, suggested as the merge survivor. */
+  customer: Customer;
+  duplicate: Customer;
+  reasons: DuplicateReason[];
+  name_similarity: number;
+}
+
+export interface CustomerMergeResult {
+  merge_id: string;
+  survivor: Customer;
+  moved_order_count: number;
+}
+
+export interface CustomerMergeRecord {
+  id: string;
+  survivor_id: string;
+  merged_id: string;
+  survivor_snapshot: Customer;
+  merged_snapshot: Customer;
+  moved_order_ids: string[];
+  created_at?: string | null;
+}
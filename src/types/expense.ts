@@ -37,3 +37,82 @@ export const createEmptyExpenseFormData = (): ExpenseFormData => ({
   payment_method: "cash",
   notes: "",
 });
+
+export interface ExpenseCategory {
+  id: string;
+  name: string;
+  parent_id?: string | null;
+  monthly_budget?: number | null;
+  /** Live expenses filed under this category, not counting subcategories. */
+  expense_count: number;
+  created_at?: string | null;
+  updated_at?: string | null;
+}
+
+export interface PaymentMethod {
+  id: string;
+  name: string;
+  expense_count: number;
+  created_at?: string | null;
+  updated_at?: string | null;
+}
+
+/** One category in one month; `category_id` is null for uncategorized spend. */
+export interface BudgetReportRow {
+  month: string;
+  category_id: string | null;
+  category_name: string | null;
+  parent_id: string | null;
+  monthly_budget: number | null;
+  spent: number;
+  spent_with_children: number;
+  remaining: number | null;
+  over_budget: boolean;
+}
+
+export type RecurringFrequency = "monthly" | "weekly";
+
+export interface RecurringExpenseInput {
+  title: string;
+  amount: number;
+  category?: string | null;
+  payment_method?: string | null;
+  notes?: string | null;
+  frequency: RecurringFrequency;
+  /** 1-31 for monthly; shorter months use their last day. */
+  day_of_month?: number | null;
+  /** 0 (Sunday) to 6 (Saturday) for weekly. */
+  day_of_week?: number | null;
+  /** `YYYY-MM-DD`; defaults to today. */
+  start_date?: string | null;
+  end_date?: string | null;
+  is_active?: boolean;
+}
+
+export interface RecurringExpense {
+  id: string;
+  title: string;
+  amount: number;
+  category: string | null;
+  payment_method: string | null;
+  notes: string | null;
+  frequency: RecurringFrequency;
+  day_of_month: number | null;
+  day_of_week: number | null;
+  start_date: string;
+  end_date: string | null;
+  is_active: boolean;
+  last_generated_date: string | null;
+  next_due_date: string | null;
+  created_at?: string | null;
+  updated_at?: string | null;
+}
+
+export interface UpcomingRecurringExpense {
+  recurring_id: string;
+  title: string;
+  amount: number;
+  category: string | null;
+  payment_method: string | null;
+  due_date: string;
+}
@@ -0,0 +1,25 @@
+export type ScheduledJob = "backup" | "recurring_expenses";
+
+export type JobTrigger = "scheduled" | "catch_up";
+
+/** One run of a scheduled job. */
+export interface JobRun {
+  id: string;
+  job: ScheduledJob;
+  trigger: JobTrigger;
+  status: "running" | "success" | "failed";
+  error: string | null;
+  started_at: string;
+  finished_at: string | null;
+}
+
+/** Emitted when a missed run is made up for on startup. */
+export const JOB_CATCH_UP_EVENT = "jobs://catch-up";
+/** Emitted with the `JobRun` when a scheduled job finishes. */
+export const JOB_COMPLETED_EVENT = "jobs://completed";
+
+export interface JobCatchUpEvent {
+  job: ScheduledJob;
+  /** The scheduled time that was missed. */
+  missed_at: string;
+}
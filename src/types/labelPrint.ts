@@ -30,3 +30,9 @@ export interface ParcelPrintLabel {
 export interface ParcelPrintQueueItem extends ParcelPrintLabel {
   copies: number;
 }
+
+export interface LabelSheetResult {
+  path: string;
+  label_count: number;
+  page_count: number;
+}
@@ -48,6 +48,8 @@ export interface OrderItem {
 }
 
 export interface OrderItemPayload {
+  /** Existing item id when editing, so its photos stay with it. */
+  id?: string;
   product_url?: string;
   product_qty?: number;
   price?: number;
@@ -55,6 +57,7 @@ export interface OrderItemPayload {
 }
 
 export interface OrderFormItemData {
+  id?: string;
   product_url: string;
   product_qty: number;
   price: number;
@@ -147,6 +150,48 @@ export interface OrderDetail {
   items: OrderItem[];
 }
 
+export type OrderDateField =
+  | "order_date"
+  | "arrived_date"
+  | "shipment_date"
+  | "user_withdraw_date"
+  | "created_at";
+
+export interface DateRangeCondition {
+  field: OrderDateField;
+  /** Inclusive, e.g. "2026-10-01". */
+  from?: string | null;
+  to?: string | null;
+}
+
+export interface NumberRange {
+  min?: number | null;
+  max?: number | null;
+}
+
+export interface OrderFeeFlags {
+  shipping_fee?: boolean | null;
+  delivery_fee?: boolean | null;
+  cargo_fee?: boolean | null;
+  /** Not allowed in `by_shop`. */
+  service_fee?: boolean | null;
+}
+
+/** Structured order filter; every set condition must match. */
+export interface OrderConditions {
+  statuses?: OrderStatus[] | null;
+  order_from?: string[] | null;
+  cities?: string[] | null;
+  customer_ids?: string[] | null;
+  date_ranges?: DateRangeCondition[] | null;
+  paid?: OrderFeeFlags | null;
+  by_shop?: OrderFeeFlags | null;
+  exclude_cargo_fee?: boolean | null;
+  total_price?: NumberRange | null;
+  total_weight?: NumberRange | null;
+  has_items?: boolean | null;
+}
+
 export interface PaginatedOrders {
   orders: OrderWithCustomer[];
   total: number;
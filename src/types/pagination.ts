@@ -0,0 +1,17 @@
+/** One page of a cursor-paginated list. */
+export interface CursorPage<T> {
+  items: T[];
+  /** Pass back as `cursor` to load the following page; null on the last page. */
+  next_cursor: string | null;
+  /** Pass back as `cursor` to load the preceding page; null on the first page. */
+  prev_cursor: string | null;
+  /** Only counted when `includeTotal` is set. */
+  total: number | null;
+}
+
+export interface CursorPageParams<TFilter> {
+  filter?: TFilter | null;
+  cursor?: string | null;
+  limit?: number;
+  includeTotal?: boolean;
+}
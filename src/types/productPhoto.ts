@@ -0,0 +1,20 @@
+export type PhotoSource = "file" | "clipboard" | "url";
+
+export interface OrderItemPhoto {
+  id: string;
+  order_item_id: string;
+  /** SHA-256 of the image bytes; identical images share one file. */
+  content_hash: string;
+  content_type: string;
+  size_bytes: number;
+  width: number | null;
+  height: number | null;
+  source: PhotoSource;
+  /** Where the image was downloaded from, for `url` photos. */
+  source_url: string | null;
+  /** Image in app data; load it with `convertFileSrc`. */
+  local_path: string;
+  thumbnail_path: string | null;
+  cloud_url: string | null;
+  created_at: string | null;
+}
@@ -0,0 +1,34 @@
+import { OrderStatus } from "./order";
+
+export type ScanOutcome =
+  | "updated"
+  | "unchanged"
+  | "skipped"
+  | "duplicate"
+  | "unknown";
+
+export interface ScannedOrder {
+  id: string;
+  order_id: string | null;
+  customer_name: string | null;
+  status: OrderStatus | null;
+}
+
+export interface ScanResult {
+  code: string;
+  outcome: ScanOutcome;
+  previous_status: OrderStatus | null;
+  order: ScannedOrder | null;
+}
+
+export interface ScanSessionReport {
+  session_id: string;
+  started_at: string;
+  target_status: OrderStatus | null;
+  expected_count: number;
+  scanned: ScannedOrder[];
+  missing: ScannedOrder[];
+  unexpected: ScannedOrder[];
+  unknown_codes: string[];
+  last_result: ScanResult | null;
+}
@@ -1,6 +1,16 @@
+import { BackupEncryption } from "./backup";
+import { OrderStatus } from "./order";
+
 export type ThemeMode = "light" | "dark";
 export type AccentColor = "blue" | "purple" | "pink" | "cyan" | "green";
 export type FontSize = "small" | "normal" | "large" | "extra-large";
+export type LabelSheetSize =
+  | "a4_21"
+  | "a4_14"
+  | "a4_8"
+  | "letter_10"
+  | "letter_6"
+  | "label_4x6";
 
 export interface AppSettings {
   language: string;
@@ -13,15 +23,51 @@ export interface AppSettings {
   exchange_currency_symbol: string;
   invoice_printer_name: string;
   silent_invoice_print: boolean;
+  receipt_printer_transport: "cups" | "device" | "network";
+  receipt_printer_target: string;
+  receipt_paper_width: "58mm" | "80mm";
+  scan_target_status: OrderStatus | "";
+  scan_date_field: "arrived_date" | "shipment_date" | "user_withdraw_date" | "";
+  label_sheet_size: LabelSheetSize;
+  label_font_path: string;
+  default_phone_country: string;
   auto_backup: boolean;
+  /** `never`, `daily`, `weekly`, `monthly` or `custom`. */
   backup_frequency: string;
+  /** One or more local times as `HH:MM`, separated by commas. */
   backup_time: string;
+  /** Day of a weekly backup, 0 (Sunday) to 6. */
+  backup_weekday: number;
+  /** Day of a monthly backup, 1 to 28. */
+  backup_month_day: number;
+  /** Cron expression for the `custom` frequency, with or without seconds. */
+  backup_cron: string;
   font_size: FontSize;
   aws_access_key_id: string;
+  /** Shown as `********` once saved; send it back unchanged to keep it. */
   aws_secret_access_key: string;
   aws_region: string;
   aws_bucket_name: string;
+  /** CDN URL uploaded files are served from, for any storage provider. */
   imagekit_base_url: string;
+  storage_provider: StorageProvider;
+  /** Endpoint of an S3-compatible service, e.g. `http://localhost:9000`. */
+  s3_endpoint_url: string;
+  s3_force_path_style: boolean;
+  /** Folder used as storage by the `local` provider. */
+  local_storage_dir: string;
+  /** Changed only through `setBackupEncryption`. */
+  backup_encryption: BackupEncryption;
+  /** `********` when set. */
+  backup_passphrase: string;
+}
+
+export type StorageProvider = "s3" | "s3_compatible" | "local";
+
+export interface StoredObject {
+  key: string;
+  size_bytes: number;
+  last_modified: string | null;
 }
 
 export interface AppSettingsLanguage {
@@ -38,6 +84,9 @@ export interface AwsS3Config {
   secret_access_key: string;
   region: string;
   bucket_name: string;
+  /** Set for S3-compatible services; leave empty for AWS. */
+  endpoint_url?: string;
+  force_path_style?: boolean;
 }
 
 export interface DriveConnectionStatus {
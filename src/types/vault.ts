@@ -0,0 +1,8 @@
+/** What encrypts the stored credentials. */
+export type VaultMode = "machine_key" | "master_password";
+
+export interface VaultStatus {
+  mode: VaultMode;
+  /** False until a master-password vault is unlocked this session. */
+  unlocked: boolean;
+}